@@ -0,0 +1,197 @@
+//! Adaptive quizzes, which pick each question to suit how the student
+//! is doing so far.
+//!
+//! The quiz aims for a difficulty, stepping it up after a run of
+//! correct answers and down after every miss, and asks whichever unasked
+//! question in the pool is closest to it. Alongside this it keeps an
+//! estimate of the student's level on the same scale as difficulty,
+//! which moves less with each answer. The quiz stops once that estimate
+//! has settled, or after [`ADAPTIVE_MAX_ITEMS`] questions.
+
+use crate::{
+    attempts::question_order,
+    questions::{MAX_DIFFICULTY, MIN_DIFFICULTY},
+};
+use serde::{Deserialize, Serialize};
+
+/// The most questions asked in one adaptive quiz.
+pub const ADAPTIVE_MAX_ITEMS: usize = 15;
+/// The fewest questions asked before the estimate can be trusted.
+const MIN_ITEMS: usize = 5;
+/// Correct answers in a row needed to step the difficulty up.
+const STREAK_TO_STEP_UP: u32 = 2;
+/// The estimate has settled once none of the last few answers moved
+/// it by more than this.
+const SETTLED_WITHIN: f64 = 0.15;
+const SETTLING_ANSWERS: usize = 3;
+
+/// How well a student has mastered a topic, as worked out at the end of
+/// an adaptive quiz.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MasteryEstimate {
+    /// On the same scale as question difficulty.
+    pub level: f64,
+    pub questions: u32,
+    /// Whether the estimate settled before the quiz ran out of
+    /// questions.
+    pub settled: bool,
+}
+
+/// The state of an adaptive quiz after some number of answers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdaptiveRun {
+    /// The difficulty to aim for with the next question.
+    pub target: u8,
+    streak: u32,
+    level: f64,
+    /// How far each answer moved the estimate.
+    changes: Vec<f64>,
+}
+
+impl Default for AdaptiveRun {
+    fn default() -> Self {
+        let middle = (MIN_DIFFICULTY + MAX_DIFFICULTY) / 2;
+        AdaptiveRun {
+            target: middle,
+            streak: 0,
+            level: middle as f64,
+            changes: vec![],
+        }
+    }
+}
+
+impl AdaptiveRun {
+    /// Replays a quiz from the difficulty of each question asked and
+    /// whether it was answered correctly.
+    pub fn replay(answers: impl IntoIterator<Item = (u8, bool)>) -> Self {
+        let mut run = AdaptiveRun::default();
+        for (difficulty, correct) in answers {
+            run.record(difficulty, correct);
+        }
+        run
+    }
+
+    pub fn record(&mut self, difficulty: u8, correct: bool) {
+        if correct {
+            self.streak += 1;
+            if self.streak >= STREAK_TO_STEP_UP {
+                self.target = (self.target + 1).min(MAX_DIFFICULTY);
+                self.streak = 0;
+            }
+        } else {
+            self.target = self.target.saturating_sub(1).max(MIN_DIFFICULTY);
+            self.streak = 0;
+        }
+
+        // The estimate moves towards the answer by how surprising it
+        // was, and by less with every question asked.
+        let expected = 1.0 / (1.0 + (difficulty as f64 - self.level).exp());
+        let outcome = if correct { 1.0 } else { 0.0 };
+        let weight = 2.0 / (self.changes.len() as f64 + 2.0);
+        let level = (self.level + weight * (outcome - expected))
+            .clamp(MIN_DIFFICULTY as f64, MAX_DIFFICULTY as f64);
+        self.changes.push((level - self.level).abs());
+        self.level = level;
+    }
+
+    pub fn settled(&self) -> bool {
+        self.changes.len() >= MIN_ITEMS
+            && self
+                .changes
+                .iter()
+                .rev()
+                .take(SETTLING_ANSWERS)
+                .all(|change| *change < SETTLED_WITHIN)
+    }
+
+    /// Whether the quiz should stop, given how many questions are in
+    /// its pool.
+    pub fn finished(&self, pool: usize) -> bool {
+        let asked = self.changes.len();
+        self.settled() || asked >= ADAPTIVE_MAX_ITEMS || asked >= pool
+    }
+
+    pub fn estimate(&self) -> MasteryEstimate {
+        MasteryEstimate {
+            level: self.level,
+            questions: self.changes.len() as u32,
+            settled: self.settled(),
+        }
+    }
+}
+
+/// The questions asked so far in an adaptive quiz, as the index in the
+/// pool of each in turn, followed by the next one to ask unless the quiz
+/// is over. `answered` gives whether the question shown at a position
+/// was answered correctly, or `None` if it hasn't been answered yet.
+/// Questions of the same difficulty are asked in an order shuffled by
+/// `seed`.
+pub fn adaptive_order(
+    difficulties: &[u8],
+    seed: u64,
+    mut answered: impl FnMut(usize, usize) -> Option<bool>,
+) -> Vec<usize> {
+    let preference = question_order(difficulties.len(), true, seed);
+    let mut run = AdaptiveRun::default();
+    let mut order = vec![];
+    while !run.finished(difficulties.len()) {
+        let Some(next) = preference
+            .iter()
+            .copied()
+            .filter(|index| !order.contains(index))
+            .min_by_key(|&index| difficulties[index].abs_diff(run.target))
+        else {
+            break;
+        };
+        order.push(next);
+        match answered(order.len() - 1, next) {
+            Some(correct) => run.record(difficulties[next], correct),
+            None => break,
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn difficulty_follows_answers() {
+        let difficulties = [1, 1, 1, 2, 2, 2, 3, 3, 3, 4, 4, 4, 5, 5, 5];
+        let asked = |results: &[bool]| {
+            adaptive_order(&difficulties, 3, |shown, _| results.get(shown).copied())
+                .into_iter()
+                .map(|index| difficulties[index])
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(asked(&[]), vec![3]);
+        assert_eq!(asked(&[true, true]), vec![3, 3, 4]);
+        assert_eq!(asked(&[true, true, false]), vec![3, 3, 4, 3]);
+        assert_eq!(asked(&[false, false]), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn quizzes_stop() {
+        // Getting every middling question right settles well above the
+        // middle, without needing every question.
+        let difficulties = [3; 40];
+        let everything_right = adaptive_order(&difficulties, 0, |_, _| Some(true));
+        assert!(everything_right.len() < ADAPTIVE_MAX_ITEMS);
+        let run = AdaptiveRun::replay(everything_right.iter().map(|_| (3, true)));
+        assert!(run.estimate().settled && run.estimate().level > 4.0);
+
+        let pool = adaptive_order(&difficulties[..4], 0, |_, _| Some(true));
+        assert_eq!(pool.len(), 4);
+
+        // Getting questions at one level right and the next level up
+        // wrong settles on somewhere between the two.
+        let mut run = AdaptiveRun::default();
+        while !run.finished(40) {
+            run.record(run.target, run.target <= 3);
+        }
+        let estimate = run.estimate();
+        assert!(estimate.settled);
+        assert!((3.0..4.0).contains(&estimate.level), "{estimate:?}");
+    }
+}
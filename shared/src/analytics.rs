@@ -0,0 +1,492 @@
+//! Summaries worked out from saved [`QuizReview`]s, for the charts and
+//! reports shown to students and teachers.
+
+use crate::responses::{Confidence, QuestionSnapshot, QuizReview};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How many questions are listed as the most missed.
+pub const MOST_MISSED: usize = 5;
+
+/// A student's progress across every quiz they have finished.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProgressReport {
+    /// One point per attempt, oldest first.
+    pub history: Vec<ScorePoint>,
+    /// Marks earned out of marks available for each set, by set name.
+    pub set_accuracy: Vec<Accuracy>,
+    /// The questions answered wrongly most often, worst first.
+    pub most_missed: Vec<MissedQuestion>,
+    pub questions_attempted: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScorePoint {
+    pub finished: DateTime<Utc>,
+    pub set_name: String,
+    pub awarded: u32,
+    pub available: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Accuracy {
+    pub label: String,
+    pub awarded: u32,
+    pub available: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MissedQuestion {
+    pub set_name: String,
+    pub question: QuestionSnapshot,
+    pub attempts: u32,
+    pub misses: u32,
+}
+
+impl ScorePoint {
+    pub fn percent(&self) -> f64 {
+        percent(self.awarded, self.available)
+    }
+}
+
+impl Accuracy {
+    pub fn percent(&self) -> f64 {
+        percent(self.awarded, self.available)
+    }
+}
+
+fn percent(awarded: u32, available: u32) -> f64 {
+    match available {
+        0 => 0.0,
+        _ => 100.0 * awarded as f64 / available as f64,
+    }
+}
+
+fn set_label(review: &QuizReview) -> String {
+    match review.set_name.as_str() {
+        "" => String::from("Unknown set"),
+        name => name.to_owned(),
+    }
+}
+
+impl ProgressReport {
+    pub fn from_reviews(reviews: &[QuizReview]) -> Self {
+        let mut reviews = reviews.iter().collect::<Vec<_>>();
+        reviews.sort_by_key(|r| r.finished);
+
+        let history = reviews
+            .iter()
+            .map(|review| {
+                let (awarded, available) = review.score();
+                ScorePoint {
+                    finished: review.finished,
+                    set_name: set_label(review),
+                    awarded,
+                    available,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut set_accuracy: Vec<Accuracy> = vec![];
+        for point in &history {
+            match set_accuracy.iter_mut().find(|a| a.label == point.set_name) {
+                Some(acc) => {
+                    acc.awarded += point.awarded;
+                    acc.available += point.available;
+                }
+                None => set_accuracy.push(Accuracy {
+                    label: point.set_name.clone(),
+                    awarded: point.awarded,
+                    available: point.available,
+                }),
+            }
+        }
+        set_accuracy.sort_by(|a, b| a.label.cmp(&b.label));
+
+        // Questions are told apart by their set, position and text, so an
+        // edited question isn't counted together with the old one.
+        let mut missed: Vec<((String, usize, QuestionSnapshot), MissedQuestion)> = vec![];
+        let mut questions_attempted = 0;
+        for review in &reviews {
+            for response in &review.responses {
+                questions_attempted += 1;
+                let key = (
+                    set_label(review),
+                    response.question().index,
+                    response.snapshot().clone(),
+                );
+                let wrong = !response.is_correct() as u32;
+                match missed.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, q)) => {
+                        q.attempts += 1;
+                        q.misses += wrong;
+                    }
+                    None => missed.push((
+                        key,
+                        MissedQuestion {
+                            set_name: set_label(review),
+                            question: response.snapshot().clone(),
+                            attempts: 1,
+                            misses: wrong,
+                        },
+                    )),
+                }
+            }
+        }
+        let mut most_missed = missed
+            .into_iter()
+            .map(|(_, q)| q)
+            .filter(|q| q.misses > 0)
+            .collect::<Vec<_>>();
+        most_missed.sort_by(|a, b| b.misses.cmp(&a.misses).then(a.attempts.cmp(&b.attempts)));
+        most_missed.truncate(MOST_MISSED);
+
+        ProgressReport {
+            history,
+            set_accuracy,
+            most_missed,
+            questions_attempted,
+        }
+    }
+}
+
+/// How many percentage points a topic's expected accuracy has to be
+/// above its real accuracy for the student to count as overconfident.
+pub const OVERCONFIDENT_BY: f64 = 20.0;
+/// The fewest rated answers on a topic before it is judged.
+pub const MIN_RATED_ANSWERS: u32 = 3;
+
+/// How well a student's confidence in their answers matches how often
+/// they turn out to be right.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationReport {
+    /// One entry for each confidence rating, least sure first.
+    pub levels: Vec<Calibration>,
+    /// Topics where the student is right much less often than they
+    /// expect to be, worst first.
+    pub overconfident: Vec<Calibration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Calibration {
+    pub label: String,
+    pub answers: u32,
+    pub correct: u32,
+    /// The expected accuracy of every answer, added up.
+    pub expected: f64,
+}
+
+/// An answer the student rated their confidence in, along with the
+/// topics of its question.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RatedAnswer {
+    pub confidence: Confidence,
+    pub correct: bool,
+    pub topics: Vec<String>,
+}
+
+impl Calibration {
+    fn new(label: String) -> Self {
+        Calibration {
+            label,
+            answers: 0,
+            correct: 0,
+            expected: 0.0,
+        }
+    }
+
+    fn add(&mut self, answer: &RatedAnswer) {
+        self.answers += 1;
+        self.correct += answer.correct as u32;
+        self.expected += answer.confidence.expected_accuracy();
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        percent(self.correct, self.answers)
+    }
+
+    pub fn expected_accuracy(&self) -> f64 {
+        match self.answers {
+            0 => 0.0,
+            n => 100.0 * self.expected / n as f64,
+        }
+    }
+
+    /// How many percentage points more often the student expected to be
+    /// right than they were.
+    pub fn overconfidence(&self) -> f64 {
+        self.expected_accuracy() - self.accuracy()
+    }
+}
+
+impl CalibrationReport {
+    pub fn from_answers(answers: &[RatedAnswer]) -> Self {
+        let levels = Confidence::all()
+            .into_iter()
+            .map(|confidence| {
+                let mut level = Calibration::new(confidence.name().to_owned());
+                for answer in answers.iter().filter(|a| a.confidence == confidence) {
+                    level.add(answer);
+                }
+                level
+            })
+            .collect();
+
+        let mut topics: Vec<Calibration> = vec![];
+        for answer in answers {
+            for topic in &answer.topics {
+                match topics.iter_mut().find(|t| &t.label == topic) {
+                    Some(calibration) => calibration.add(answer),
+                    None => {
+                        let mut calibration = Calibration::new(topic.clone());
+                        calibration.add(answer);
+                        topics.push(calibration);
+                    }
+                }
+            }
+        }
+        let mut overconfident = topics
+            .into_iter()
+            .filter(|t| t.answers >= MIN_RATED_ANSWERS && t.overconfidence() >= OVERCONFIDENT_BY)
+            .collect::<Vec<_>>();
+        overconfident.sort_by(|a, b| b.overconfidence().total_cmp(&a.overconfidence()));
+
+        CalibrationReport {
+            levels,
+            overconfident,
+        }
+    }
+}
+
+/// How a class did on each question of one set, worked out by the
+/// backend from every saved attempt at it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ItemAnalysis {
+    pub set_name: String,
+    /// How many attempts at the set there were.
+    pub attempts: u32,
+    pub items: Vec<ItemStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemStats {
+    /// Where the question is in the set, counting from zero.
+    pub index: usize,
+    pub question: QuestionSnapshot,
+    pub attempts: u32,
+    /// The percentage of attempts that got the question right.
+    pub facility: f64,
+    /// The average time spent on the question, in seconds.
+    pub average_time: f64,
+    /// How much more often the top scoring 27% of attempts got this
+    /// right than the bottom 27%, from -1 to 1. Questions with a low
+    /// or negative value don't tell strong and weak students apart.
+    /// There is no value until both groups have an attempt.
+    pub discrimination: Option<f64>,
+    /// The wrong answers given most often, most common first.
+    pub wrong_answers: Vec<WrongAnswer>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WrongAnswer {
+    pub answer: String,
+    pub count: u32,
+}
+
+/// The share of attempts at either end of the score range that are
+/// compared to work out the discrimination index.
+pub const DISCRIMINATION_GROUP: f64 = 0.27;
+
+/// How many of the most common wrong answers are reported.
+pub const WRONG_ANSWERS: usize = 3;
+
+impl ItemAnalysis {
+    /// The report as CSV, with one row per question.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "Question,Title,Markup,Attempts,Facility (%),Average Time (s),Discrimination,Common Wrong Answers\n",
+        );
+        for item in &self.items {
+            let discrimination = item
+                .discrimination
+                .map(|d| format!("{d:.2}"))
+                .unwrap_or_default();
+            let wrong = item
+                .wrong_answers
+                .iter()
+                .map(|w| format!("{} ({})", w.answer, w.count))
+                .collect::<Vec<_>>()
+                .join("; ");
+            let row = [
+                csv_field(&(item.index + 1).to_string()),
+                csv_text(&item.question.title),
+                csv_text(&item.question.markup),
+                csv_field(&item.attempts.to_string()),
+                csv_field(&format!("{:.1}", item.facility)),
+                csv_field(&format!("{:.1}", item.average_time)),
+                csv_field(&discrimination),
+                csv_text(&wrong),
+            ];
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Quotes a field if it has anything in it that CSV treats specially.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Quotes text typed in by teachers or students, like [`csv_field`],
+/// and puts a `'` in front of anything a spreadsheet would otherwise run
+/// as a formula.
+fn csv_text(text: &str) -> String {
+    match text.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        true => format!("\"'{}\"", text.replace('"', "\"\"")),
+        false => csv_field(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        questions::Question,
+        responses::{QuestionRef, Response},
+    };
+    use chrono::Duration;
+
+    fn review(set_name: &str, days_ago: i64, answers: &[&str]) -> QuizReview {
+        let questions = [
+            Question::new("Q1".into(), "1+1".into(), true, "2".into()),
+            Question::new("Q2".into(), "2+2".into(), true, "4".into()),
+        ];
+        let responses = questions
+            .iter()
+            .zip(answers)
+            .enumerate()
+            .map(|(i, (q, a))| Response::new(QuestionRef::new(1, i), q, vec![a.to_string()]))
+            .collect();
+        let mut review = QuizReview::new("student".into(), set_name.into(), Utc::now(), responses);
+        review.finished -= Duration::days(days_ago);
+        review
+    }
+
+    #[test]
+    fn progress_summarises_reviews() {
+        let reviews = vec![
+            review("Adding", 0, &["2", "4"]),
+            review("Adding", 2, &["2", "5"]),
+            review("Other", 1, &["3", "5"]),
+        ];
+        let report = ProgressReport::from_reviews(&reviews);
+
+        assert_eq!(report.questions_attempted, 6);
+        let scores = report.history.iter().map(|p| p.awarded).collect::<Vec<_>>();
+        assert_eq!(scores, vec![1, 0, 2]);
+
+        assert_eq!(report.set_accuracy[0].label, "Adding");
+        assert_eq!(report.set_accuracy[0].percent(), 75.0);
+        assert_eq!(report.set_accuracy[1].percent(), 0.0);
+
+        // Missed once out of one attempt comes before once out of two.
+        assert_eq!(report.most_missed.len(), 3);
+        assert_eq!(report.most_missed[0].set_name, "Other");
+        assert_eq!(report.most_missed[2].set_name, "Adding");
+        assert_eq!(report.most_missed[2].question.title, "Q2");
+        assert_eq!(report.most_missed[2].attempts, 2);
+    }
+
+    #[test]
+    fn calibration_finds_overconfident_topics() {
+        let answer = |confidence, correct, topic: &str| RatedAnswer {
+            confidence,
+            correct,
+            topics: vec![topic.to_owned()],
+        };
+        let answers = [
+            answer(Confidence::Sure, false, "Algebra"),
+            answer(Confidence::Sure, false, "Algebra"),
+            answer(Confidence::Sure, true, "Algebra"),
+            answer(Confidence::Sure, true, "Number"),
+            answer(Confidence::Sure, true, "Number"),
+            answer(Confidence::Guess, false, "Number"),
+        ];
+        let report = CalibrationReport::from_answers(&answers);
+
+        let sure = &report.levels[2];
+        assert_eq!(
+            (sure.label.as_str(), sure.answers, sure.correct),
+            ("Sure", 5, 3)
+        );
+        assert_eq!(sure.accuracy(), 60.0);
+        assert_eq!(report.levels[1].answers, 0);
+
+        assert_eq!(report.overconfident.len(), 1);
+        assert_eq!(report.overconfident[0].label, "Algebra");
+    }
+
+    #[test]
+    fn csv_quotes_awkward_fields() {
+        let analysis = ItemAnalysis {
+            set_name: "Set".into(),
+            attempts: 2,
+            items: vec![ItemStats {
+                index: 0,
+                question: QuestionSnapshot {
+                    title: "Solve, \"carefully\"".into(),
+                    markup: "x+1=2".into(),
+                },
+                attempts: 2,
+                facility: 50.0,
+                average_time: 12.0,
+                discrimination: None,
+                wrong_answers: vec![WrongAnswer {
+                    answer: "x=3".into(),
+                    count: 1,
+                }],
+            }],
+        };
+        let csv = analysis.to_csv();
+        let row = csv.lines().nth(1).unwrap();
+        assert_eq!(
+            row,
+            "1,\"Solve, \"\"carefully\"\"\",x+1=2,2,50.0,12.0,,x=3 (1)"
+        );
+    }
+
+    #[test]
+    fn csv_defuses_formulas() {
+        let analysis = ItemAnalysis {
+            set_name: "Set".into(),
+            attempts: 1,
+            items: vec![ItemStats {
+                index: 0,
+                question: QuestionSnapshot {
+                    title: "@SUM(A1)".into(),
+                    markup: "x-1=2".into(),
+                },
+                attempts: 1,
+                facility: 0.0,
+                average_time: 5.0,
+                discrimination: Some(-0.5),
+                wrong_answers: vec![WrongAnswer {
+                    answer: "=HYPERLINK(\"x\")".into(),
+                    count: 1,
+                }],
+            }],
+        };
+        let csv = analysis.to_csv();
+        let row = csv.lines().nth(1).unwrap();
+        assert_eq!(
+            row,
+            "1,\"'@SUM(A1)\",x-1=2,1,0.0,5.0,-0.50,\"'=HYPERLINK(\"\"x\"\") (1)\""
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Exam access arrangements, which change how a student sits a quiz.
+
+use crate::timing::TimeLimit;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
+
+/// The arrangements a student has been granted. Every account starts
+/// with none of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessArrangements {
+    /// Extra time as a percentage of any time limit, such as 25 for
+    /// 25% extra time.
+    #[serde(default)]
+    pub extra_time: u32,
+    /// The student may have questions read aloud to them.
+    #[serde(default)]
+    pub reader: bool,
+    /// The student may pause a timed quiz for a rest break, which
+    /// stops the clock.
+    #[serde(default)]
+    pub rest_breaks: bool,
+    #[serde(default)]
+    pub calculator: CalculatorOverride,
+}
+
+/// Whether a student can use a calculator, regardless of what each
+/// question says.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CalculatorOverride {
+    /// Follow each question.
+    #[default]
+    AsSet,
+    Always,
+    Never,
+}
+
+impl AccessArrangements {
+    /// The extra time percentages that can be granted.
+    pub const EXTRA_TIME_OPTIONS: [u32; 5] = [0, 10, 25, 50, 100];
+
+    pub fn is_empty(&self) -> bool {
+        self == &AccessArrangements::default()
+    }
+
+    /// Lengthens a time limit by the student's extra time, rounding up
+    /// to the next second.
+    pub fn extend(&self, limit: TimeLimit) -> TimeLimit {
+        let seconds = (limit.seconds() * (100 + self.extra_time)).div_ceil(100);
+        match limit {
+            TimeLimit::Overall { .. } => TimeLimit::Overall { seconds },
+            TimeLimit::PerQuestion { .. } => TimeLimit::PerQuestion { seconds },
+        }
+    }
+
+    /// Whether the student can use a calculator on a question which
+    /// does or doesn't allow one.
+    pub fn calculator_allowed(&self, question_allows: bool) -> bool {
+        match self.calculator {
+            CalculatorOverride::AsSet => question_allows,
+            CalculatorOverride::Always => true,
+            CalculatorOverride::Never => false,
+        }
+    }
+
+    /// A short description of each arrangement in effect, for reports.
+    pub fn notes(&self) -> Vec<String> {
+        let mut notes = vec![];
+        if self.extra_time > 0 {
+            notes.push(format!("{}% extra time", self.extra_time));
+        }
+        if self.reader {
+            notes.push(String::from("Reader"));
+        }
+        if self.rest_breaks {
+            notes.push(String::from("Rest breaks"));
+        }
+        match self.calculator {
+            CalculatorOverride::AsSet => {}
+            CalculatorOverride::Always => notes.push(String::from("Calculator always allowed")),
+            CalculatorOverride::Never => notes.push(String::from("No calculator")),
+        }
+        notes
+    }
+}
+
+impl CalculatorOverride {
+    pub fn all() -> Vec<CalculatorOverride> {
+        vec![
+            CalculatorOverride::AsSet,
+            CalculatorOverride::Always,
+            CalculatorOverride::Never,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CalculatorOverride::AsSet => "As each question says",
+            CalculatorOverride::Always => "Always allowed",
+            CalculatorOverride::Never => "Never allowed",
+        }
+    }
+}
+
+impl Display for CalculatorOverride {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for CalculatorOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CalculatorOverride::all()
+            .into_iter()
+            .find(|c| c.name() == s)
+            .ok_or(format!("unknown calculator setting: {s}"))
+    }
+}
+
+/// Everything that changes how the logged in student sits a quiz.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuizConditions {
+    /// The time limit with any extra time already added.
+    pub time_limit: Option<TimeLimit>,
+    pub arrangements: AccessArrangements,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extra_time_and_calculator() {
+        let arrangements = AccessArrangements {
+            extra_time: 25,
+            calculator: CalculatorOverride::Always,
+            ..Default::default()
+        };
+        assert_eq!(
+            arrangements.extend(TimeLimit::Overall { seconds: 600 }),
+            TimeLimit::Overall { seconds: 750 }
+        );
+        assert_eq!(
+            arrangements.extend(TimeLimit::PerQuestion { seconds: 30 }),
+            TimeLimit::PerQuestion { seconds: 38 }
+        );
+        assert!(arrangements.calculator_allowed(false));
+        assert_eq!(
+            arrangements.notes(),
+            vec!["25% extra time", "Calculator always allowed"]
+        );
+        assert!(AccessArrangements::default().is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+//! Homework: a question set given to a class to finish between two dates.
+
+use crate::{responses::QuizReview, timing::TimeLimit};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Assignment {
+    /// A unique ID, which is given out by the backend when the
+    /// assignment is saved.
+    #[serde(default)]
+    id: String,
+    set_name: String,
+    class_name: String,
+    set_by: String,
+    /// Students can't start the set before this.
+    opens: DateTime<Utc>,
+    /// Attempts finished after this are still accepted, but are late.
+    due: DateTime<Utc>,
+    /// How many times each student can submit it. Zero means there is
+    /// no limit.
+    #[serde(default)]
+    attempt_limit: u32,
+    /// Overrides the set's own time limit, if given.
+    #[serde(default)]
+    time_limit: Option<TimeLimit>,
+}
+
+/// Where a student is up to with an assignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AssignmentStatus {
+    /// It hasn't opened yet.
+    Upcoming,
+    /// It is open and hasn't been done yet.
+    Outstanding,
+    /// The due date has passed and it hasn't been done.
+    Overdue,
+    Completed,
+    CompletedLate,
+}
+
+/// An assignment as one student sees it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StudentAssignment {
+    pub assignment: Assignment,
+    pub status: AssignmentStatus,
+    pub attempts_used: u32,
+}
+
+/// How every student in the class has got on with an assignment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompletionGrid {
+    pub assignment: Assignment,
+    pub rows: Vec<CompletionRow>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompletionRow {
+    pub username: String,
+    pub attempts: u32,
+    /// The marks awarded and available on their best attempt.
+    pub best_score: Option<(u32, u32)>,
+    /// When their first attempt was finished.
+    pub first_finished: Option<DateTime<Utc>>,
+    pub late: bool,
+}
+
+impl Assignment {
+    pub fn new(
+        set_name: String,
+        class_name: String,
+        set_by: String,
+        opens: DateTime<Utc>,
+        due: DateTime<Utc>,
+        attempt_limit: u32,
+    ) -> Self {
+        Assignment {
+            id: String::new(),
+            set_name,
+            class_name,
+            set_by,
+            opens,
+            due,
+            attempt_limit,
+            time_limit: None,
+        }
+    }
+
+    pub fn with_time_limit(mut self, time_limit: Option<TimeLimit>) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+
+    pub fn with_id(mut self, id: String) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn id(&self) -> &String {
+        &self.id
+    }
+
+    pub fn set_name(&self) -> &String {
+        &self.set_name
+    }
+
+    pub fn class_name(&self) -> &String {
+        &self.class_name
+    }
+
+    pub fn set_by(&self) -> &String {
+        &self.set_by
+    }
+
+    pub fn opens(&self) -> &DateTime<Utc> {
+        &self.opens
+    }
+
+    pub fn due(&self) -> &DateTime<Utc> {
+        &self.due
+    }
+
+    pub fn attempt_limit(&self) -> u32 {
+        self.attempt_limit
+    }
+
+    pub fn time_limit(&self) -> Option<TimeLimit> {
+        self.time_limit
+    }
+
+    /// Whether a student who has already made `attempts_used` attempts
+    /// can start another one at `now`.
+    pub fn can_attempt(&self, now: DateTime<Utc>, attempts_used: u32) -> bool {
+        now >= self.opens && (self.attempt_limit == 0 || attempts_used < self.attempt_limit)
+    }
+
+    /// Where a student is up to, given the reviews they have submitted
+    /// for this assignment.
+    pub fn status(&self, now: DateTime<Utc>, reviews: &[QuizReview]) -> AssignmentStatus {
+        match reviews.iter().map(|r| r.finished).min() {
+            Some(first) if first > self.due => AssignmentStatus::CompletedLate,
+            Some(_) => AssignmentStatus::Completed,
+            None if now < self.opens => AssignmentStatus::Upcoming,
+            None if now > self.due => AssignmentStatus::Overdue,
+            None => AssignmentStatus::Outstanding,
+        }
+    }
+}
+
+impl AssignmentStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AssignmentStatus::Upcoming => "Upcoming",
+            AssignmentStatus::Outstanding => "Outstanding",
+            AssignmentStatus::Overdue => "Overdue",
+            AssignmentStatus::Completed => "Completed",
+            AssignmentStatus::CompletedLate => "Completed late",
+        }
+    }
+}
+
+impl CompletionGrid {
+    /// Builds the grid for `students` from every review submitted for
+    /// the assignment. Students with no reviews get an empty row.
+    pub fn new(assignment: Assignment, students: &[String], reviews: &[QuizReview]) -> Self {
+        let rows = students
+            .iter()
+            .map(|username| {
+                let theirs = reviews
+                    .iter()
+                    .filter(|r| &r.username == username)
+                    .collect::<Vec<_>>();
+                let first_finished = theirs.iter().map(|r| r.finished).min();
+                CompletionRow {
+                    username: username.clone(),
+                    attempts: theirs.len() as u32,
+                    best_score: theirs.iter().map(|r| r.score()).max_by_key(|(a, _)| *a),
+                    first_finished,
+                    late: first_finished.is_some_and(|f| f > assignment.due),
+                }
+            })
+            .collect();
+        CompletionGrid { assignment, rows }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn status_follows_the_dates() {
+        let now = Utc::now();
+        let assignment = Assignment::new(
+            "Set".into(),
+            "Class".into(),
+            "teacher".into(),
+            now - Duration::days(2),
+            now - Duration::days(1),
+            1,
+        );
+        assert_eq!(assignment.status(now, &[]), AssignmentStatus::Overdue);
+        assert_eq!(
+            assignment.status(now - Duration::hours(36), &[]),
+            AssignmentStatus::Outstanding
+        );
+        assert_eq!(
+            assignment.status(now - Duration::days(3), &[]),
+            AssignmentStatus::Upcoming
+        );
+
+        let reviews = [QuizReview::new("student".into(), "Set".into(), now, vec![])];
+        assert_eq!(
+            assignment.status(now, &reviews),
+            AssignmentStatus::CompletedLate
+        );
+        assert!(!assignment.can_attempt(now, 1));
+
+        let grid = CompletionGrid::new(assignment, &["student".into(), "other".into()], &reviews);
+        assert!(grid.rows[0].late);
+        assert_eq!(grid.rows[0].attempts, 1);
+        assert_eq!(grid.rows[1].best_score, None);
+    }
+}
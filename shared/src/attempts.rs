@@ -0,0 +1,228 @@
+//! The state of a quiz while it is being sat, before it is marked.
+
+use crate::{
+    questions::Question,
+    responses::{Confidence, QuestionRef, QuizMode, Response},
+};
+use chrono::{DateTime, Duration, Utc};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Everything a student has done on one question so far. Drafts can be
+/// changed freely until the whole quiz is handed in.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestionDraft {
+    /// The answer entered for each part, in order.
+    pub answers: Vec<String>,
+    pub hints_used: u32,
+    /// The student wants to come back to this question before handing in.
+    pub flagged: bool,
+    /// Time spent on the question over every visit to it.
+    pub seconds_spent: i64,
+    pub calculator_log: Vec<String>,
+    /// Time ran out on the question, so it can't be changed any more.
+    pub timed_out: bool,
+    /// The answer has been marked in practice mode, and is being shown
+    /// with its feedback.
+    pub checked: bool,
+    /// How many times the answer has been checked in practice mode.
+    pub tries: u32,
+    pub confidence: Option<Confidence>,
+}
+
+impl QuestionDraft {
+    pub fn answer(&self, part: usize) -> &str {
+        self.answers.get(part).map_or("", String::as_str)
+    }
+
+    pub fn set_answer(&mut self, part: usize, answer: String) {
+        if self.answers.len() <= part {
+            self.answers.resize(part + 1, String::new());
+        }
+        self.answers[part] = answer;
+    }
+
+    /// Whether the answer can't be changed right now.
+    pub fn locked(&self) -> bool {
+        self.timed_out || self.checked
+    }
+
+    /// Whether a checked answer can be tried again in practice mode.
+    pub fn can_retry(&self, correct: bool) -> bool {
+        self.checked && !correct && self.tries <= QuizMode::PRACTICE_RETRIES
+    }
+
+    /// Whether anything has been entered for any part.
+    pub fn is_answered(&self) -> bool {
+        self.answers.iter().any(|a| !a.trim().is_empty())
+    }
+
+    /// Marks the draft as the answer to `question`, as it stands when
+    /// the quiz is handed in at `finished`.
+    pub fn to_response(
+        &self,
+        reference: QuestionRef,
+        question: &Question,
+        hint_penalty: u32,
+        finished: DateTime<Utc>,
+    ) -> Response {
+        let response = Response::new(reference, question, self.answers.clone())
+            .with_hints(self.hints_used, hint_penalty)
+            .with_solution(question.solution().clone())
+            .with_time_spent(finished - Duration::seconds(self.seconds_spent), finished)
+            .with_calculator_log(self.calculator_log.clone())
+            .with_tries(self.tries)
+            .with_confidence(self.confidence);
+        match self.timed_out {
+            true => response.out_of_time(),
+            false => response,
+        }
+    }
+}
+
+/// A quiz that has been started but not handed in, saved so that the
+/// student can pick it up again later.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuizAttempt {
+    pub username: String,
+    pub set_name: String,
+    #[serde(default)]
+    pub assignment_id: Option<String>,
+    pub current_question: usize,
+    pub drafts: Vec<QuestionDraft>,
+    /// Seconds spent with the quiz open, not counting rest breaks or
+    /// time away from it. Only untimed attempts carry on from this.
+    pub elapsed: i64,
+    /// When a timed attempt began, so that its clock keeps running while
+    /// the student is away from it.
+    #[serde(default)]
+    pub started: Option<DateTime<Utc>>,
+    /// When the current question was shown, if each question is timed.
+    #[serde(default)]
+    pub question_shown: Option<DateTime<Utc>>,
+    /// Seconds of rest breaks that the clock of a timed attempt has been
+    /// stopped for.
+    #[serde(default)]
+    pub break_seconds: i64,
+    /// Seeds anything random about the attempt, so that it comes back
+    /// exactly as it was left.
+    pub seed: u64,
+    /// When the attempt was last saved.
+    pub saved: DateTime<Utc>,
+}
+
+impl QuizAttempt {
+    /// Whether the attempt has been left alone for longer than
+    /// `resume_hours`, after which it can't be resumed.
+    pub fn expired(&self, resume_hours: u32, now: DateTime<Utc>) -> bool {
+        now - self.saved > Duration::hours(resume_hours as i64)
+    }
+}
+
+/// The order to show a set's `count` questions in for one attempt, as
+/// the index in the set of each question in turn. The same seed always
+/// gives the same order, so a resumed attempt comes back unchanged.
+pub fn question_order(count: usize, shuffle: bool, seed: u64) -> Vec<usize> {
+    let mut order = (0..count).collect::<Vec<_>>();
+    if shuffle {
+        order.shuffle(&mut StdRng::seed_from_u64(seed));
+    }
+    order
+}
+
+/// The draft for question `index`, which is blank if it hasn't been
+/// visited yet.
+pub fn draft_at(drafts: &[QuestionDraft], index: usize) -> QuestionDraft {
+    drafts.get(index).cloned().unwrap_or_default()
+}
+
+/// The draft for question `index`, adding blank drafts up to it if
+/// they haven't been made yet.
+pub fn draft_mut(drafts: &mut Vec<QuestionDraft>, index: usize) -> &mut QuestionDraft {
+    if drafts.len() <= index {
+        drafts.resize(index + 1, QuestionDraft::default());
+    }
+    &mut drafts[index]
+}
+
+/// The indices of the questions out of `total` with nothing entered.
+pub fn unanswered(drafts: &[QuestionDraft], total: usize) -> Vec<usize> {
+    (0..total)
+        .filter(|&i| !draft_at(drafts, i).is_answered())
+        .collect()
+}
+
+/// The indices of the questions flagged for review.
+pub fn flagged(drafts: &[QuestionDraft]) -> Vec<usize> {
+    drafts
+        .iter()
+        .enumerate()
+        .filter(|(_, d)| d.flagged)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drafts_track_answers_and_flags() {
+        let mut drafts = vec![];
+        draft_mut(&mut drafts, 1).set_answer(2, String::from("7"));
+        draft_mut(&mut drafts, 0).flagged = true;
+        assert_eq!(drafts[1].answers, vec!["", "", "7"]);
+        assert_eq!(drafts[1].answer(2), "7");
+        assert_eq!(drafts[1].answer(5), "");
+        assert_eq!(unanswered(&drafts, 4), vec![0, 2, 3]);
+        assert_eq!(flagged(&drafts), vec![0]);
+        assert_eq!(draft_at(&drafts, 9), QuestionDraft::default());
+    }
+
+    #[test]
+    fn practice_retries_run_out() {
+        let mut draft = QuestionDraft {
+            checked: true,
+            ..Default::default()
+        };
+        for _ in 0..=QuizMode::PRACTICE_RETRIES {
+            draft.tries += 1;
+            assert!(draft.locked());
+            assert!(!draft.can_retry(true));
+        }
+        assert!(!draft.can_retry(false));
+        draft.tries = 1;
+        assert!(draft.can_retry(false));
+    }
+
+    #[test]
+    fn shuffled_orders_are_seeded_permutations() {
+        assert_eq!(question_order(4, false, 7), vec![0, 1, 2, 3]);
+        let order = question_order(20, true, 7);
+        assert_eq!(order, question_order(20, true, 7));
+        assert_ne!(order, question_order(20, true, 8));
+        let mut sorted = order.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn attempts_expire() {
+        let saved = Utc::now();
+        let attempt = QuizAttempt {
+            username: String::from("student"),
+            set_name: String::from("Algebra"),
+            assignment_id: None,
+            current_question: 0,
+            drafts: vec![],
+            elapsed: 0,
+            started: None,
+            question_shown: None,
+            break_seconds: 0,
+            seed: 0,
+            saved,
+        };
+        assert!(!attempt.expired(24, saved + Duration::hours(23)));
+        assert!(attempt.expired(24, saved + Duration::hours(25)));
+    }
+}
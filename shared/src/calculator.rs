@@ -0,0 +1,415 @@
+//! A scientific calculator for use during quizzes.
+//!
+//! Expressions are typed as they would be on a handheld calculator,
+//! such as `2sin(30) + 3^2` or `ln(e^2)/Ans`. The usual precedence
+//! applies, powers are right associative, and a number or bracket
+//! written next to another is multiplied, so `2pi` means `2 * pi`.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    f64::consts::{E, PI},
+    fmt::Display,
+};
+
+/// Whether trig functions work in degrees or radians.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AngleMode {
+    #[default]
+    Degrees,
+    Radians,
+}
+
+/// The calculator's state between calculations.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Calculator {
+    pub mode: AngleMode,
+    /// The value stored with `M+` and `M-`, and read back as `M`.
+    pub memory: f64,
+    /// The result of the last calculation, read back as `Ans`.
+    pub ans: f64,
+}
+
+/// The functions that can be written before a bracket, like `sin(30)`.
+pub const FUNCTIONS: [&str; 10] = [
+    "sin", "cos", "tan", "asin", "acos", "atan", "ln", "log", "sqrt", "abs",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Op(char),
+    Open,
+    Close,
+}
+
+impl AngleMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AngleMode::Degrees => "DEG",
+            AngleMode::Radians => "RAD",
+        }
+    }
+
+    pub fn toggled(&self) -> Self {
+        match self {
+            AngleMode::Degrees => AngleMode::Radians,
+            AngleMode::Radians => AngleMode::Degrees,
+        }
+    }
+}
+
+impl Display for AngleMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl Calculator {
+    /// Works out `expression` and remembers the result as `Ans`.
+    pub fn evaluate(&mut self, expression: &str) -> Result<f64, String> {
+        let tokens = tokenise(expression)?;
+        if tokens.is_empty() {
+            return Err(String::from("nothing to calculate"));
+        }
+        let mut parser = Parser {
+            tokens,
+            at: 0,
+            calculator: self,
+        };
+        let value = parser.sum()?;
+        if parser.peek().is_some() {
+            return Err(String::from("syntax error"));
+        }
+        if !value.is_finite() {
+            return Err(String::from("maths error"));
+        }
+        self.ans = value;
+        Ok(value)
+    }
+
+    pub fn memory_add(&mut self, value: f64) {
+        self.memory += value;
+    }
+
+    pub fn memory_subtract(&mut self, value: f64) {
+        self.memory -= value;
+    }
+
+    pub fn memory_clear(&mut self) {
+        self.memory = 0.0;
+    }
+
+    fn angle_in(&self, angle: f64) -> f64 {
+        match self.mode {
+            AngleMode::Degrees => angle.to_radians(),
+            AngleMode::Radians => angle,
+        }
+    }
+
+    fn angle_out(&self, angle: f64) -> f64 {
+        match self.mode {
+            AngleMode::Degrees => angle.to_degrees(),
+            AngleMode::Radians => angle,
+        }
+    }
+
+    fn apply(&self, function: &str, x: f64) -> Result<f64, String> {
+        let value = match function {
+            "sin" => self.angle_in(x).sin(),
+            "cos" => self.angle_in(x).cos(),
+            "tan" => {
+                // tan(90) in degrees should be an error rather than a
+                // huge number left over from rounding.
+                let cos = self.angle_in(x).cos();
+                if cos.abs() < 1e-12 {
+                    return Err(String::from("maths error"));
+                }
+                self.angle_in(x).sin() / cos
+            }
+            "asin" => self.angle_out(x.asin()),
+            "acos" => self.angle_out(x.acos()),
+            "atan" => self.angle_out(x.atan()),
+            "ln" => x.ln(),
+            "log" => x.log10(),
+            "sqrt" => x.sqrt(),
+            "abs" => x.abs(),
+            other => return Err(format!("unknown function: {other}")),
+        };
+        if value.is_nan() {
+            return Err(String::from("maths error"));
+        }
+        Ok(value)
+    }
+}
+
+/// Shows a result to at most 10 decimal places, which hides rounding
+/// errors like `sin(30) = 0.49999999999999994`. Very large and very
+/// small values are shown in standard form.
+pub fn format_result(value: f64) -> String {
+    if value != 0.0 && !(1e-6..1e12).contains(&value.abs()) {
+        return format!("{value:.6e}");
+    }
+    let text = format!("{value:.10}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => String::from("0"),
+        text => text.to_owned(),
+    }
+}
+
+fn tokenise(expression: &str) -> Result<Vec<Token>, String> {
+    let chars = expression.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            c if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                // An exponent straight after the number is standard form,
+                // as results are shown, rather than a multiple of e.
+                if let Some(digits) = exponent_digits(&chars[i..]) {
+                    i += digits;
+                }
+                let text = chars[start..i].iter().collect::<String>();
+                let number = text.parse().map_err(|_| format!("bad number: {text}"))?;
+                tokens.push(Token::Number(number));
+            }
+            c if c.is_alphabetic() => {
+                let start = i;
+                while i < chars.len() && chars[i].is_alphabetic() {
+                    i += 1;
+                }
+                tokens.push(Token::Name(chars[start..i].iter().collect()));
+            }
+            '+' | '-' | '*' | '/' | '^' | '!' => {
+                tokens.push(Token::Op(c));
+                i += 1;
+            }
+            '×' => {
+                tokens.push(Token::Op('*'));
+                i += 1;
+            }
+            '÷' => {
+                tokens.push(Token::Op('/'));
+                i += 1;
+            }
+            '−' => {
+                tokens.push(Token::Op('-'));
+                i += 1;
+            }
+            '√' => {
+                tokens.push(Token::Name(String::from("sqrt")));
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                i += 1;
+            }
+            other => return Err(format!("unexpected character: {other}")),
+        }
+    }
+    Ok(tokens)
+}
+
+/// How many characters make up an exponent like `e12` or `E-6` at the
+/// start of `chars`, if there is one.
+fn exponent_digits(chars: &[char]) -> Option<usize> {
+    if !matches!(chars.first(), Some('e' | 'E')) {
+        return None;
+    }
+    let sign = usize::from(matches!(chars.get(1), Some('+' | '-')));
+    let digits = chars[1 + sign..]
+        .iter()
+        .take_while(|c| c.is_ascii_digit())
+        .count();
+    (digits > 0).then_some(1 + sign + digits)
+}
+
+/// A recursive descent parser, with one method for each level of
+/// precedence from loosest to tightest.
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    at: usize,
+    calculator: &'a Calculator,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.at)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.at).cloned();
+        self.at += 1;
+        token
+    }
+
+    fn sum(&mut self) -> Result<f64, String> {
+        let mut value = self.product()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.at += 1;
+            let rhs = self.product()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    fn product(&mut self) -> Result<f64, String> {
+        let mut value = self.unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Op('*')) => {
+                    self.at += 1;
+                    value *= self.unary()?;
+                }
+                Some(Token::Op('/')) => {
+                    self.at += 1;
+                    let rhs = self.unary()?;
+                    if rhs == 0.0 {
+                        return Err(String::from("cannot divide by zero"));
+                    }
+                    value /= rhs;
+                }
+                // Something written straight after a value, like the
+                // `pi` in `2pi`, is multiplied by it.
+                Some(Token::Number(_) | Token::Name(_) | Token::Open) => {
+                    value *= self.power()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<f64, String> {
+        match self.peek() {
+            Some(Token::Op('-')) => {
+                self.at += 1;
+                Ok(-self.unary()?)
+            }
+            Some(Token::Op('+')) => {
+                self.at += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<f64, String> {
+        let base = self.factorial()?;
+        if let Some(Token::Op('^')) = self.peek() {
+            self.at += 1;
+            return Ok(base.powf(self.unary()?));
+        }
+        Ok(base)
+    }
+
+    fn factorial(&mut self) -> Result<f64, String> {
+        let mut value = self.primary()?;
+        while let Some(Token::Op('!')) = self.peek() {
+            self.at += 1;
+            if value < 0.0 || value.fract() != 0.0 || value > 170.0 {
+                return Err(String::from("maths error"));
+            }
+            value = (1..=value as u64).map(|n| n as f64).product();
+        }
+        Ok(value)
+    }
+
+    fn primary(&mut self) -> Result<f64, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(n),
+            Some(Token::Open) => {
+                let value = self.sum()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(value),
+                    _ => Err(String::from("missing closing bracket")),
+                }
+            }
+            Some(Token::Name(name)) => match name.as_str() {
+                "pi" | "π" => Ok(PI),
+                "e" => Ok(E),
+                "Ans" | "ans" => Ok(self.calculator.ans),
+                "M" => Ok(self.calculator.memory),
+                function if FUNCTIONS.contains(&function) => {
+                    let x = self.factorial()?;
+                    self.calculator.apply(function, x)
+                }
+                other => Err(format!("unknown name: {other}")),
+            },
+            Some(_) => Err(String::from("syntax error")),
+            None => Err(String::from("the expression ended too soon")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(calculator: &mut Calculator, expression: &str) -> String {
+        format_result(calculator.evaluate(expression).unwrap())
+    }
+
+    #[test]
+    fn precedence_and_implicit_multiplication() {
+        let mut calc = Calculator::default();
+        assert_eq!(eval(&mut calc, "1 + 2 * 3"), "7");
+        assert_eq!(eval(&mut calc, "-2^2"), "-4");
+        assert_eq!(eval(&mut calc, "2^3^2"), "512");
+        assert_eq!(eval(&mut calc, "2(3 + 4)"), "14");
+        assert_eq!(eval(&mut calc, "Ans / 7"), "2");
+        assert_eq!(eval(&mut calc, "5! ÷ 4"), "30");
+        assert_eq!(eval(&mut calc, "2^-1"), "0.5");
+        assert!(calc.evaluate("1 / 0").is_err());
+        assert!(calc.evaluate("(1 + 2").is_err());
+        assert!(calc.evaluate("2 +").is_err());
+    }
+
+    #[test]
+    fn standard_form_reads_back_in() {
+        let mut calc = Calculator::default();
+        for value in [1.23456e12, -6.02e23, 3.5e-9] {
+            let shown = format_result(value);
+            assert_eq!(eval(&mut calc, &shown), shown);
+        }
+        assert_eq!(eval(&mut calc, "6.02E+2 / 2"), "301");
+        assert_eq!(
+            eval(&mut calc, "2e"),
+            format_result(2.0 * std::f64::consts::E)
+        );
+        assert_eq!(
+            eval(&mut calc, "2e^1"),
+            format_result(2.0 * std::f64::consts::E)
+        );
+    }
+
+    #[test]
+    fn trig_logs_and_memory() {
+        let mut calc = Calculator::default();
+        assert_eq!(eval(&mut calc, "sin(30)"), "0.5");
+        assert_eq!(eval(&mut calc, "asin(0.5)"), "30");
+        assert!(calc.evaluate("tan(90)").is_err());
+        calc.mode = calc.mode.toggled();
+        assert_eq!(eval(&mut calc, "cos(pi)"), "-1");
+        assert_eq!(eval(&mut calc, "ln(e^2) + log(1000)"), "5");
+        assert_eq!(eval(&mut calc, "√16"), "4");
+        assert!(calc.evaluate("sqrt(-1)").is_err());
+
+        calc.memory_add(3.0);
+        calc.memory_add(4.0);
+        calc.memory_subtract(2.0);
+        assert_eq!(eval(&mut calc, "2M"), "10");
+        calc.memory_clear();
+        assert_eq!(eval(&mut calc, "M"), "0");
+    }
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A teaching group, such as `10X Maths`. Teachers own the classes they
+/// create and can share them with other teachers, and students are
+/// enrolled by username. Names are unique, like question set names.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Class {
+    name: String,
+    teachers: Vec<String>,
+    #[serde(default)]
+    students: Vec<String>,
+    #[serde(default)]
+    created: DateTime<Utc>,
+}
+
+impl Class {
+    /// A new, empty class owned by `teacher`.
+    pub fn new(name: String, teacher: String) -> Self {
+        Class {
+            name,
+            teachers: vec![teacher],
+            students: vec![],
+            created: Utc::now(),
+        }
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn teachers(&self) -> &Vec<String> {
+        &self.teachers
+    }
+
+    pub fn students(&self) -> &Vec<String> {
+        &self.students
+    }
+
+    pub fn created(&self) -> &DateTime<Utc> {
+        &self.created
+    }
+
+    pub fn is_teacher(&self, username: &str) -> bool {
+        self.teachers.iter().any(|t| t == username)
+    }
+
+    pub fn is_enrolled(&self, username: &str) -> bool {
+        self.students.iter().any(|s| s == username)
+    }
+}
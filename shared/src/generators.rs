@@ -0,0 +1,1004 @@
+//! Procedural question generators for common GCSE/A-level skills.
+//!
+//! Each [`Generator`] produces fully formed [`Question`]s with the
+//! correct answer already worked out, so that teachers can fill a
+//! [`QuestionSet`](crate::questions::QuestionSet) in the Creator
+//! without typing every item by hand. Every generator takes a
+//! difficulty from 1 (easiest) to 5 (hardest), which widens the
+//! number ranges and unlocks harder forms of the problem.
+//!
+//! Answers are written in a single canonical form (for example
+//! `3x^2-2x+1` or `x=-3,x=4`), and the question title tells the
+//! student which form is expected. Each question is also given a
+//! [`GradingRule`] so that equivalent forms, such as the roots in
+//! the other order, still earn the marks.
+
+use crate::{
+    marking::GradingRule,
+    questions::{Question, MAX_DIFFICULTY, MIN_DIFFICULTY},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    ops::{Add, Div, Mul, Sub},
+    str::FromStr,
+};
+
+/// Every topic that can be generated procedurally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Generator {
+    ExpandBrackets,
+    LinearEquation,
+    SimultaneousEquations,
+    QuadraticFactorising,
+    FractionArithmetic,
+    Differentiation,
+    Integration,
+}
+
+impl Generator {
+    /// Every generator, in the order they are shown in the Creator.
+    pub fn all() -> Vec<Generator> {
+        vec![
+            Generator::ExpandBrackets,
+            Generator::LinearEquation,
+            Generator::SimultaneousEquations,
+            Generator::QuadraticFactorising,
+            Generator::FractionArithmetic,
+            Generator::Differentiation,
+            Generator::Integration,
+        ]
+    }
+
+    /// A human readable name for the generator.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Generator::ExpandBrackets => "Expanding Brackets",
+            Generator::LinearEquation => "Linear Equations",
+            Generator::SimultaneousEquations => "Simultaneous Equations",
+            Generator::QuadraticFactorising => "Quadratics by Factorising",
+            Generator::FractionArithmetic => "Fraction Arithmetic",
+            Generator::Differentiation => "Differentiation",
+            Generator::Integration => "Integration",
+        }
+    }
+
+    /// The topic tag given to every question this generator makes.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Generator::ExpandBrackets => "Algebra > Expanding Brackets",
+            Generator::LinearEquation => "Algebra > Linear Equations",
+            Generator::SimultaneousEquations => "Algebra > Simultaneous Equations",
+            Generator::QuadraticFactorising => "Algebra > Quadratics",
+            Generator::FractionArithmetic => "Number > Fractions",
+            Generator::Differentiation => "Calculus > Differentiation",
+            Generator::Integration => "Calculus > Integration",
+        }
+    }
+
+    /// Generates a single question. The difficulty is clamped into
+    /// the range [`MIN_DIFFICULTY`]..=[`MAX_DIFFICULTY`].
+    pub fn generate<R: Rng>(&self, difficulty: u8, rng: &mut R) -> Question {
+        let difficulty = difficulty.clamp(MIN_DIFFICULTY, MAX_DIFFICULTY);
+        let question = match self {
+            Generator::ExpandBrackets => ExpandBrackets::random(difficulty, rng).question(),
+            Generator::LinearEquation => LinearEquation::random(difficulty, rng).question(),
+            Generator::SimultaneousEquations => {
+                SimultaneousEquations::random(difficulty, rng).question()
+            }
+            Generator::QuadraticFactorising => {
+                QuadraticFactorising::random(difficulty, rng).question()
+            }
+            Generator::FractionArithmetic => FractionArithmetic::random(difficulty, rng).question(),
+            Generator::Differentiation => Differentiation::random(difficulty, rng).question(),
+            Generator::Integration => Integration::random(difficulty, rng).question(),
+        };
+        question
+            .with_tags(vec![self.tag().to_owned()])
+            .with_difficulty(difficulty)
+    }
+
+    /// Generates `count` questions from a seed. This is what the
+    /// frontend uses, as it means the caller does not need its own
+    /// random number generator.
+    pub fn generate_many(&self, difficulty: u8, count: usize, seed: u64) -> Vec<Question> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..count)
+            .map(|_| self.generate(difficulty, &mut rng))
+            .collect()
+    }
+}
+
+impl Display for Generator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Generator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Generator::all()
+            .into_iter()
+            .find(|g| g.name() == s)
+            .ok_or(format!("unknown generator: {s}"))
+    }
+}
+
+/// Greatest common divisor, always non-negative.
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// An exact rational number which is always kept in lowest terms
+/// with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fraction {
+    num: i64,
+    den: i64,
+}
+
+impl Fraction {
+    /// Builds a fraction and reduces it. Panics if `den` is zero.
+    pub fn new(num: i64, den: i64) -> Self {
+        assert!(den != 0, "fraction denominator cannot be zero");
+        let sign = if den < 0 { -1 } else { 1 };
+        let g = gcd(num, den).max(1);
+        Fraction {
+            num: sign * num / g,
+            den: sign * den / g,
+        }
+    }
+
+    pub fn integer(n: i64) -> Self {
+        Fraction { num: n, den: 1 }
+    }
+
+    pub fn num(&self) -> i64 {
+        self.num
+    }
+
+    pub fn den(&self) -> i64 {
+        self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+}
+
+impl Add for Fraction {
+    type Output = Fraction;
+    fn add(self, rhs: Self) -> Self::Output {
+        Fraction::new(self.num * rhs.den + rhs.num * self.den, self.den * rhs.den)
+    }
+}
+
+impl Sub for Fraction {
+    type Output = Fraction;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Fraction::new(self.num * rhs.den - rhs.num * self.den, self.den * rhs.den)
+    }
+}
+
+impl Mul for Fraction {
+    type Output = Fraction;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Fraction::new(self.num * rhs.num, self.den * rhs.den)
+    }
+}
+
+impl Div for Fraction {
+    type Output = Fraction;
+    fn div(self, rhs: Self) -> Self::Output {
+        Fraction::new(self.num * rhs.den, self.den * rhs.num)
+    }
+}
+
+impl Display for Fraction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl FromStr for Fraction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = |_| format!("not a fraction: {s}");
+        match s.trim().split_once('/') {
+            Some((n, d)) => {
+                let d = d.trim().parse::<i64>().map_err(bad)?;
+                if d == 0 {
+                    return Err(format!("zero denominator: {s}"));
+                }
+                Ok(Fraction::new(n.trim().parse().map_err(bad)?, d))
+            }
+            None => Ok(Fraction::integer(s.trim().parse().map_err(bad)?)),
+        }
+    }
+}
+
+/// Writes a sum of terms such as `3x^2-x+4` or `2x+3y`, skipping
+/// any zero coefficients and hiding coefficients of one on variable
+/// terms. An empty sum is written as `0`.
+fn format_terms(terms: &[(i64, String)]) -> String {
+    let mut out = String::new();
+    for (coeff, var) in terms.iter().filter(|(c, _)| *c != 0) {
+        if *coeff < 0 {
+            out.push('-');
+        } else if !out.is_empty() {
+            out.push('+');
+        }
+        if coeff.abs() != 1 || var.is_empty() {
+            out.push_str(&coeff.abs().to_string());
+        }
+        out.push_str(var);
+    }
+    if out.is_empty() {
+        out.push('0');
+    }
+    out
+}
+
+/// A polynomial in `x` with integer coefficients. The coefficient of
+/// `x^n` is stored at index `n`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial(Vec<i64>);
+
+impl Polynomial {
+    pub fn new(mut coeffs: Vec<i64>) -> Self {
+        while coeffs.len() > 1 && coeffs.last() == Some(&0) {
+            coeffs.pop();
+        }
+        Polynomial(coeffs)
+    }
+
+    pub fn coeffs(&self) -> &Vec<i64> {
+        &self.0
+    }
+
+    pub fn degree(&self) -> usize {
+        self.0.len().saturating_sub(1)
+    }
+
+    pub fn eval(&self, x: Fraction) -> Fraction {
+        self.0.iter().rev().fold(Fraction::integer(0), |acc, c| {
+            acc * x + Fraction::integer(*c)
+        })
+    }
+
+    pub fn eval_f64(&self, x: f64) -> f64 {
+        self.0.iter().rev().fold(0.0, |acc, c| acc * x + *c as f64)
+    }
+
+    pub fn mul(&self, rhs: &Polynomial) -> Polynomial {
+        let mut out = vec![0; self.0.len() + rhs.0.len() - 1];
+        for (i, a) in self.0.iter().enumerate() {
+            for (j, b) in rhs.0.iter().enumerate() {
+                out[i + j] += a * b;
+            }
+        }
+        Polynomial::new(out)
+    }
+
+    pub fn derivative(&self) -> Polynomial {
+        let coeffs = self
+            .0
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(n, c)| c * n as i64)
+            .collect::<Vec<_>>();
+        if coeffs.is_empty() {
+            Polynomial::new(vec![0])
+        } else {
+            Polynomial::new(coeffs)
+        }
+    }
+
+    /// The exact value of the definite integral between `lower` and `upper`.
+    pub fn integrate(&self, lower: i64, upper: i64) -> Fraction {
+        self.0
+            .iter()
+            .enumerate()
+            .fold(Fraction::integer(0), |acc, (n, c)| {
+                let p = n as u32 + 1;
+                acc + Fraction::new(c * (upper.pow(p) - lower.pow(p)), p as i64)
+            })
+    }
+}
+
+impl Display for Polynomial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let terms = self
+            .0
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(n, c)| {
+                let var = match n {
+                    0 => String::new(),
+                    1 => "x".to_owned(),
+                    _ => format!("x^{n}"),
+                };
+                (*c, var)
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", format_terms(&terms))
+    }
+}
+
+impl FromStr for Polynomial {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+        if s.is_empty() {
+            return Err("empty polynomial".to_owned());
+        }
+        let bad = || format!("not a polynomial: {s}");
+
+        // Split before every sign that isn't the very first character.
+        let mut terms = vec![];
+        let mut start = 0;
+        for (i, c) in s.char_indices() {
+            if (c == '+' || c == '-') && i != 0 {
+                terms.push(&s[start..i]);
+                start = i;
+            }
+        }
+        terms.push(&s[start..]);
+
+        let mut coeffs = vec![];
+        for term in terms {
+            let (sign, body) = match term.strip_prefix('-') {
+                Some(body) => (-1, body),
+                None => (1, term.strip_prefix('+').unwrap_or(term)),
+            };
+            let (coeff, power) = match body.split_once('x') {
+                None => (body.parse::<i64>().map_err(|_| bad())?, 0),
+                Some((c, p)) => {
+                    let coeff = if c.is_empty() {
+                        1
+                    } else {
+                        c.parse().map_err(|_| bad())?
+                    };
+                    let power = match p.strip_prefix('^') {
+                        Some(p) => p.parse::<usize>().map_err(|_| bad())?,
+                        None if p.is_empty() => 1,
+                        None => return Err(bad()),
+                    };
+                    (coeff, power)
+                }
+            };
+            if coeffs.len() <= power {
+                coeffs.resize(power + 1, 0);
+            }
+            coeffs[power] += sign * coeff;
+        }
+        Ok(Polynomial::new(coeffs))
+    }
+}
+
+/// Picks a non-zero integer from `-max..=max`.
+fn nonzero<R: Rng>(rng: &mut R, max: i64) -> i64 {
+    let n = rng.gen_range(1..=max);
+    if rng.gen_bool(0.5) {
+        -n
+    } else {
+        n
+    }
+}
+
+/// `(ax+b)(cx+d)`
+#[derive(Debug)]
+struct ExpandBrackets {
+    first: Polynomial,
+    second: Polynomial,
+}
+
+impl ExpandBrackets {
+    fn random<R: Rng>(difficulty: u8, rng: &mut R) -> Self {
+        let range = 2 + 2 * difficulty as i64;
+        let lead = |rng: &mut R| match difficulty {
+            1 | 2 => 1,
+            3 => rng.gen_range(1..=3),
+            _ => nonzero(rng, 4),
+        };
+        ExpandBrackets {
+            first: Polynomial::new(vec![nonzero(rng, range), lead(rng)]),
+            second: Polynomial::new(vec![nonzero(rng, range), lead(rng)]),
+        }
+    }
+
+    fn expanded(&self) -> Polynomial {
+        self.first.mul(&self.second)
+    }
+
+    fn question(&self) -> Question {
+        let (b, a) = (self.first.coeffs()[0], self.first.coeffs()[1]);
+        let (d, c) = (self.second.coeffs()[0], self.second.coeffs()[1]);
+        Question::new(
+            "Expand and simplify, giving your answer as ax^2+bx+c".to_owned(),
+            format!("({})({})", self.first, self.second),
+            false,
+            self.expanded().to_string(),
+        )
+        .with_marks(2, GradingRule::Polynomial)
+        .with_hints(vec![
+            "Multiply every term in the first bracket by every term in the second".to_owned(),
+        ])
+        .with_solution(vec![
+            format!(
+                "Multiply out: {}",
+                format_terms(&[
+                    (a * c, "x^2".to_owned()),
+                    (a * d, "x".to_owned()),
+                    (b * c, "x".to_owned()),
+                    (b * d, String::new()),
+                ])
+            ),
+            format!("Collect like terms: {}", self.expanded()),
+        ])
+    }
+}
+
+/// `ax+b=cx+d`, where `c` is zero on the easier difficulties.
+#[derive(Debug)]
+struct LinearEquation {
+    lhs: Polynomial,
+    rhs: Polynomial,
+    solution: i64,
+}
+
+impl LinearEquation {
+    fn random<R: Rng>(difficulty: u8, rng: &mut R) -> Self {
+        let range = 3 * difficulty as i64;
+        let solution = rng.gen_range(-range..=range);
+        let a = nonzero(rng, 2 + difficulty as i64);
+        let b = rng.gen_range(-range..=range);
+        let c = if difficulty < 3 {
+            0
+        } else {
+            loop {
+                let c = nonzero(rng, 2 + difficulty as i64);
+                if c != a {
+                    break c;
+                }
+            }
+        };
+        let d = a * solution + b - c * solution;
+        LinearEquation {
+            lhs: Polynomial::new(vec![b, a]),
+            rhs: Polynomial::new(vec![d, c]),
+            solution,
+        }
+    }
+
+    fn question(&self) -> Question {
+        let coeff = self.lhs.coeffs()[1] - self.rhs.coeffs().get(1).unwrap_or(&0);
+        let constant = self.rhs.coeffs()[0] - self.lhs.coeffs()[0];
+        Question::new(
+            "Solve for x, giving your answer as x=n".to_owned(),
+            format!("{}={}", self.lhs, self.rhs),
+            false,
+            format!("x={}", self.solution),
+        )
+        .with_marks(2, GradingRule::Normalised)
+        .with_hints(vec![
+            "Get all of the x terms on one side and the numbers on the other".to_owned(),
+        ])
+        .with_solution(vec![
+            format!("Rearrange: {}x={}", coeff, constant),
+            format!("Divide both sides by {}: x={}", coeff, self.solution),
+        ])
+    }
+}
+
+/// `ax+by=c` and `dx+ey=f`.
+#[derive(Debug)]
+struct SimultaneousEquations {
+    first: (i64, i64, i64),
+    second: (i64, i64, i64),
+    solution: (i64, i64),
+}
+
+impl SimultaneousEquations {
+    fn random<R: Rng>(difficulty: u8, rng: &mut R) -> Self {
+        let range = 2 + 2 * difficulty as i64;
+        let coeff = 1 + difficulty as i64;
+        let (x, y) = (rng.gen_range(-range..=range), rng.gen_range(-range..=range));
+        loop {
+            let (a, b) = (nonzero(rng, coeff), nonzero(rng, coeff));
+            // The easier difficulties share a y coefficient, so the
+            // equations can be solved by subtracting one from the other.
+            let (d, e) = if difficulty < 3 {
+                (nonzero(rng, coeff), b)
+            } else {
+                (nonzero(rng, coeff), nonzero(rng, coeff))
+            };
+            if a * e - b * d != 0 {
+                return SimultaneousEquations {
+                    first: (a, b, a * x + b * y),
+                    second: (d, e, d * x + e * y),
+                    solution: (x, y),
+                };
+            }
+        }
+    }
+
+    fn format_equation((a, b, c): (i64, i64, i64)) -> String {
+        format!(
+            "{}={}",
+            format_terms(&[(a, "x".to_owned()), (b, "y".to_owned())]),
+            c
+        )
+    }
+
+    fn question(&self) -> Question {
+        let ((a, b, c), (d, e, f)) = (self.first, self.second);
+        Question::new(
+            "Solve simultaneously, giving your answer as x=n,y=m".to_owned(),
+            format!(
+                "{}, {}",
+                Self::format_equation(self.first),
+                Self::format_equation(self.second)
+            ),
+            false,
+            format!("x={},y={}", self.solution.0, self.solution.1),
+        )
+        .with_marks(4, GradingRule::AnyOrder)
+        .with_hints(vec![
+            "Scale the equations so one variable has the same coefficient in both".to_owned(),
+            "Subtract one equation from the other to eliminate that variable".to_owned(),
+        ])
+        .with_solution(vec![
+            format!("Eliminate y: {}x={}", a * e - b * d, c * e - b * f),
+            format!("Divide through: x={}", self.solution.0),
+            format!("Substitute back in to find y: y={}", self.solution.1),
+        ])
+    }
+}
+
+/// `(ax-p)(x-q)=0`, expanded, with `a` being one below difficulty 4.
+#[derive(Debug)]
+struct QuadraticFactorising {
+    quadratic: Polynomial,
+    factors: (Polynomial, Polynomial),
+    roots: Vec<Fraction>,
+}
+
+impl QuadraticFactorising {
+    fn random<R: Rng>(difficulty: u8, rng: &mut R) -> Self {
+        let range = 2 + 2 * difficulty as i64;
+        let a = if difficulty < 4 {
+            1
+        } else {
+            rng.gen_range(2..=difficulty as i64 - 1)
+        };
+        loop {
+            let (p, q) = (nonzero(rng, range), nonzero(rng, range));
+            let mut roots = vec![Fraction::new(p, a), Fraction::integer(q)];
+            if roots[0] == roots[1] {
+                continue;
+            }
+            roots.sort_by(|l, r| (l.num * r.den).cmp(&(r.num * l.den)));
+            let factors = (Polynomial::new(vec![-p, a]), Polynomial::new(vec![-q, 1]));
+            let quadratic = factors.0.mul(&factors.1);
+            return QuadraticFactorising {
+                quadratic,
+                factors,
+                roots,
+            };
+        }
+    }
+
+    fn question(&self) -> Question {
+        Question::new(
+            "Solve by factorising, giving your answer as x=a,x=b (smallest first)".to_owned(),
+            format!("{}=0", self.quadratic),
+            false,
+            self.roots
+                .iter()
+                .map(|r| format!("x={r}"))
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+        .with_marks(3, GradingRule::AnyOrder)
+        .with_hints(vec![
+            "Find two brackets which multiply out to give the quadratic".to_owned(),
+            "If a product is zero then one of its factors must be zero".to_owned(),
+        ])
+        .with_solution(vec![
+            format!("Factorise: ({})({})=0", self.factors.0, self.factors.1),
+            "Set each bracket equal to zero and solve".to_owned(),
+            format!(
+                "x={}",
+                self.roots
+                    .iter()
+                    .map(|r| r.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" or x=")
+            ),
+        ])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// `a/b op c/d`
+#[derive(Debug)]
+struct FractionArithmetic {
+    lhs: Fraction,
+    op: Operation,
+    rhs: Fraction,
+}
+
+impl FractionArithmetic {
+    fn random<R: Rng>(difficulty: u8, rng: &mut R) -> Self {
+        let ops = match difficulty {
+            1 => vec![Operation::Add, Operation::Sub],
+            2 => vec![Operation::Add, Operation::Sub, Operation::Mul],
+            _ => vec![
+                Operation::Add,
+                Operation::Sub,
+                Operation::Mul,
+                Operation::Div,
+            ],
+        };
+        let op = ops[rng.gen_range(0..ops.len())];
+        let max_den = 3 + 2 * difficulty as i64;
+        let operand = |rng: &mut R| {
+            let den = rng.gen_range(2..=max_den);
+            // Improper and negative fractions only show up on the
+            // harder difficulties.
+            let num = if difficulty < 4 {
+                rng.gen_range(1..den)
+            } else {
+                nonzero(rng, 2 * den)
+            };
+            Fraction::new(num, den)
+        };
+        FractionArithmetic {
+            lhs: operand(rng),
+            op,
+            rhs: operand(rng),
+        }
+    }
+
+    fn result(&self) -> Fraction {
+        match self.op {
+            Operation::Add => self.lhs + self.rhs,
+            Operation::Sub => self.lhs - self.rhs,
+            Operation::Mul => self.lhs * self.rhs,
+            Operation::Div => self.lhs / self.rhs,
+        }
+    }
+
+    fn question(&self) -> Question {
+        let symbol = match self.op {
+            Operation::Add => "+",
+            Operation::Sub => "-",
+            Operation::Mul => "×",
+            Operation::Div => "÷",
+        };
+        let (a, b, c, d) = (self.lhs.num, self.lhs.den, self.rhs.num, self.rhs.den);
+        let (hint, working) = match self.op {
+            Operation::Add | Operation::Sub => (
+                "Write both fractions over a common denominator",
+                format!(
+                    "Use the denominator {}: {}/{} {} {}/{}",
+                    b * d,
+                    a * d,
+                    b * d,
+                    symbol,
+                    c * b,
+                    b * d
+                ),
+            ),
+            Operation::Mul => (
+                "Multiply the numerators together and the denominators together",
+                format!("Multiply: {}/{}", a * c, b * d),
+            ),
+            Operation::Div => (
+                "Dividing by a fraction is the same as multiplying by its reciprocal",
+                format!("Multiply by the reciprocal: {}/{} × {}/{}", a, b, d, c),
+            ),
+        };
+        let wrap = |f: Fraction| {
+            if f.num < 0 {
+                format!("({f})")
+            } else {
+                f.to_string()
+            }
+        };
+        Question::new(
+            "Work out, giving your answer as a fraction in its simplest form".to_owned(),
+            format!("{} {} {}", wrap(self.lhs), symbol, wrap(self.rhs)),
+            false,
+            self.result().to_string(),
+        )
+        .with_marks(2, GradingRule::Normalised)
+        .with_hints(vec![hint.to_owned()])
+        .with_solution(vec![working, format!("Simplify: {}", self.result())])
+    }
+}
+
+/// Picks a polynomial whose degree and coefficients grow with difficulty.
+fn random_polynomial<R: Rng>(difficulty: u8, rng: &mut R) -> Polynomial {
+    let degree = 1 + (difficulty as usize).div_ceil(2);
+    let range = 2 + difficulty as i64;
+    let mut coeffs = (0..degree)
+        .map(|_| rng.gen_range(-range..=range))
+        .collect::<Vec<_>>();
+    coeffs.push(nonzero(rng, range));
+    Polynomial::new(coeffs)
+}
+
+/// `y=f(x)`, find `dy/dx`.
+#[derive(Debug)]
+struct Differentiation {
+    function: Polynomial,
+}
+
+impl Differentiation {
+    fn random<R: Rng>(difficulty: u8, rng: &mut R) -> Self {
+        Differentiation {
+            function: random_polynomial(difficulty, rng),
+        }
+    }
+
+    fn question(&self) -> Question {
+        Question::new(
+            "Find dy/dx, giving your answer as a simplified polynomial".to_owned(),
+            format!("y={}", self.function),
+            false,
+            self.function.derivative().to_string(),
+        )
+        .with_marks(2, GradingRule::Polynomial)
+        .with_hints(vec![
+            "Multiply each term by its power, then take one off the power".to_owned(),
+        ])
+        .with_solution(vec![
+            "Differentiate each term separately, constants disappear".to_owned(),
+            format!("dy/dx={}", self.function.derivative()),
+        ])
+    }
+}
+
+/// The definite integral of `f(x)` between two integer bounds.
+#[derive(Debug)]
+struct Integration {
+    function: Polynomial,
+    lower: i64,
+    upper: i64,
+}
+
+impl Integration {
+    fn random<R: Rng>(difficulty: u8, rng: &mut R) -> Self {
+        let function = random_polynomial(difficulty, rng);
+        let lower = if difficulty < 3 {
+            rng.gen_range(0..=2)
+        } else {
+            rng.gen_range(-3..=2)
+        };
+        let upper = lower + rng.gen_range(1..=3);
+        Integration {
+            function,
+            lower,
+            upper,
+        }
+    }
+
+    fn question(&self) -> Question {
+        Question::new(
+            "Evaluate the integral, giving your answer as a fraction in its simplest form"
+                .to_owned(),
+            format!("∫[{},{}] ({}) dx", self.lower, self.upper, self.function),
+            false,
+            self.function.integrate(self.lower, self.upper).to_string(),
+        )
+        .with_marks(3, GradingRule::Normalised)
+        .with_hints(vec![
+            "Add one to each power, then divide by the new power".to_owned(),
+            "Substitute the upper limit, then subtract the lower limit".to_owned(),
+        ])
+        .with_solution(vec![
+            "Integrate each term to get F(x)".to_owned(),
+            format!("Work out F({}) - F({})", self.upper, self.lower),
+            format!("= {}", self.function.integrate(self.lower, self.upper)),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::responses::{QuestionRef, Response};
+
+    /// Runs `check` over a spread of seeds at every difficulty.
+    fn for_each_rng(mut check: impl FnMut(u8, &mut StdRng)) {
+        for difficulty in MIN_DIFFICULTY..=MAX_DIFFICULTY {
+            for seed in 0..200 {
+                let mut rng = StdRng::seed_from_u64(seed);
+                check(difficulty, &mut rng);
+            }
+        }
+    }
+
+    #[test]
+    fn fractions_are_reduced() {
+        assert_eq!(Fraction::new(6, -8), Fraction::new(-3, 4));
+        assert_eq!(Fraction::new(4, 2).to_string(), "2");
+        assert_eq!("-6/8".parse::<Fraction>(), Ok(Fraction::new(-3, 4)));
+        assert!("1/0".parse::<Fraction>().is_err());
+    }
+
+    #[test]
+    fn polynomials_round_trip() {
+        for s in ["3x^2-2x+1", "-x^3+x", "5", "0", "x-7", "-2x^4+3x^2-x-1"] {
+            assert_eq!(s.parse::<Polynomial>().unwrap().to_string(), s);
+        }
+        assert_eq!(
+            "1 + 2x - x^2".parse::<Polynomial>().unwrap().to_string(),
+            "-x^2+2x+1"
+        );
+    }
+
+    #[test]
+    fn expand_brackets_answers_are_correct() {
+        for_each_rng(|difficulty, rng| {
+            let problem = ExpandBrackets::random(difficulty, rng);
+            let answer = problem.question().answer().parse::<Polynomial>().unwrap();
+            for x in -5..=5 {
+                let x = Fraction::integer(x);
+                assert_eq!(
+                    answer.eval(x),
+                    problem.first.eval(x) * problem.second.eval(x),
+                    "{problem:?}"
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn linear_equation_answers_are_correct() {
+        for_each_rng(|difficulty, rng| {
+            let problem = LinearEquation::random(difficulty, rng);
+            let question = problem.question();
+            let x = question.answer().strip_prefix("x=").unwrap();
+            let x = Fraction::integer(x.parse().unwrap());
+            assert_eq!(problem.lhs.eval(x), problem.rhs.eval(x), "{problem:?}");
+            assert!(!question.markup().contains("+-"));
+        });
+    }
+
+    #[test]
+    fn simultaneous_equation_answers_are_correct() {
+        for_each_rng(|difficulty, rng| {
+            let problem = SimultaneousEquations::random(difficulty, rng);
+            let question = problem.question();
+            let (x, y) = question.answer().split_once(',').unwrap();
+            let x: i64 = x.strip_prefix("x=").unwrap().parse().unwrap();
+            let y: i64 = y.strip_prefix("y=").unwrap().parse().unwrap();
+            for (a, b, c) in [problem.first, problem.second] {
+                assert_eq!(a * x + b * y, c, "{problem:?}");
+            }
+        });
+    }
+
+    #[test]
+    fn quadratic_answers_are_correct() {
+        for_each_rng(|difficulty, rng| {
+            let problem = QuadraticFactorising::random(difficulty, rng);
+            let question = problem.question();
+            let markup = question.markup().strip_suffix("=0").unwrap();
+            let quadratic = markup.parse::<Polynomial>().unwrap();
+            assert_eq!(quadratic.degree(), 2);
+
+            let roots = question
+                .answer()
+                .split(',')
+                .map(|r| r.strip_prefix("x=").unwrap().parse::<Fraction>().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(roots.len(), 2);
+            assert!(roots[0].to_f64() < roots[1].to_f64());
+            for root in roots {
+                assert!(quadratic.eval(root).is_zero(), "{problem:?}");
+            }
+        });
+    }
+
+    #[test]
+    fn fraction_answers_are_correct() {
+        for_each_rng(|difficulty, rng| {
+            let problem = FractionArithmetic::random(difficulty, rng);
+            let answer = problem.question().answer().parse::<Fraction>().unwrap();
+            let (a, b) = (problem.lhs.num(), problem.lhs.den());
+            let (c, d) = (problem.rhs.num(), problem.rhs.den());
+            // Cross multiply rather than reusing the Fraction operators.
+            let (num, den) = match problem.op {
+                Operation::Add => (a * d + c * b, b * d),
+                Operation::Sub => (a * d - c * b, b * d),
+                Operation::Mul => (a * c, b * d),
+                Operation::Div => (a * d, b * c),
+            };
+            assert_eq!(answer.num() * den, num * answer.den(), "{problem:?}");
+            assert_eq!(gcd(answer.num(), answer.den()).max(1), 1);
+        });
+    }
+
+    #[test]
+    fn differentiation_answers_are_correct() {
+        for_each_rng(|difficulty, rng| {
+            let problem = Differentiation::random(difficulty, rng);
+            let answer = problem.question().answer().parse::<Polynomial>().unwrap();
+            // Compare against a central difference of the original function.
+            let h = 1e-4;
+            for x in [-2.0, -0.5, 0.0, 1.0, 1.5] {
+                let f = &problem.function;
+                let numeric = (f.eval_f64(x + h) - f.eval_f64(x - h)) / (2.0 * h);
+                assert!((answer.eval_f64(x) - numeric).abs() < 1e-4, "{problem:?}");
+            }
+        });
+    }
+
+    #[test]
+    fn integration_answers_are_correct() {
+        for_each_rng(|difficulty, rng| {
+            let problem = Integration::random(difficulty, rng);
+            let answer = problem.question().answer().parse::<Fraction>().unwrap();
+            // Simpson's rule is exact for cubics and very close beyond that.
+            let steps = 1000;
+            let (a, b) = (problem.lower as f64, problem.upper as f64);
+            let h = (b - a) / steps as f64;
+            let f = |x: f64| problem.function.eval_f64(x);
+            let mut sum = f(a) + f(b);
+            for i in 1..steps {
+                let weight = if i % 2 == 0 { 2.0 } else { 4.0 };
+                sum += weight * f(a + i as f64 * h);
+            }
+            let numeric = sum * h / 3.0;
+            assert!((answer.to_f64() - numeric).abs() < 1e-6, "{problem:?}");
+        });
+    }
+
+    #[test]
+    fn generate_many_is_deterministic() {
+        for generator in Generator::all() {
+            let first = generator.generate_many(3, 5, 42);
+            assert_eq!(first.len(), 5);
+            assert_eq!(first, generator.generate_many(3, 5, 42));
+            assert_eq!(generator.name().parse::<Generator>(), Ok(generator));
+            for (i, question) in first.into_iter().enumerate() {
+                let response = Response::new(
+                    QuestionRef::new(1, i),
+                    &question,
+                    vec![question.answer().clone()],
+                );
+                assert!(response.is_correct(), "{question:?}");
+            }
+        }
+    }
+}
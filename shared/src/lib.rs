@@ -1,8 +1,24 @@
+pub mod adaptive;
+pub mod analytics;
+pub mod arrangements;
+pub mod assignments;
+pub mod attempts;
+pub mod calculator;
+pub mod classes;
 pub mod commands;
+pub mod generators;
+pub mod live;
+pub mod marking;
+pub mod mistakes;
+pub mod queries;
 pub mod questions;
 pub mod requests;
 pub mod responses;
+pub mod scheduling;
+pub mod sources;
+pub mod timing;
 
+use arrangements::AccessArrangements;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
@@ -31,6 +47,9 @@ pub struct User {
     date_of_birth: NaiveDate,
     access_level: AccessLevel,
     strikes: u8,
+    /// Exam access arrangements, which teachers and admins can change.
+    #[serde(default)]
+    arrangements: AccessArrangements,
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Serialize, Deserialize, Debug)]
@@ -60,6 +79,7 @@ impl User {
             date_of_birth,
             access_level,
             strikes: 0,
+            arrangements: AccessArrangements::default(),
         }
     }
 
@@ -86,4 +106,8 @@ impl User {
     pub fn access_level(&self) -> &AccessLevel {
         &self.access_level
     }
+
+    pub fn arrangements(&self) -> &AccessArrangements {
+        &self.arrangements
+    }
 }
@@ -0,0 +1,518 @@
+//! Live quizzes, which a teacher hosts from their own copy of the app
+//! for a class to sit together over the local network.
+//!
+//! The teacher's backend keeps the [`LiveSession`] and answers every
+//! [`LiveMessage`] sent to it by the students' backends. Questions are
+//! asked one at a time, and only move on when the teacher says so.
+
+use crate::{
+    questions::{Question, QuestionSet},
+    responses::{QuestionRef, QuizReview, Response},
+    sources::QuizSource,
+};
+use chrono::{DateTime, Utc};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// The port a live quiz is hosted on, unless the student gives another.
+pub const LIVE_PORT: u16 = 7878;
+/// How many characters long a join code is.
+pub const LIVE_CODE_LENGTH: usize = 6;
+/// How many characters long a player's token is. It is never typed in,
+/// so it can be long enough that it can't be guessed.
+pub const LIVE_TOKEN_LENGTH: usize = 20;
+/// Letters and digits that can't be mistaken for each other when read
+/// off the board.
+const CODE_CHARS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// A fresh join code for a session.
+pub fn live_code(seed: u64) -> String {
+    random_chars(&mut StdRng::seed_from_u64(seed), LIVE_CODE_LENGTH)
+}
+
+fn random_chars(rng: &mut StdRng, length: usize) -> String {
+    (0..length)
+        .map(|_| CODE_CHARS[rng.gen_range(0..CODE_CHARS.len())] as char)
+        .collect()
+}
+
+/// A live quiz being run by a teacher.
+#[derive(Debug, Clone)]
+pub struct LiveSession {
+    pub code: String,
+    pub set: QuestionSet,
+    /// The database ID of the set on the teacher's machine.
+    pub set_id: String,
+    pub started: DateTime<Utc>,
+    /// The question being asked, or nothing before the first one.
+    pub current: Option<usize>,
+    /// When the current question was put up.
+    pub shown: DateTime<Utc>,
+    pub finished: bool,
+    /// Everyone who has joined, in the order they did.
+    pub players: Vec<LivePlayer>,
+    pub answers: Vec<LiveAnswer>,
+    /// Draws the join code and each player's token.
+    rng: StdRng,
+}
+
+/// A student who has joined a session. Only their own backend is given
+/// their token, which it sends back with everything it asks, so no one
+/// else can answer or join under their name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LivePlayer {
+    pub username: String,
+    pub token: String,
+}
+
+/// One student's answer to one question.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveAnswer {
+    pub username: String,
+    pub response: Response,
+}
+
+/// What a student's backend asks of the host.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiveMessage {
+    pub code: String,
+    pub username: String,
+    /// The token the student was given when they joined. It can only be
+    /// left out when joining for the first time.
+    pub token: Option<String>,
+    pub request: LiveRequest,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LiveRequest {
+    Join,
+    /// Asks what is happening now, without changing anything.
+    Poll,
+    /// Answers the question at `question`, one submission per part.
+    Answer {
+        question: usize,
+        submissions: Vec<String>,
+    },
+}
+
+/// The session as one student sees it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveView {
+    /// The student's token, to send with everything else they ask.
+    pub token: String,
+    pub set_name: String,
+    pub total: usize,
+    pub current: Option<usize>,
+    pub question: Option<LiveQuestion>,
+    /// Whether the student has answered the current question.
+    pub answered: bool,
+    pub finished: bool,
+    /// The student's marks so far, out of those available so far.
+    pub score: (u32, u32),
+    /// The student's attempt, once the session has finished, for their
+    /// own copy of the app to save.
+    pub review: Option<QuizReview>,
+}
+
+/// A question as students see it while it is up. Every student's
+/// backend is sent this, so the answers, hints and worked solution are
+/// left out until the session has finished.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiveQuestion {
+    pub title: String,
+    pub markup: String,
+    pub calculator_allowed: bool,
+    pub parts: Vec<LivePart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LivePart {
+    pub prompt: String,
+    pub marks: u32,
+}
+
+impl From<&Question> for LiveQuestion {
+    fn from(question: &Question) -> Self {
+        LiveQuestion {
+            title: question.title().clone(),
+            markup: question.markup().clone(),
+            calculator_allowed: question.calculator_allowed(),
+            parts: question
+                .parts()
+                .into_iter()
+                .map(|part| LivePart {
+                    prompt: part.prompt,
+                    marks: part.marks,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The session as the teacher sees it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiveHostView {
+    pub code: String,
+    /// Where students can reach the session, filled in by the backend.
+    pub address: String,
+    pub set_name: String,
+    pub total: usize,
+    pub current: Option<usize>,
+    pub question: Option<Question>,
+    pub finished: bool,
+    pub players: Vec<String>,
+    /// How many have answered the current question.
+    pub answered: usize,
+    pub distribution: Vec<AnswerCount>,
+    pub leaderboard: Vec<LeaderboardEntry>,
+}
+
+/// How many students gave the same answer to a question.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnswerCount {
+    pub answer: String,
+    pub count: u32,
+    pub correct: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub username: String,
+    pub marks: u32,
+    pub correct: u32,
+}
+
+impl LiveSession {
+    /// Starts a session, with its join code drawn from `seed`, which
+    /// has to be unpredictable as it also decides every player's token.
+    pub fn new(set: QuestionSet, set_id: String, seed: u64) -> Self {
+        let now = Utc::now();
+        let mut rng = StdRng::seed_from_u64(seed);
+        LiveSession {
+            code: random_chars(&mut rng, LIVE_CODE_LENGTH),
+            set,
+            set_id,
+            started: now,
+            current: None,
+            shown: now,
+            finished: false,
+            players: vec![],
+            answers: vec![],
+            rng,
+        }
+    }
+
+    /// Lets a student into the session, and returns the token they have
+    /// to send from then on. Joining again is only allowed with the
+    /// token given the first time, so a name can't be taken over.
+    pub fn join(&mut self, username: &str, token: Option<&str>) -> Result<String, String> {
+        if self.finished {
+            return Err(String::from("this session has finished"));
+        }
+        if let Some(player) = self.player(username) {
+            return match token == Some(player.token.as_str()) {
+                true => Ok(player.token.clone()),
+                false => Err(String::from("someone has already joined with that name")),
+            };
+        }
+        let token = random_chars(&mut self.rng, LIVE_TOKEN_LENGTH);
+        self.players.push(LivePlayer {
+            username: username.to_owned(),
+            token: token.clone(),
+        });
+        Ok(token)
+    }
+
+    /// Checks that a message really came from the student it names.
+    pub fn check(&self, username: &str, token: Option<&str>) -> Result<(), String> {
+        match self.player(username) {
+            Some(player) if token == Some(player.token.as_str()) => Ok(()),
+            _ => Err(String::from("join the session first")),
+        }
+    }
+
+    fn player(&self, username: &str) -> Option<&LivePlayer> {
+        self.players
+            .iter()
+            .find(|player| player.username == username)
+    }
+
+    /// Marks a student's answer to the question being asked. Each
+    /// question can only be answered once, and only while it is up.
+    pub fn answer(
+        &mut self,
+        username: &str,
+        question: usize,
+        submissions: Vec<String>,
+    ) -> Result<(), String> {
+        if self.player(username).is_none() {
+            return Err(String::from("join the session before answering"));
+        }
+        if self.finished || self.current != Some(question) {
+            return Err(String::from("that question has closed"));
+        }
+        if self.answer_of(username, question).is_some() {
+            return Err(String::from("you have already answered that question"));
+        }
+        // Stray spaces would otherwise split the same answer in two on
+        // the teacher's screen.
+        let submissions = submissions.iter().map(|s| s.trim().to_owned()).collect();
+        let response = Response::new(
+            self.reference(question),
+            &self.set.questions()[question],
+            submissions,
+        )
+        .with_time_spent(self.shown, Utc::now());
+        self.answers.push(LiveAnswer {
+            username: username.to_owned(),
+            response,
+        });
+        Ok(())
+    }
+
+    /// Puts up the next question, or finishes after the last one.
+    pub fn advance(&mut self) {
+        let next = self.current.map_or(0, |i| i + 1);
+        if next < self.set.questions().len() {
+            self.current = Some(next);
+            self.shown = Utc::now();
+        } else {
+            self.finish();
+        }
+    }
+
+    /// Ends the session, leaving any questions not yet asked out of
+    /// everyone's results.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    fn reference(&self, question: usize) -> QuestionRef {
+        QuestionRef {
+            set_id: self.set_id.clone(),
+            ..QuestionRef::new(self.set.version(), question)
+        }
+    }
+
+    fn answer_of(&self, username: &str, question: usize) -> Option<&Response> {
+        self.answers
+            .iter()
+            .find(|answer| {
+                answer.username == username && answer.response.question().index == question
+            })
+            .map(|answer| &answer.response)
+    }
+
+    /// How many questions have been put up so far.
+    fn asked(&self) -> usize {
+        self.current.map_or(0, |i| i + 1)
+    }
+
+    /// A student's responses to every question asked so far, with a
+    /// blank answer for any they missed.
+    fn responses_of(&self, username: &str) -> Vec<Response> {
+        (0..self.asked())
+            .map(|i| match self.answer_of(username, i) {
+                Some(response) => response.clone(),
+                None => Response::new(self.reference(i), &self.set.questions()[i], vec![]),
+            })
+            .collect()
+    }
+
+    /// The student's attempt at the session, as a normal review.
+    pub fn review_of(&self, username: &str) -> QuizReview {
+        let mut review = QuizReview::new(
+            username.to_owned(),
+            self.set.name().clone(),
+            self.started,
+            self.responses_of(username),
+        )
+        .with_question_order((0..self.asked()).collect())
+        .from_source(QuizSource::Live(self.code.clone()));
+        review.set_id = self.set_id.clone();
+        review
+    }
+
+    pub fn view(&self, username: &str) -> LiveView {
+        let answered = self
+            .current
+            .is_some_and(|i| self.answer_of(username, i).is_some());
+        let review = self.review_of(username);
+        LiveView {
+            token: self
+                .player(username)
+                .map(|player| player.token.clone())
+                .unwrap_or_default(),
+            set_name: self.set.name().clone(),
+            total: self.set.questions().len(),
+            current: self.current.filter(|_| !self.finished),
+            question: self
+                .current
+                .filter(|_| !self.finished)
+                .map(|i| LiveQuestion::from(&self.set.questions()[i])),
+            answered,
+            finished: self.finished,
+            score: review.score(),
+            review: Some(review).filter(|_| self.finished),
+        }
+    }
+
+    /// The answers given to `question`, the most common first.
+    pub fn distribution(&self, question: usize) -> Vec<AnswerCount> {
+        let mut counts: Vec<AnswerCount> = vec![];
+        for answer in &self.answers {
+            let response = &answer.response;
+            if response.question().index != question {
+                continue;
+            }
+            let given = response.submitted();
+            match counts.iter_mut().find(|count| &count.answer == given) {
+                Some(count) => count.count += 1,
+                None => counts.push(AnswerCount {
+                    answer: given.clone(),
+                    count: 1,
+                    correct: response.is_correct(),
+                }),
+            }
+        }
+        counts.sort_by_key(|count| std::cmp::Reverse(count.count));
+        counts
+    }
+
+    /// Everyone who has joined, by marks and then by how many they got
+    /// right.
+    pub fn leaderboard(&self) -> Vec<LeaderboardEntry> {
+        let mut board = self
+            .players
+            .iter()
+            .map(|player| {
+                let answers = self
+                    .answers
+                    .iter()
+                    .filter(|answer| answer.username == player.username);
+                LeaderboardEntry {
+                    username: player.username.clone(),
+                    marks: answers
+                        .clone()
+                        .map(|answer| answer.response.marks_awarded())
+                        .sum(),
+                    correct: answers
+                        .filter(|answer| answer.response.is_correct())
+                        .count() as u32,
+                }
+            })
+            .collect::<Vec<_>>();
+        board.sort_by_key(|entry| std::cmp::Reverse((entry.marks, entry.correct)));
+        board
+    }
+
+    pub fn host_view(&self) -> LiveHostView {
+        let current = self.current.filter(|_| !self.finished);
+        LiveHostView {
+            code: self.code.clone(),
+            address: String::new(),
+            set_name: self.set.name().clone(),
+            total: self.set.questions().len(),
+            current,
+            question: current.map(|i| self.set.questions()[i].clone()),
+            finished: self.finished,
+            players: self
+                .players
+                .iter()
+                .map(|player| player.username.clone())
+                .collect(),
+            answered: current.map_or(0, |i| {
+                self.answers
+                    .iter()
+                    .filter(|answer| answer.response.question().index == i)
+                    .count()
+            }),
+            distribution: current.map(|i| self.distribution(i)).unwrap_or_default(),
+            leaderboard: self.leaderboard(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> LiveSession {
+        let questions = vec![
+            Question::new("Q1".into(), "1+1".into(), true, "2".into()),
+            Question::new("Q2".into(), "2+2".into(), true, "4".into()),
+        ];
+        let set = QuestionSet::new("Adding".into(), "teacher".into(), questions);
+        let mut session = LiveSession::new(set, "set".into(), 7);
+        for student in ["amy", "ben", "cal"] {
+            session.join(student, None).unwrap();
+        }
+        session
+    }
+
+    #[test]
+    fn names_are_kept_by_their_token() {
+        let mut session = session();
+        assert_eq!(session.code, live_code(7));
+        let token = session.view("amy").token;
+        assert_eq!(token.len(), LIVE_TOKEN_LENGTH);
+        assert_ne!(token, session.view("ben").token);
+
+        assert!(session.join("amy", None).is_err());
+        assert!(session.join("amy", Some("wrong")).is_err());
+        assert_eq!(session.join("amy", Some(&token)), Ok(token.clone()));
+        assert_eq!(session.players.len(), 3);
+
+        assert!(session.check("amy", Some(&token)).is_ok());
+        assert!(session.check("amy", None).is_err());
+        assert!(session.check("ben", Some(&token)).is_err());
+        assert!(session.check("dan", Some(&token)).is_err());
+    }
+
+    #[test]
+    fn questions_are_answered_in_lockstep() {
+        let mut session = session();
+        assert!(session.answer("amy", 0, vec!["2".into()]).is_err());
+
+        session.advance();
+        session.answer("amy", 0, vec!["2".into()]).unwrap();
+        assert!(session.answer("amy", 0, vec!["3".into()]).is_err());
+        assert!(session.answer("dan", 0, vec!["2".into()]).is_err());
+        session.answer("ben", 0, vec![" 2".into()]).unwrap();
+        session.answer("cal", 0, vec!["3".into()]).unwrap();
+
+        let distribution = session.distribution(0);
+        assert_eq!(distribution[0].answer, "2");
+        assert_eq!(distribution[0].count, 2);
+        assert!(distribution[0].correct && !distribution[1].correct);
+
+        session.advance();
+        // Students are only shown the question, not its answer.
+        let question = session.view("amy").question.unwrap();
+        assert_eq!(question.markup, "2+2");
+        assert_eq!(question.parts[0].marks, 1);
+        assert!(session.answer("amy", 0, vec!["2".into()]).is_err());
+        session.answer("ben", 1, vec!["4".into()]).unwrap();
+        session.advance();
+        assert!(session.finished);
+
+        let board = session.leaderboard();
+        assert_eq!(board[0].username, "ben");
+        assert_eq!(board[2].username, "cal");
+        assert_eq!(board[2].marks, 0);
+
+        // Missed questions are still in the review, as blank answers.
+        let review = session.view("amy").review.unwrap();
+        assert_eq!(review.responses.len(), 2);
+        assert_eq!(review.score(), (1, 2));
+        assert_eq!(review.responses[1].question().set_id, "set");
+    }
+
+    #[test]
+    fn codes_are_easy_to_read_out() {
+        let code = live_code(42);
+        assert_eq!(code.len(), LIVE_CODE_LENGTH);
+        assert!(code.bytes().all(|c| CODE_CHARS.contains(&c)));
+        assert_ne!(code, live_code(43));
+    }
+}
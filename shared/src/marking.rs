@@ -0,0 +1,186 @@
+//! Rules for deciding how many marks a submitted answer is worth.
+//!
+//! Every part of a [`Question`](crate::questions::Question) carries a
+//! [`GradingRule`], which is chosen by the author in the Creator. The
+//! rules are written to and read from short strings (such as
+//! `numeric:2`) so that they can be typed into a plain text box.
+
+use crate::generators::{Fraction, Polynomial};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GradingRule {
+    /// The answer must match character for character.
+    #[default]
+    Exact,
+    /// Case and whitespace are ignored.
+    Normalised,
+    /// Both answers are read as numbers (decimals or fractions) and
+    /// must agree when rounded to this many decimal places.
+    Numeric(u8),
+    /// A comma separated list where order doesn't matter. Marks are
+    /// awarded in proportion to how many items were correct.
+    AnyOrder,
+    /// Both answers are read as polynomials in `x` and must be equal
+    /// once like terms are collected.
+    Polynomial,
+}
+
+impl GradingRule {
+    /// Every rule, in the order they are listed in the Creator.
+    pub fn all() -> Vec<GradingRule> {
+        vec![
+            GradingRule::Exact,
+            GradingRule::Normalised,
+            GradingRule::Numeric(2),
+            GradingRule::AnyOrder,
+            GradingRule::Polynomial,
+        ]
+    }
+
+    /// Works out how many of the `marks` available the submission earns.
+    pub fn mark(&self, submitted: &str, answer: &str, marks: u32) -> u32 {
+        match self {
+            GradingRule::Exact => full_or_none(submitted == answer, marks),
+            GradingRule::Normalised => {
+                full_or_none(normalise(submitted) == normalise(answer), marks)
+            }
+            GradingRule::Numeric(places) => {
+                let close = match (parse_number(submitted), parse_number(answer)) {
+                    (Some(s), Some(a)) => (s - a).abs() < 0.5 * 10f64.powi(-(*places as i32)),
+                    _ => false,
+                };
+                full_or_none(close, marks)
+            }
+            GradingRule::AnyOrder => {
+                let mut expected = split_list(answer);
+                let total = expected.len() as u32;
+                if total == 0 {
+                    return full_or_none(normalise(submitted).is_empty(), marks);
+                }
+                let submitted = split_list(submitted);
+                // Anything extra that was submitted cancels out a correct item,
+                // so listing every possible value doesn't earn full marks.
+                let extra = submitted.len().saturating_sub(expected.len()) as u32;
+                let mut correct = 0u32;
+                for item in submitted {
+                    if let Some(i) = expected.iter().position(|e| *e == item) {
+                        expected.remove(i);
+                        correct += 1;
+                    }
+                }
+                marks * correct.saturating_sub(extra) / total
+            }
+            GradingRule::Polynomial => {
+                let same = match (
+                    submitted.parse::<Polynomial>(),
+                    answer.parse::<Polynomial>(),
+                ) {
+                    (Ok(s), Ok(a)) => s == a,
+                    _ => normalise(submitted) == normalise(answer),
+                };
+                full_or_none(same, marks)
+            }
+        }
+    }
+}
+
+fn full_or_none(correct: bool, marks: u32) -> u32 {
+    if correct {
+        marks
+    } else {
+        0
+    }
+}
+
+fn normalise(s: &str) -> String {
+    s.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn split_list(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(normalise)
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Reads a decimal or a fraction such as `-3/4`.
+pub fn parse_number(s: &str) -> Option<f64> {
+    let s = s.trim();
+    s.parse::<f64>()
+        .ok()
+        .or_else(|| s.parse::<Fraction>().ok().map(|f| f.to_f64()))
+}
+
+impl Display for GradingRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GradingRule::Exact => write!(f, "exact"),
+            GradingRule::Normalised => write!(f, "normalised"),
+            GradingRule::Numeric(places) => write!(f, "numeric:{places}"),
+            GradingRule::AnyOrder => write!(f, "any-order"),
+            GradingRule::Polynomial => write!(f, "polynomial"),
+        }
+    }
+}
+
+impl FromStr for GradingRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "exact" => Ok(GradingRule::Exact),
+            "normalised" => Ok(GradingRule::Normalised),
+            "any-order" => Ok(GradingRule::AnyOrder),
+            "polynomial" => Ok(GradingRule::Polynomial),
+            other => match other.strip_prefix("numeric:") {
+                Some(places) => places
+                    .parse()
+                    .map(GradingRule::Numeric)
+                    .map_err(|_| format!("bad decimal places: {places}")),
+                None => Err(format!("unknown grading rule: {other}")),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_round_trip_through_strings() {
+        for rule in GradingRule::all() {
+            assert_eq!(rule.to_string().parse::<GradingRule>(), Ok(rule));
+        }
+    }
+
+    #[test]
+    fn numeric_accepts_fractions_and_decimals() {
+        let rule = GradingRule::Numeric(2);
+        assert_eq!(rule.mark("0.75", "3/4", 2), 2);
+        assert_eq!(rule.mark("0.333", "1/3", 2), 2);
+        assert_eq!(rule.mark("0.34", "1/3", 2), 0);
+        assert_eq!(rule.mark("abc", "1/3", 2), 0);
+    }
+
+    #[test]
+    fn any_order_gives_partial_credit() {
+        let rule = GradingRule::AnyOrder;
+        assert_eq!(rule.mark("x=4, x=-3", "x=-3,x=4", 2), 2);
+        assert_eq!(rule.mark("x=4", "x=-3,x=4", 2), 1);
+        assert_eq!(rule.mark("x=4,x=-3,x=1", "x=-3,x=4", 2), 1);
+        assert_eq!(rule.mark("", "x=-3,x=4", 2), 0);
+    }
+
+    #[test]
+    fn polynomial_ignores_term_order() {
+        let rule = GradingRule::Polynomial;
+        assert_eq!(rule.mark("1 - 2x + 3x^2", "3x^2-2x+1", 1), 1);
+        assert_eq!(rule.mark("3x^2+2x+1", "3x^2-2x+1", 1), 0);
+    }
+}
@@ -0,0 +1,172 @@
+//! The mistake deck, which holds every question a student has got wrong
+//! until they show they can now answer it.
+
+use crate::{
+    questions::{has_topic, Question, QuestionSet},
+    responses::{QuestionRef, QuizReview},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How many correct answers in a row take a question out of the deck.
+pub const CORRECT_TO_CLEAR: u32 = 2;
+/// A question the student was sure of but got wrong needs one more
+/// correct answer than usual to leave the deck.
+pub const CONFIDENT_MISS_TO_CLEAR: u32 = CORRECT_TO_CLEAR + 1;
+/// The most questions put into one quiz from the deck.
+pub const DECK_QUIZ_LIMIT: usize = 20;
+
+/// Narrows the deck down to one set or topic. Empty fields match
+/// everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeckFilter {
+    #[serde(default)]
+    pub set_name: Option<String>,
+    /// A topic tag, which also matches any narrower tag under it.
+    #[serde(default)]
+    pub topic: Option<String>,
+}
+
+/// A question in the deck, along with the name of its set to show.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeckEntry {
+    pub question: QuestionRef,
+    pub set_name: String,
+}
+
+impl DeckFilter {
+    /// Whether `question` from `set` belongs in the filtered deck. The
+    /// topic can be tagged on either the question or its whole set.
+    pub fn matches(&self, set: &QuestionSet, question: &Question) -> bool {
+        let set_matches = self.set_name.as_ref().is_none_or(|name| name == set.name());
+        let topic_matches = self
+            .topic
+            .as_ref()
+            .is_none_or(|topic| has_topic(set.tags().iter().chain(question.tags()), topic));
+        set_matches && topic_matches
+    }
+}
+
+/// Every question in `reviews` that was answered wrongly and hasn't
+/// been answered correctly [`CORRECT_TO_CLEAR`] times in a row since,
+/// or [`CONFIDENT_MISS_TO_CLEAR`] if the student was sure of the wrong
+/// answer. The most recent mistakes come first.
+pub fn mistake_deck(reviews: &[QuizReview]) -> Vec<QuestionRef> {
+    let mut reviews = reviews.iter().collect::<Vec<_>>();
+    reviews.sort_by_key(|review| review.finished);
+
+    // Each question's latest reference, how many times in a row it has
+    // been right since it was last wrong, how many it needs to be, and
+    // when it was last wrong.
+    let mut deck = HashMap::new();
+    for review in reviews {
+        for response in &review.responses {
+            let question = response.question();
+            if question.set_id.is_empty() {
+                continue;
+            }
+            // A republished set may have moved its questions, so each
+            // version's questions are kept apart.
+            let key = (
+                question.set_id.clone(),
+                question.set_version,
+                question.index,
+            );
+            if !response.is_correct() {
+                let needed = match response.confident_miss() {
+                    true => CONFIDENT_MISS_TO_CLEAR,
+                    false => CORRECT_TO_CLEAR,
+                };
+                deck.insert(key, (question.clone(), 0, needed, review.finished));
+            } else if let Some(entry) = deck.get_mut(&key) {
+                entry.0 = question.clone();
+                entry.1 += 1;
+            }
+        }
+    }
+
+    let mut deck = deck
+        .into_values()
+        .filter(|(_, streak, needed, _)| streak < needed)
+        .collect::<Vec<_>>();
+    deck.sort_by_key(|(_, _, _, last_wrong)| std::cmp::Reverse(*last_wrong));
+    deck.into_iter().map(|(question, ..)| question).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::responses::{Confidence, Response};
+    use chrono::{Duration, Utc};
+
+    fn review(days_ago: i64, answers: &[&str]) -> QuizReview {
+        review_of_version(1, days_ago, answers)
+    }
+
+    fn review_of_version(version: u32, days_ago: i64, answers: &[&str]) -> QuizReview {
+        let questions = [
+            Question::new("Q1".into(), "1+1".into(), true, "2".into()),
+            Question::new("Q2".into(), "2+2".into(), true, "4".into()),
+        ];
+        let responses = questions
+            .iter()
+            .zip(answers)
+            .enumerate()
+            .map(|(i, (q, a))| {
+                let mut reference = QuestionRef::new(version, i);
+                reference.set_id = String::from("set");
+                Response::new(reference, q, vec![a.to_string()])
+            })
+            .collect();
+        let mut review = QuizReview::new("student".into(), "Adding".into(), Utc::now(), responses);
+        review.finished -= Duration::days(days_ago);
+        review
+    }
+
+    #[test]
+    fn questions_leave_after_two_correct_in_a_row() {
+        let indices = |reviews: &[QuizReview]| {
+            mistake_deck(reviews)
+                .iter()
+                .map(|q| q.index)
+                .collect::<Vec<_>>()
+        };
+        let mut reviews = vec![review(5, &["2", "5"])];
+        assert_eq!(indices(&reviews), vec![1]);
+
+        reviews.push(review(4, &["3", "4"]));
+        assert_eq!(indices(&reviews), vec![0, 1]);
+
+        // Out of order reviews are still read in the order they were sat.
+        reviews.insert(0, review(3, &["2", "4"]));
+        assert_eq!(indices(&reviews), vec![0]);
+
+        reviews.push(review(2, &["2", "0"]));
+        assert_eq!(indices(&reviews), vec![1]);
+    }
+
+    #[test]
+    fn confident_misses_take_longer_to_clear() {
+        let mut sure = review(4, &["2", "5"]);
+        let response = sure.responses[1].clone();
+        sure.responses[1] = response.with_confidence(Some(Confidence::Sure));
+        let mut reviews = vec![sure];
+        for days_ago in [3, 2] {
+            reviews.push(review(days_ago, &["2", "4"]));
+            assert_eq!(mistake_deck(&reviews).len(), 1);
+        }
+        reviews.push(review(1, &["2", "4"]));
+        assert!(mistake_deck(&reviews).is_empty());
+    }
+
+    #[test]
+    fn republished_questions_do_not_clear_old_mistakes() {
+        let mut reviews = vec![review(4, &["2", "5"])];
+        for days_ago in [3, 2] {
+            reviews.push(review_of_version(2, days_ago, &["2", "4"]));
+        }
+        let deck = mistake_deck(&reviews);
+        assert_eq!(deck.len(), 1);
+        assert_eq!((deck[0].set_version, deck[0].index), (1, 1));
+    }
+}
@@ -0,0 +1,176 @@
+//! Structs describing the filtered and sorted searches that the
+//! frontend can ask the backend to run. They are sent across as
+//! command arguments, so the database does the filtering instead
+//! of every set being shipped to the client first.
+
+use crate::questions::ExamLevel;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
+
+/// A search over the question sets, used by the Browser page.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SetQuery {
+    /// Matched against the set name and author, ignoring case.
+    pub search: String,
+    /// A set must match every tag here. A broad tag like `Algebra`
+    /// also matches narrower ones like `Algebra > Quadratics`.
+    pub tags: Vec<String>,
+    pub level: Option<ExamLevel>,
+    pub max_difficulty: Option<u8>,
+    pub sort: SetSort,
+    pub descending: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SetSort {
+    #[default]
+    Name,
+    Author,
+    Date,
+    Difficulty,
+}
+
+impl SetSort {
+    pub fn all() -> Vec<SetSort> {
+        vec![
+            SetSort::Name,
+            SetSort::Author,
+            SetSort::Date,
+            SetSort::Difficulty,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SetSort::Name => "Name",
+            SetSort::Author => "Author",
+            SetSort::Date => "Date",
+            SetSort::Difficulty => "Difficulty",
+        }
+    }
+}
+
+impl Display for SetSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for SetSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SetSort::all()
+            .into_iter()
+            .find(|sort| sort.name() == s)
+            .ok_or(format!("unknown sort: {s}"))
+    }
+}
+
+/// Whose quiz reviews to fetch. The backend checks the logged in
+/// user's access level before running anything wider than `Mine`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReviewScope {
+    /// Only the reviews belonging to whoever is logged in.
+    #[default]
+    Mine,
+    /// Reviews from students. Teachers only see the students enrolled
+    /// in their classes, while admins see every student.
+    Students,
+    /// Every review in the database. Admins only.
+    All,
+}
+
+impl ReviewScope {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReviewScope::Mine => "My Attempts",
+            ReviewScope::Students => "Students",
+            ReviewScope::All => "Everyone",
+        }
+    }
+}
+
+impl FromStr for ReviewScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [ReviewScope::Mine, ReviewScope::Students, ReviewScope::All]
+            .into_iter()
+            .find(|scope| scope.name() == s)
+            .ok_or(format!("unknown review scope: {s}"))
+    }
+}
+
+/// A search over the quiz reviews, used by the Review page. Every
+/// field is optional, and those left empty don't filter anything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReviewQuery {
+    /// Only reviews of the set with exactly this name.
+    pub set_name: Option<String>,
+    /// Only reviews from this student, within the scope being searched.
+    pub username: Option<String>,
+    /// Only reviews from students enrolled in the class with this name.
+    pub class_name: Option<String>,
+    /// Only reviews finished at or after this time.
+    pub from: Option<DateTime<Utc>>,
+    /// Only reviews finished before this time.
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// The most items the backend will send back in one page. This is
+/// enough for the longest listings, such as every set to choose from
+/// when setting homework.
+pub const MAX_PAGE_SIZE: u64 = 1000;
+
+/// Which slice of a long listing to fetch. Pages are numbered from zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Page {
+    pub index: u64,
+    pub size: u64,
+}
+
+impl Page {
+    pub fn new(index: u64, size: u64) -> Self {
+        Page { index, size }
+    }
+
+    /// The same page with its size kept between one and
+    /// [`MAX_PAGE_SIZE`], as the frontend can ask for any size.
+    pub fn clamped(self) -> Self {
+        Page {
+            size: self.size.clamp(1, MAX_PAGE_SIZE),
+            ..self
+        }
+    }
+
+    /// How many items come before this page.
+    pub fn skip(&self) -> u64 {
+        self.index.saturating_mul(self.size)
+    }
+}
+
+/// One page of results, along with how many results there are in total
+/// so that the frontend can draw page controls.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Paged<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+    pub page: Page,
+}
+
+impl<T> Paged<T> {
+    /// How many pages there are, which is always at least one.
+    pub fn page_count(&self) -> u64 {
+        self.total.div_ceil(self.page.size.max(1)).max(1)
+    }
+
+    pub fn has_next(&self) -> bool {
+        self.page.index + 1 < self.page_count()
+    }
+
+    pub fn has_previous(&self) -> bool {
+        self.page.index > 0
+    }
+}
@@ -1,13 +1,160 @@
+use crate::{marking::GradingRule, timing::TimeLimit};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
+
+/// The lowest difficulty a set or question can be given.
+pub const MIN_DIFFICULTY: u8 = 1;
+/// The highest difficulty a set or question can be given.
+pub const MAX_DIFFICULTY: u8 = 5;
+
+/// The separator between the levels of a topic tag, as in
+/// `Algebra > Quadratics`.
+pub const TAG_SEPARATOR: &str = " > ";
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct QuestionSet {
     name: String,
     author: String,
     questions: Vec<Question>,
+    /// How many marks are lost for each hint a student reveals.
+    /// Zero means hints are free.
+    #[serde(default)]
+    hint_penalty: u32,
+    /// Topic tags such as `Algebra > Quadratics`.
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    level: ExamLevel,
+    #[serde(default = "default_difficulty")]
+    difficulty: u8,
+    /// When the set was published. Sets from before this was
+    /// recorded read back as the Unix epoch.
+    #[serde(default)]
+    created: DateTime<Utc>,
+    /// Which revision of the set this is, so that a review can tell
+    /// exactly which questions were answered. Sets start at version 1,
+    /// and go up by one each time their author publishes them again.
+    #[serde(default = "first_version")]
+    version: u32,
+    /// An optional limit for sitting the set under exam conditions.
+    #[serde(default)]
+    time_limit: Option<TimeLimit>,
+    /// Whether the calculations students make on the built-in
+    /// calculator are saved with their answers.
+    #[serde(default)]
+    log_calculator: bool,
+    /// How long an unfinished attempt is kept for the student to resume.
+    #[serde(default = "default_resume_hours")]
+    resume_hours: u32,
+    /// Whether each attempt shows the questions in a different order.
+    #[serde(default)]
+    shuffle_questions: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+/// A lightweight view of a [`QuestionSet`] for listings, which leaves
+/// out the questions themselves. The backend builds these with a
+/// projection so the full sets never leave the database.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetSummary {
+    pub id: String,
+    pub name: String,
+    pub author: String,
+    pub question_count: u32,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub level: ExamLevel,
+    #[serde(default = "default_difficulty")]
+    pub difficulty: u8,
+    #[serde(default)]
+    pub created: DateTime<Utc>,
+}
+
+/// The qualification a set is aimed at.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExamLevel {
+    KS3,
+    #[default]
+    GCSE,
+    AS,
+    A,
+}
+
+impl ExamLevel {
+    pub fn all() -> Vec<ExamLevel> {
+        vec![ExamLevel::KS3, ExamLevel::GCSE, ExamLevel::AS, ExamLevel::A]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExamLevel::KS3 => "KS3",
+            ExamLevel::GCSE => "GCSE",
+            ExamLevel::AS => "AS-Level",
+            ExamLevel::A => "A-Level",
+        }
+    }
+}
+
+impl Display for ExamLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for ExamLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ExamLevel::all()
+            .into_iter()
+            .find(|l| l.name() == s)
+            .ok_or(format!("unknown exam level: {s}"))
+    }
+}
+
+fn default_difficulty() -> u8 {
+    MIN_DIFFICULTY
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+fn default_resume_hours() -> u32 {
+    24
+}
+
+/// Turns a typed list like `Algebra > Quadratics, Number` into tidy tags,
+/// dropping empty entries and normalising the spacing around `>`.
+pub fn parse_tags(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(|tag| {
+            tag.split('>')
+                .map(str::trim)
+                .filter(|level| !level.is_empty())
+                .collect::<Vec<_>>()
+                .join(TAG_SEPARATOR)
+        })
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// A tag along with every broader tag above it, so `Algebra > Quadratics`
+/// gives both `Algebra` and `Algebra > Quadratics`.
+pub fn tag_with_parents(tag: &str) -> Vec<String> {
+    let levels = tag.split(TAG_SEPARATOR).collect::<Vec<_>>();
+    (1..=levels.len())
+        .map(|n| levels[..n].join(TAG_SEPARATOR))
+        .collect()
+}
+
+/// Whether any of `tags` is `topic` or comes under it.
+pub fn has_topic<'a>(mut tags: impl Iterator<Item = &'a String>, topic: &str) -> bool {
+    tags.any(|tag| tag_with_parents(tag).iter().any(|t| t == topic))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct QuestionBuilder {
     inner: Vec<PartialQuestion>,
 }
@@ -28,6 +175,27 @@ impl QuestionBuilder {
         self.inner[i] = v;
     }
 
+    /// Appends already built questions (such as those from a
+    /// [`Generator`](crate::generators::Generator)) to the end of the
+    /// builder, first dropping any blank pages left at the end that
+    /// would otherwise stop the set from building.
+    pub fn append(&mut self, questions: Vec<Question>) {
+        while self.inner.last().is_some_and(|q| q.is_blank()) {
+            self.inner.pop();
+        }
+        self.inner
+            .extend(questions.into_iter().map(PartialQuestion::from));
+    }
+
+    /// How many pages there are, blank or not.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
     pub fn build(self) -> Option<Vec<Question>> {
         let mut out = Vec::with_capacity(self.inner.len());
         for v in self.inner {
@@ -41,12 +209,19 @@ impl QuestionBuilder {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct PartialQuestion {
     pub title: Option<String>,
     pub markup: Option<String>,
     pub calculator_allowed: Option<bool>,
     pub answer: Option<String>,
+    pub hints: Vec<String>,
+    pub solution: Vec<String>,
+    pub marks: u32,
+    pub grading: GradingRule,
+    pub parts: Vec<QuestionPart>,
+    pub tags: Vec<String>,
+    pub difficulty: u8,
 }
 
 impl PartialQuestion {
@@ -56,19 +231,56 @@ impl PartialQuestion {
             markup: None,
             calculator_allowed: None,
             answer: None,
+            hints: vec![],
+            solution: vec![],
+            marks: 1,
+            grading: GradingRule::Exact,
+            parts: vec![],
+            tags: vec![],
+            difficulty: MIN_DIFFICULTY,
         }
     }
 
+    /// Whether nothing has been typed into or changed on this question
+    /// yet. The Creator fills in its own default for the calculator on
+    /// every page it shows, so that doesn't count.
+    pub fn is_blank(&self) -> bool {
+        *self
+            == PartialQuestion {
+                calculator_allowed: self.calculator_allowed.filter(|&allowed| allowed),
+                ..PartialQuestion::new()
+            }
+    }
+
     pub fn build(self) -> Option<Question> {
+        // Multi-part questions don't need an overall answer, so a summary
+        // of the part answers is stored in its place.
+        let answer = match self.parts.is_empty() {
+            true => self.answer,
+            false => Some(
+                self.parts
+                    .iter()
+                    .map(|p| p.answer.clone())
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            ),
+        };
         if let Some(title) = self.title {
             if let Some(markup) = self.markup {
                 if let Some(calculator_allowed) = self.calculator_allowed {
-                    if let Some(answer) = self.answer {
+                    if let Some(answer) = answer {
                         return Some(Question {
                             title,
                             markup,
                             calculator_allowed,
                             answer,
+                            hints: self.hints,
+                            solution: self.solution,
+                            marks: self.marks,
+                            grading: self.grading,
+                            parts: self.parts,
+                            tags: self.tags,
+                            difficulty: self.difficulty,
                         });
                     }
                 }
@@ -78,15 +290,123 @@ impl PartialQuestion {
     }
 }
 
+impl From<Question> for PartialQuestion {
+    fn from(value: Question) -> Self {
+        PartialQuestion {
+            title: Some(value.title),
+            markup: Some(value.markup),
+            calculator_allowed: Some(value.calculator_allowed),
+            answer: Some(value.answer),
+            hints: value.hints,
+            solution: value.solution,
+            marks: value.marks,
+            grading: value.grading,
+            parts: value.parts,
+            tags: value.tags,
+            difficulty: value.difficulty,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Question {
     title: String,
     markup: String,
     calculator_allowed: bool,
     answer: String,
+    /// Hints which are revealed one at a time during a quiz.
+    #[serde(default)]
+    hints: Vec<String>,
+    /// The steps of the worked solution, shown on the Review page.
+    #[serde(default)]
+    solution: Vec<String>,
+    /// The marks for a single part question.
+    #[serde(default = "one_mark")]
+    marks: u32,
+    #[serde(default)]
+    grading: GradingRule,
+    /// The ordered sub-parts of an exam style question. When this is
+    /// empty the question is treated as one part using `answer`.
+    #[serde(default)]
+    parts: Vec<QuestionPart>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default = "default_difficulty")]
+    difficulty: u8,
+}
+
+fn one_mark() -> u32 {
+    1
+}
+
+/// A single part of a multi-part question, such as "(a) factorise".
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct QuestionPart {
+    pub prompt: String,
+    pub answer: String,
+    pub marks: u32,
+    pub grading: GradingRule,
+}
+
+impl QuestionPart {
+    pub fn new(prompt: String, answer: String, marks: u32, grading: GradingRule) -> Self {
+        QuestionPart {
+            prompt,
+            answer,
+            marks,
+            grading,
+        }
+    }
+
+    /// The letter used to label the part at `index`, e.g. `(a)`.
+    pub fn label(index: usize) -> String {
+        format!("({})", (b'a' + (index % 26) as u8) as char)
+    }
+}
+
+/// Parts are written in the Creator as `prompt | answer | marks | rule`,
+/// where the marks and rule can be left off.
+impl Display for QuestionPart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} | {} | {} | {}",
+            self.prompt, self.answer, self.marks, self.grading
+        )
+    }
+}
+
+impl FromStr for QuestionPart {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split('|').map(str::trim).collect::<Vec<_>>();
+        if fields.len() < 2 || fields.len() > 4 || fields[0].is_empty() || fields[1].is_empty() {
+            return Err(format!(
+                "expected 'prompt | answer | marks | rule', got: {s}"
+            ));
+        }
+        let marks = match fields.get(2) {
+            Some(m) => m.parse().map_err(|_| format!("bad mark count: {m}"))?,
+            None => 1,
+        };
+        let grading = match fields.get(3) {
+            Some(g) => g.parse()?,
+            None => GradingRule::Exact,
+        };
+        Ok(QuestionPart::new(
+            fields[0].to_owned(),
+            fields[1].to_owned(),
+            marks,
+            grading,
+        ))
+    }
 }
 
 impl QuestionSet {
+    /// The choices for how many hours an unfinished attempt is kept.
+    pub const RESUME_OPTIONS: [u32; 5] = [1, 6, 24, 72, 168];
+
     pub fn name(&self) -> &String {
         &self.name
     }
@@ -99,16 +419,165 @@ impl QuestionSet {
         &self.questions
     }
 
+    pub fn hint_penalty(&self) -> u32 {
+        self.hint_penalty
+    }
+
+    pub fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub fn level(&self) -> ExamLevel {
+        self.level
+    }
+
+    pub fn difficulty(&self) -> u8 {
+        self.difficulty
+    }
+
+    pub fn created(&self) -> &DateTime<Utc> {
+        &self.created
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn time_limit(&self) -> Option<TimeLimit> {
+        self.time_limit
+    }
+
+    pub fn log_calculator(&self) -> bool {
+        self.log_calculator
+    }
+
+    pub fn resume_hours(&self) -> u32 {
+        self.resume_hours
+    }
+
+    pub fn shuffle_questions(&self) -> bool {
+        self.shuffle_questions
+    }
+
     pub fn new(name: String, author: String, questions: Vec<Question>) -> Self {
         QuestionSet {
             name,
             author,
             questions,
+            hint_penalty: 0,
+            tags: vec![],
+            level: ExamLevel::default(),
+            difficulty: MIN_DIFFICULTY,
+            created: Utc::now(),
+            version: first_version(),
+            time_limit: None,
+            log_calculator: false,
+            resume_hours: default_resume_hours(),
+            shuffle_questions: false,
         }
     }
+
+    /// Makes this the next version of `older`, which it is replacing.
+    pub fn replacing(mut self, older: &QuestionSet) -> Self {
+        self.version = older.version + 1;
+        self
+    }
+
+    /// Credits the set to `author` in place of whoever it was built with.
+    pub fn with_author(mut self, author: String) -> Self {
+        self.author = author;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_level(mut self, level: ExamLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn with_difficulty(mut self, difficulty: u8) -> Self {
+        self.difficulty = difficulty.clamp(MIN_DIFFICULTY, MAX_DIFFICULTY);
+        self
+    }
+
+    pub fn with_hint_penalty(mut self, hint_penalty: u32) -> Self {
+        self.hint_penalty = hint_penalty;
+        self
+    }
+
+    pub fn with_time_limit(mut self, time_limit: Option<TimeLimit>) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+
+    pub fn with_calculator_logging(mut self, log_calculator: bool) -> Self {
+        self.log_calculator = log_calculator;
+        self
+    }
+
+    pub fn with_resume_hours(mut self, resume_hours: u32) -> Self {
+        self.resume_hours = resume_hours;
+        self
+    }
+
+    pub fn with_shuffled_questions(mut self, shuffle_questions: bool) -> Self {
+        self.shuffle_questions = shuffle_questions;
+        self
+    }
 }
 
 impl Question {
+    pub fn new(title: String, markup: String, calculator_allowed: bool, answer: String) -> Self {
+        Question {
+            title,
+            markup,
+            calculator_allowed,
+            answer,
+            hints: vec![],
+            solution: vec![],
+            marks: 1,
+            grading: GradingRule::Exact,
+            parts: vec![],
+            tags: vec![],
+            difficulty: MIN_DIFFICULTY,
+        }
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_difficulty(mut self, difficulty: u8) -> Self {
+        self.difficulty = difficulty.clamp(MIN_DIFFICULTY, MAX_DIFFICULTY);
+        self
+    }
+
+    pub fn with_marks(mut self, marks: u32, grading: GradingRule) -> Self {
+        self.marks = marks;
+        self.grading = grading;
+        self
+    }
+
+    pub fn with_parts(mut self, parts: Vec<QuestionPart>) -> Self {
+        self.parts = parts;
+        self
+    }
+
+    pub fn with_hints(mut self, hints: Vec<String>) -> Self {
+        self.hints = hints;
+        self
+    }
+
+    pub fn with_solution(mut self, solution: Vec<String>) -> Self {
+        self.solution = solution;
+        self
+    }
+
     // Ref-getter for the title field
     pub fn title(&self) -> &String {
         &self.title
@@ -125,4 +594,62 @@ impl Question {
     pub fn answer(&self) -> &String {
         &self.answer
     }
+
+    pub fn hints(&self) -> &Vec<String> {
+        &self.hints
+    }
+
+    pub fn solution(&self) -> &Vec<String> {
+        &self.solution
+    }
+
+    /// The parts a student has to answer, in order. Single part
+    /// questions come back as one unlabelled part.
+    pub fn parts(&self) -> Vec<QuestionPart> {
+        if self.parts.is_empty() {
+            vec![QuestionPart::new(
+                String::new(),
+                self.answer.clone(),
+                self.marks,
+                self.grading,
+            )]
+        } else {
+            self.parts.clone()
+        }
+    }
+
+    pub fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub fn difficulty(&self) -> u8 {
+        self.difficulty
+    }
+
+    pub fn total_marks(&self) -> u32 {
+        self.parts().iter().map(|p| p.marks).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_trailing_blank_pages_are_dropped() {
+        let mut builder = QuestionBuilder::new();
+        let mut hinted = builder.get(0);
+        hinted.hints = vec![String::from("Add them up")];
+        builder.set(0, hinted);
+        builder.get(1);
+        let mut shown = builder.get(2);
+        shown.calculator_allowed = Some(true);
+        builder.set(2, shown);
+
+        let generated = Question::new("Q".into(), "1+1".into(), true, "2".into());
+        builder.append(vec![generated]);
+        assert_eq!(builder.len(), 2);
+        assert_eq!(builder.get(0).hints.len(), 1);
+        assert_eq!(builder.get(1).answer.as_deref(), Some("2"));
+    }
 }
@@ -36,6 +36,23 @@ pub enum UserReqErrorKind {
     FetchReviewsError,
     DeleteQuestionsError,
     DeleteReviewError,
+    NotLoggedIn,
+    PermissionDenied,
+    ExportError,
+    AddClassError,
+    UpdateClassError,
+    DeleteClassError,
+    FetchClassesError,
+    AddAssignmentError,
+    FetchAssignmentsError,
+    DeleteAssignmentError,
+    UpdateArrangementsError,
+    SaveAttemptError,
+    FetchAttemptsError,
+    DeleteAttemptError,
+    BuildQuizError,
+    UpdateMemoryError,
+    LiveSessionError,
 }
 
 impl Display for UserReqError {
@@ -1,37 +1,411 @@
+use crate::{
+    adaptive::MasteryEstimate,
+    arrangements::AccessArrangements,
+    questions::{Question, QuestionSet},
+    sources::QuizSource,
+    timing::TimeLimit,
+};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt::Display;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct QuizReview {
     pub username: String,
+    /// The database ID of the set that was taken, which is filled in
+    /// by the backend when the review is saved.
+    #[serde(default)]
+    pub set_id: String,
+    /// The name of the set that was taken. Reviews saved before this
+    /// was recorded leave it blank.
+    #[serde(default)]
+    pub set_name: String,
+    /// When the first question was shown. Older reviews read back as
+    /// the Unix epoch.
+    #[serde(default)]
+    pub started: DateTime<Utc>,
+    /// When the last answer was submitted. Older reviews read back as
+    /// the Unix epoch.
+    #[serde(default)]
+    pub finished: DateTime<Utc>,
+    /// The total marks earned, stored so that the database can sort
+    /// and summarise attempts without going through every response.
+    #[serde(default)]
+    pub score: u32,
+    #[serde(default)]
+    pub max_score: u32,
+    /// The ID of the assignment this attempt was for, if it was set
+    /// as homework.
+    #[serde(default)]
+    pub assignment_id: Option<String>,
+    /// The time limit the attempt was sat under, if it was timed,
+    /// including any extra time.
+    #[serde(default)]
+    pub time_limit: Option<TimeLimit>,
+    /// The access arrangements the student had when they sat it.
+    #[serde(default)]
+    pub arrangements: AccessArrangements,
+    /// The index in the set of each question, in the order they were
+    /// shown. The responses are kept in this order too. Reviews saved
+    /// before this was recorded leave it empty.
+    #[serde(default)]
+    pub question_order: Vec<usize>,
+    #[serde(default)]
+    pub mode: QuizMode,
+    /// Where the questions came from. For anything but a single set,
+    /// each response's [`QuestionRef`] points at the set its question
+    /// came from, and the review has no set ID of its own.
+    #[serde(default)]
+    pub source: QuizSource,
+    /// How well the topic has been mastered, worked out by the backend
+    /// at the end of an adaptive quiz.
+    #[serde(default)]
+    pub mastery: Option<MasteryEstimate>,
     pub responses: Vec<Response>,
 }
 
+/// How a quiz was sat.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuizMode {
+    /// Answers are only marked once the whole quiz is handed in.
+    #[default]
+    Test,
+    /// Each answer is marked as soon as it is given, and a wrong answer
+    /// can be tried again. Practice is left out of assessment reports.
+    Practice,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct Response {
-    question: String,
+    question: QuestionRef,
+    /// A plain text copy of the question as it was shown, so the review
+    /// still reads correctly if the set is changed or deleted later.
+    snapshot: QuestionSnapshot,
     is_correct: bool,
     submitted: String,
     answer: String,
+    /// What was submitted for each part of the question.
+    #[serde(default)]
+    parts: Vec<PartResponse>,
+    /// Marks earned before any hint penalty is taken off.
+    #[serde(default)]
+    marks_awarded: u32,
+    /// Reviews saved before marks existed leave this as zero, and
+    /// are treated as being worth a single mark.
+    #[serde(default)]
+    marks_available: u32,
+    #[serde(default)]
+    hints_used: u32,
+    /// The marks taken off for the hints that were used.
+    #[serde(default)]
+    hint_penalty: u32,
+    #[serde(default)]
+    solution: Vec<String>,
+    /// How long was spent on the question, in milliseconds.
+    #[serde(default)]
+    time_spent: u64,
+    /// Whether time ran out and the answer was handed in automatically.
+    #[serde(default)]
+    timed_out: bool,
+    /// Each calculation made on the built-in calculator while answering,
+    /// such as `3^2 + 4^2 = 25`, if the set logs them.
+    #[serde(default)]
+    calculator_log: Vec<String>,
+    /// How many times the answer was checked in practice mode.
+    #[serde(default)]
+    tries: u32,
+    /// How sure the student said they were of the answer, if they said.
+    #[serde(default)]
+    confidence: Option<Confidence>,
+}
+
+/// Points at the exact question that a [`Response`] answers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestionRef {
+    /// The database ID of the set, which is filled in by the backend
+    /// when the review is saved.
+    pub set_id: String,
+    pub set_version: u32,
+    /// Where the question is in the set, counting from zero.
+    pub index: usize,
+}
+
+/// The text of a question as the student saw it. This is only ever
+/// shown as plain text, never interpreted as HTML.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestionSnapshot {
+    pub title: String,
+    pub markup: String,
+}
+
+impl QuizMode {
+    /// How many more times a wrong answer can be tried in practice.
+    pub const PRACTICE_RETRIES: u32 = 2;
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            QuizMode::Test => "Test",
+            QuizMode::Practice => "Practice",
+        }
+    }
+}
+
+impl Display for QuizMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// How sure a student is of an answer, rated as they give it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Confidence {
+    Guess,
+    Unsure,
+    Sure,
+}
+
+impl Confidence {
+    pub fn all() -> Vec<Confidence> {
+        vec![Confidence::Guess, Confidence::Unsure, Confidence::Sure]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Confidence::Guess => "Guess",
+            Confidence::Unsure => "Unsure",
+            Confidence::Sure => "Sure",
+        }
+    }
+
+    /// The share of answers rated this way that a well calibrated
+    /// student would get right.
+    pub fn expected_accuracy(&self) -> f64 {
+        match self {
+            Confidence::Guess => 0.25,
+            Confidence::Unsure => 0.6,
+            Confidence::Sure => 0.9,
+        }
+    }
+}
+
+impl Display for Confidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl QuestionRef {
+    pub fn new(set_version: u32, index: usize) -> Self {
+        QuestionRef {
+            set_id: String::new(),
+            set_version,
+            index,
+        }
+    }
+}
+
+impl QuestionSnapshot {
+    pub fn of(question: &Question) -> Self {
+        QuestionSnapshot {
+            title: question.title().clone(),
+            markup: question.markup().clone(),
+        }
+    }
+
+    /// Recovers the text from the `<p>title</p><p>markup</p>` string
+    /// that older reviews stored, dropping any formatting tags along the
+    /// way.
+    pub fn from_legacy_html(html: &str) -> Self {
+        let mut paragraphs = html
+            .split("</p>")
+            .map(strip_tags)
+            .filter(|text| !text.is_empty());
+        QuestionSnapshot {
+            title: paragraphs.next().unwrap_or_default(),
+            markup: paragraphs.collect::<Vec<_>>().join(" "),
+        }
+    }
+}
+
+/// The formatting tags that older reviews could contain. Anything else
+/// between `<` and `>` is kept, as the questions themselves were never
+/// escaped and often hold inequalities like `x<5 and y>2`.
+const LEGACY_TAGS: [&str; 13] = [
+    "<p>",
+    "</p>",
+    "<br>",
+    "<br/>",
+    "<br />",
+    "<b>",
+    "</b>",
+    "<i>",
+    "</i>",
+    "<em>",
+    "</em>",
+    "<strong>",
+    "</strong>",
+];
+
+fn strip_tags(html: &str) -> String {
+    let text = LEGACY_TAGS
+        .iter()
+        .fold(html.to_owned(), |text, tag| text.replace(tag, ""));
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+        .trim()
+        .to_owned()
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct PartResponse {
+    pub prompt: String,
+    pub submitted: String,
+    pub answer: String,
+    pub marks_awarded: u32,
+    pub marks_available: u32,
 }
 
 impl Response {
-    pub fn new(question: String, submitted: String, answer: String) -> Response {
+    /// Marks the answers submitted for each part of `marked`, in
+    /// order. Any missing submissions are marked as blank answers.
+    pub fn new(question: QuestionRef, marked: &Question, submissions: Vec<String>) -> Response {
+        let parts = marked
+            .parts()
+            .into_iter()
+            .enumerate()
+            .map(|(i, part)| {
+                let submitted = submissions.get(i).cloned().unwrap_or_default();
+                PartResponse {
+                    marks_awarded: part.grading.mark(&submitted, &part.answer, part.marks),
+                    marks_available: part.marks,
+                    prompt: part.prompt,
+                    submitted,
+                    answer: part.answer,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let marks_awarded = parts.iter().map(|p| p.marks_awarded).sum();
+        let marks_available = parts.iter().map(|p| p.marks_available).sum();
+        let join = |f: fn(&PartResponse) -> &String| {
+            parts.iter().map(f).cloned().collect::<Vec<_>>().join("; ")
+        };
+
         Response {
             question,
-            is_correct: submitted == answer,
-            submitted,
-            answer,
+            snapshot: QuestionSnapshot::of(marked),
+            is_correct: marks_awarded == marks_available,
+            submitted: join(|p| &p.submitted),
+            answer: join(|p| &p.answer),
+            parts,
+            marks_awarded,
+            marks_available,
+            hints_used: 0,
+            hint_penalty: 0,
+            solution: vec![],
+            time_spent: 0,
+            timed_out: false,
+            calculator_log: vec![],
+            tries: 0,
+            confidence: None,
         }
     }
 
+    /// Marks the same submissions again against `marked`, keeping how
+    /// the question was answered but none of the marks that came with
+    /// it. Hints are charged at `penalty_per_hint`, and only as many as
+    /// the question has can have been used.
+    pub fn remarked(self, marked: &Question, penalty_per_hint: u32) -> Self {
+        let submissions = self.parts.iter().map(|p| p.submitted.clone()).collect();
+        let hints_used = self.hints_used.min(marked.hints().len() as u32);
+        Response {
+            time_spent: self.time_spent,
+            timed_out: self.timed_out,
+            calculator_log: self.calculator_log,
+            tries: self.tries,
+            confidence: self.confidence,
+            ..Response::new(self.question, marked, submissions)
+        }
+        .with_hints(hints_used, penalty_per_hint)
+        .with_solution(marked.solution().clone())
+    }
+
+    /// Records how many hints were revealed, and the penalty that
+    /// the set charges for each one.
+    pub fn with_hints(mut self, hints_used: u32, penalty_per_hint: u32) -> Self {
+        self.hints_used = hints_used;
+        self.hint_penalty = hints_used * penalty_per_hint;
+        self
+    }
+
+    pub fn with_solution(mut self, solution: Vec<String>) -> Self {
+        self.solution = solution;
+        self
+    }
+
+    /// Records the time between the question being shown and the
+    /// last of its answers being submitted.
+    pub fn with_time_spent(mut self, shown: DateTime<Utc>, answered: DateTime<Utc>) -> Self {
+        self.time_spent = (answered - shown).num_milliseconds().max(0) as u64;
+        self
+    }
+
+    /// Marks the answer as one that was handed in when time ran out.
+    pub fn out_of_time(mut self) -> Self {
+        self.timed_out = true;
+        self
+    }
+
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    pub fn with_calculator_log(mut self, calculator_log: Vec<String>) -> Self {
+        self.calculator_log = calculator_log;
+        self
+    }
+
+    pub fn calculator_log(&self) -> &Vec<String> {
+        &self.calculator_log
+    }
+
+    pub fn with_confidence(mut self, confidence: Option<Confidence>) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    pub fn confidence(&self) -> Option<Confidence> {
+        self.confidence
+    }
+
+    /// Whether the student was sure of an answer that turned out wrong,
+    /// which shows a misunderstanding rather than a gap.
+    pub fn confident_miss(&self) -> bool {
+        self.confidence == Some(Confidence::Sure) && !self.is_correct
+    }
+
+    pub fn with_tries(mut self, tries: u32) -> Self {
+        self.tries = tries;
+        self
+    }
+
+    pub fn tries(&self) -> u32 {
+        self.tries
+    }
+
     pub fn is_correct(&self) -> bool {
         self.is_correct
     }
 
-    pub fn question(&self) -> &String {
+    pub fn question(&self) -> &QuestionRef {
         &self.question
     }
 
+    pub fn snapshot(&self) -> &QuestionSnapshot {
+        &self.snapshot
+    }
+
     pub fn submitted(&self) -> &String {
         &self.submitted
     }
@@ -39,13 +413,203 @@ impl Response {
     pub fn answer(&self) -> &String {
         &self.answer
     }
+
+    pub fn hints_used(&self) -> u32 {
+        self.hints_used
+    }
+
+    pub fn hint_penalty(&self) -> u32 {
+        self.hint_penalty
+    }
+
+    pub fn solution(&self) -> &Vec<String> {
+        &self.solution
+    }
+
+    pub fn parts(&self) -> &Vec<PartResponse> {
+        &self.parts
+    }
+
+    /// How long was spent on the question. Reviews from before this
+    /// was recorded give zero.
+    pub fn time_spent(&self) -> Duration {
+        Duration::milliseconds(self.time_spent as i64)
+    }
+
+    /// The marks earned once any hint penalty has been taken off.
+    pub fn marks_awarded(&self) -> u32 {
+        if self.marks_available == 0 {
+            self.is_correct as u32
+        } else {
+            self.marks_awarded.saturating_sub(self.hint_penalty)
+        }
+    }
+
+    pub fn marks_available(&self) -> u32 {
+        self.marks_available.max(1)
+    }
 }
 
 impl QuizReview {
-    pub fn new(username: String, responses: Vec<Response>) -> Self {
-        QuizReview {
+    /// A review of an attempt at `set_name` which began at `started`
+    /// and has just been finished.
+    pub fn new(
+        username: String,
+        set_name: String,
+        started: DateTime<Utc>,
+        responses: Vec<Response>,
+    ) -> Self {
+        let mut review = QuizReview {
             username,
+            set_id: String::new(),
+            set_name,
+            started,
+            finished: Utc::now(),
+            score: 0,
+            max_score: 0,
+            assignment_id: None,
+            time_limit: None,
+            arrangements: AccessArrangements::default(),
+            question_order: vec![],
+            mode: QuizMode::default(),
+            source: QuizSource::default(),
+            mastery: None,
             responses,
+        };
+        (review.score, review.max_score) = review.score();
+        review
+    }
+
+    pub fn for_assignment(mut self, assignment_id: Option<String>) -> Self {
+        self.assignment_id = assignment_id;
+        self
+    }
+
+    pub fn with_time_limit(mut self, time_limit: Option<TimeLimit>) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+
+    pub fn with_question_order(mut self, question_order: Vec<usize>) -> Self {
+        self.question_order = question_order;
+        self
+    }
+
+    pub fn in_mode(mut self, mode: QuizMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn from_source(mut self, source: QuizSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Whether the questions were shown in a different order to the set.
+    pub fn shuffled(&self) -> bool {
+        self.question_order.iter().enumerate().any(|(i, &q)| i != q)
+    }
+
+    /// Whether any answer was handed in because time ran out.
+    pub fn timed_out(&self) -> bool {
+        self.responses.iter().any(|r| r.timed_out)
+    }
+
+    /// Records the database ID of the set that was taken, both on the
+    /// review and on each of its responses.
+    pub fn link_to_set(&mut self, set_id: String) {
+        for response in &mut self.responses {
+            response.question.set_id = set_id.clone();
+        }
+        self.set_id = set_id;
+    }
+
+    /// Marks every response again against the questions in `set`, and
+    /// totals the score from those marks. Each response has to be to a
+    /// question in this version of the set.
+    pub fn remark(&mut self, set: &QuestionSet) -> Result<(), String> {
+        let responses = std::mem::take(&mut self.responses);
+        for response in responses {
+            let question = response.question();
+            let marked = match question.set_version == set.version() {
+                true => set.questions().get(question.index),
+                false => None,
+            }
+            .ok_or(String::from("the set has changed since the quiz began"))?;
+            self.responses
+                .push(response.remarked(marked, set.hint_penalty()));
         }
+        (self.score, self.max_score) = self.score();
+        Ok(())
+    }
+
+    /// The total marks awarded and available across every response.
+    pub fn score(&self) -> (u32, u32) {
+        self.responses
+            .iter()
+            .fold((0, 0), |(awarded, available), r| {
+                (awarded + r.marks_awarded(), available + r.marks_available())
+            })
+    }
+
+    /// How long the whole attempt took, if the start was recorded.
+    pub fn duration(&self) -> Option<Duration> {
+        match self.started.timestamp() {
+            0 => None,
+            _ => Some(self.finished - self.started),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_html_becomes_plain_text() {
+        let snapshot = QuestionSnapshot::from_legacy_html("<p>Solve</p><p>2x+1=5</p>");
+        assert_eq!(snapshot.title, "Solve");
+        assert_eq!(snapshot.markup, "2x+1=5");
+
+        let snapshot =
+            QuestionSnapshot::from_legacy_html("<p><b>Shade</b></p><p>x<5 and y>2<br></p>");
+        assert_eq!(snapshot.title, "Shade");
+        assert_eq!(snapshot.markup, "x<5 and y>2");
+
+        let snapshot = QuestionSnapshot::from_legacy_html("<p>Q</p><p>a &lt; b &amp;&amp; c</p>");
+        assert_eq!(snapshot.markup, "a < b && c");
+    }
+
+    #[test]
+    fn remarking_ignores_the_marks_sent() {
+        let questions = vec![
+            Question::new("Q1".into(), "1+1".into(), true, "2".into()),
+            Question::new("Q2".into(), "2+2".into(), true, "4".into()),
+        ];
+        let set = QuestionSet::new("Adding".into(), "teacher".into(), questions.clone());
+        let responses = questions
+            .iter()
+            .enumerate()
+            .map(|(i, q)| {
+                let mut response = Response::new(QuestionRef::new(1, i), q, vec!["4".into()])
+                    .with_hints(3, 0)
+                    .with_tries(2);
+                response.is_correct = true;
+                response.marks_awarded = 1;
+                response
+            })
+            .collect();
+        let mut review = QuizReview::new("amy".into(), "Adding".into(), Utc::now(), responses);
+        review.score = 2;
+
+        review.remark(&set).unwrap();
+        assert_eq!((review.score, review.max_score), (1, 2));
+        assert!(!review.responses[0].is_correct() && review.responses[1].is_correct());
+        assert_eq!(review.responses[0].hints_used(), 0);
+        assert_eq!(review.responses[0].tries(), 2);
+
+        let mut stale = review.clone();
+        stale.responses[1].question.set_version = 2;
+        assert!(stale.remark(&set).is_err());
     }
 }
@@ -0,0 +1,127 @@
+//! Spaced repetition, which brings each question back for review just
+//! as the student is likely to start forgetting it.
+//!
+//! The schedule follows the SM-2 algorithm. Every answer is graded for
+//! how well it was recalled, from 0 for a blank to 5 for a confident
+//! correct answer. Good recall pushes the next review further away each
+//! time, while poor recall starts the question over from tomorrow.
+
+use crate::responses::{Confidence, QuestionRef, Response};
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The most questions put into one daily review.
+pub const DAILY_REVIEW_LIMIT: usize = 20;
+
+/// How easy a new question is assumed to be.
+const STARTING_EASE: f64 = 2.5;
+/// Questions never get harder than this, so they don't come back
+/// every single day forever.
+const MINIMUM_EASE: f64 = 1.3;
+
+/// How well a student remembers one question.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryState {
+    pub username: String,
+    pub question: QuestionRef,
+    /// How quickly the gap between reviews grows.
+    pub ease: f64,
+    /// Days between the last review and the next one.
+    pub interval: u32,
+    /// Reviews in a row with good recall.
+    pub repetitions: u32,
+    pub due: DateTime<Utc>,
+}
+
+impl MemoryState {
+    /// A question the student has only just met, which is due straight
+    /// away.
+    pub fn new(username: String, question: QuestionRef, now: DateTime<Utc>) -> Self {
+        MemoryState {
+            username,
+            question,
+            ease: STARTING_EASE,
+            interval: 0,
+            repetitions: 0,
+            due: now,
+        }
+    }
+
+    /// Reschedules the question after an answer recalled with `quality`
+    /// from 0 to 5.
+    pub fn review(&mut self, quality: u8, now: DateTime<Utc>) {
+        let quality = quality.min(5);
+        if quality >= 3 {
+            self.interval = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (self.interval as f64 * self.ease).round() as u32,
+            };
+            self.repetitions += 1;
+        } else {
+            self.interval = 1;
+            self.repetitions = 0;
+        }
+        let miss = (5 - quality) as f64;
+        self.ease = (self.ease + 0.1 - miss * (0.08 + miss * 0.02)).max(MINIMUM_EASE);
+        self.due = now + Duration::days(self.interval as i64);
+    }
+
+    /// Whether the question should be reviewed at some point today.
+    pub fn due_today(&self, now: DateTime<Utc>) -> bool {
+        self.due < end_of_day(now)
+    }
+}
+
+/// How well a response shows the question was remembered, from 0 to 5.
+/// Needing hints or another try, or only guessing, counts as a struggle
+/// to recall it. Being sure of a wrong answer is treated as badly as
+/// leaving it blank, as something has been remembered wrongly.
+pub fn recall_quality(response: &Response) -> u8 {
+    let struggled = response.hints_used() > 0
+        || response.tries() > 1
+        || response.confidence() == Some(Confidence::Guess);
+    match (response.is_correct(), struggled) {
+        (true, false) => 5,
+        (true, true) => 3,
+        (false, _) if response.confident_miss() => 0,
+        (false, _) if response.marks_awarded() > 0 => 2,
+        (false, _) if response.submitted().trim().is_empty() => 0,
+        (false, _) => 1,
+    }
+}
+
+/// Midnight at the end of the day `now` falls in.
+pub fn end_of_day(now: DateTime<Utc>) -> DateTime<Utc> {
+    (now.date_naive() + Duration::days(1))
+        .and_time(NaiveTime::MIN)
+        .and_utc()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn good_recall_spaces_reviews_out() {
+        let now = Utc::now();
+        let mut state = MemoryState::new(String::from("student"), QuestionRef::new(1, 0), now);
+        assert!(state.due_today(now));
+
+        state.review(5, now);
+        assert_eq!(state.interval, 1);
+        state.review(5, now);
+        assert_eq!(state.interval, 6);
+        state.review(4, now);
+        assert_eq!(state.interval, 16);
+        assert_eq!(state.due, now + Duration::days(16));
+        assert!(!state.due_today(now));
+
+        state.review(1, now);
+        assert_eq!((state.interval, state.repetitions), (1, 0));
+        for _ in 0..10 {
+            state.review(0, now);
+        }
+        assert_eq!(state.ease, MINIMUM_EASE);
+    }
+}
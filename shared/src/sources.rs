@@ -0,0 +1,168 @@
+//! Quizzes that aren't a single question set, but are put together by
+//! the backend from questions in several sets.
+
+use crate::{
+    arrangements::QuizConditions,
+    mistakes::DeckFilter,
+    questions::{has_topic, Question, QuestionSet, MAX_DIFFICULTY, MIN_DIFFICULTY},
+    responses::QuestionRef,
+    timing::TimeLimit,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// The most questions a student can put into a quiz of their own.
+pub const CUSTOM_QUIZ_MAX: u32 = 50;
+
+/// Where the questions for a quiz come from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuizSource {
+    /// A single question set, named on the review.
+    #[default]
+    Set,
+    /// The spaced repetition questions due for review today.
+    DailyReview,
+    /// The questions in the student's mistake deck.
+    MistakeDeck(DeckFilter),
+    /// Questions on a topic, each picked to suit how the student is
+    /// doing so far.
+    Adaptive(String),
+    /// A quiz the student put together themselves.
+    Custom(CustomQuiz),
+    /// A set sat together by a class, one question at a time, in the
+    /// live session with this join code.
+    Live(String),
+}
+
+/// A random draw of questions from several sets or topics, chosen in
+/// the quiz builder.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomQuiz {
+    /// Every question in these sets can be drawn.
+    pub set_names: Vec<String>,
+    /// Every question on these topics can be drawn, from any set.
+    pub tags: Vec<String>,
+    pub count: u32,
+    pub min_difficulty: u8,
+    pub max_difficulty: u8,
+    pub time_limit: Option<TimeLimit>,
+    /// Decides which questions are drawn, and in what order.
+    pub seed: u64,
+}
+
+/// Everything needed to sit a quiz.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuiltQuiz {
+    /// The questions to ask, in a set that only exists for this quiz
+    /// when they were gathered from several sets.
+    pub set: QuestionSet,
+    /// The set and place in it that each question came from. This is
+    /// left empty for a single set, whose questions are referred to by
+    /// their place in `set`.
+    pub origins: Vec<QuestionRef>,
+    pub conditions: QuizConditions,
+}
+
+impl QuizSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            QuizSource::Set => "Question Set",
+            QuizSource::DailyReview => "Daily Review",
+            QuizSource::MistakeDeck(_) => "Mistake Deck",
+            QuizSource::Adaptive(_) => "Adaptive Quiz",
+            QuizSource::Custom(_) => "Custom Quiz",
+            QuizSource::Live(_) => "Live Quiz",
+        }
+    }
+}
+
+impl CustomQuiz {
+    /// Checks the choices made in the quiz builder.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.set_names.is_empty() && self.tags.is_empty() {
+            return Err(String::from("choose at least one set or topic"));
+        }
+        if !(1..=CUSTOM_QUIZ_MAX).contains(&self.count) {
+            return Err(format!("a quiz can have 1 to {CUSTOM_QUIZ_MAX} questions"));
+        }
+        let range = MIN_DIFFICULTY..=MAX_DIFFICULTY;
+        if !range.contains(&self.min_difficulty)
+            || !range.contains(&self.max_difficulty)
+            || self.min_difficulty > self.max_difficulty
+        {
+            return Err(String::from("the difficulty range is not valid"));
+        }
+        Ok(())
+    }
+
+    /// Whether `question` from `set` can be drawn for the quiz.
+    pub fn wants(&self, set: &QuestionSet, question: &Question) -> bool {
+        let chosen = self.set_names.contains(set.name())
+            || self
+                .tags
+                .iter()
+                .any(|tag| has_topic(set.tags().iter().chain(question.tags()), tag));
+        let difficulty = self.min_difficulty..=self.max_difficulty;
+        chosen && difficulty.contains(&question.difficulty())
+    }
+
+    /// Draws the quiz's questions at random from everything it wants.
+    pub fn draw<T>(&self, mut pool: Vec<T>) -> Vec<T> {
+        pool.shuffle(&mut StdRng::seed_from_u64(self.seed));
+        pool.truncate(self.count as usize);
+        pool
+    }
+}
+
+impl BuiltQuiz {
+    /// Where the question at `index` in the quiz came from.
+    pub fn origin(&self, index: usize) -> QuestionRef {
+        self.origins
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| QuestionRef::new(self.set.version(), index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_quizzes_draw_matching_questions() {
+        let question = |difficulty, tag: &str| {
+            Question::new("Q".into(), "1+1".into(), true, "2".into())
+                .with_difficulty(difficulty)
+                .with_tags(vec![tag.to_owned()])
+        };
+        let set = QuestionSet::new(
+            "Mixed".into(),
+            "teacher".into(),
+            vec![
+                question(1, "Algebra > Quadratics"),
+                question(4, "Trigonometry"),
+            ],
+        );
+        let mut custom = CustomQuiz {
+            set_names: vec![],
+            tags: vec![String::from("Algebra")],
+            count: 1,
+            min_difficulty: 1,
+            max_difficulty: 5,
+            time_limit: None,
+            seed: 0,
+        };
+        assert!(custom.validate().is_ok());
+        assert!(custom.wants(&set, &set.questions()[0]));
+        assert!(!custom.wants(&set, &set.questions()[1]));
+
+        custom.set_names.push(String::from("Mixed"));
+        custom.min_difficulty = 2;
+        assert!(!custom.wants(&set, &set.questions()[0]));
+        assert!(custom.wants(&set, &set.questions()[1]));
+
+        assert_eq!(custom.draw((0..10).collect()).len(), 1);
+        custom.count = 0;
+        assert!(custom.validate().is_err());
+    }
+}
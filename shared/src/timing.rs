@@ -0,0 +1,96 @@
+//! Time limits for sitting a set under exam conditions.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
+
+/// How long a student has to answer, either for the whole quiz or
+/// for each question in turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeLimit {
+    Overall { seconds: u32 },
+    PerQuestion { seconds: u32 },
+}
+
+impl TimeLimit {
+    /// The limits that teachers can pick from.
+    pub fn presets() -> Vec<TimeLimit> {
+        let per_question = [30, 60, 120].map(|seconds| TimeLimit::PerQuestion { seconds });
+        let overall = [10, 20, 30, 45, 60, 90].map(|minutes| TimeLimit::Overall {
+            seconds: minutes * 60,
+        });
+        per_question.into_iter().chain(overall).collect()
+    }
+
+    pub fn seconds(&self) -> u32 {
+        match self {
+            TimeLimit::Overall { seconds } | TimeLimit::PerQuestion { seconds } => *seconds,
+        }
+    }
+
+    /// When time runs out, for a quiz that began at `started` and is
+    /// now showing a question that appeared at `question_shown`.
+    pub fn deadline(&self, started: DateTime<Utc>, question_shown: DateTime<Utc>) -> DateTime<Utc> {
+        let allowed = Duration::seconds(self.seconds() as i64);
+        match self {
+            TimeLimit::Overall { .. } => started + allowed,
+            TimeLimit::PerQuestion { .. } => question_shown + allowed,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        let seconds = self.seconds();
+        let amount = match (seconds % 60, seconds / 60) {
+            (0, 1) => String::from("1 minute"),
+            (0, minutes) => format!("{minutes} minutes"),
+            _ => format!("{seconds} seconds"),
+        };
+        match self {
+            TimeLimit::Overall { .. } => format!("{amount} overall"),
+            TimeLimit::PerQuestion { .. } => format!("{amount} per question"),
+        }
+    }
+}
+
+impl Display for TimeLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for TimeLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TimeLimit::presets()
+            .into_iter()
+            .find(|l| l.name() == s)
+            .ok_or(format!("unknown time limit: {s}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadlines_and_names() {
+        let started = Utc::now();
+        let shown = started + Duration::seconds(100);
+
+        let overall = TimeLimit::Overall { seconds: 600 };
+        assert_eq!(
+            overall.deadline(started, shown),
+            started + Duration::minutes(10)
+        );
+        assert_eq!(overall.name(), "10 minutes overall");
+
+        let each = TimeLimit::PerQuestion { seconds: 30 };
+        assert_eq!(each.deadline(started, shown), shown + Duration::seconds(30));
+        assert_eq!(each.name(), "30 seconds per question");
+
+        for preset in TimeLimit::presets() {
+            assert_eq!(preset.name().parse(), Ok(preset));
+        }
+    }
+}
@@ -9,21 +9,42 @@
 //! 3. Validating login details and adding user
 //!    login strikes.
 
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
 // Imports for the MongoDB database driver.
 use mongodb::{
-    bson::doc,
+    bson::{doc, from_document, oid::ObjectId, to_bson, Bson, Document},
+    options::{FindOneOptions, FindOptions, ReplaceOptions},
     sync::{Client, Collection, Database},
 };
 
 // Imports for the Database types in my shared library.
 use shared::{
-    questions::QuestionSet,
+    adaptive::{AdaptiveRun, MasteryEstimate},
+    analytics::{
+        CalibrationReport, ItemAnalysis, ItemStats, ProgressReport, RatedAnswer, WrongAnswer,
+        DISCRIMINATION_GROUP, WRONG_ANSWERS,
+    },
+    arrangements::{AccessArrangements, QuizConditions},
+    assignments::{Assignment, CompletionGrid, StudentAssignment},
+    attempts::QuizAttempt,
+    classes::Class,
+    mistakes::{mistake_deck, DeckEntry, DeckFilter, DECK_QUIZ_LIMIT},
+    queries::{Page, Paged, ReviewQuery, ReviewScope, SetQuery, SetSort},
+    questions::{
+        has_topic, tag_with_parents, ExamLevel, Question, QuestionSet, SetSummary, MIN_DIFFICULTY,
+        TAG_SEPARATOR,
+    },
     requests::{
         UserReqError,
         UserReqErrorKind::{self, *},
     },
-    responses::QuizReview,
-    User,
+    responses::{QuestionRef, QuestionSnapshot, QuizMode, QuizReview},
+    scheduling::{recall_quality, MemoryState, DAILY_REVIEW_LIMIT},
+    sources::{BuiltQuiz, CustomQuiz, QuizSource},
+    timing::TimeLimit,
+    AccessLevel, User,
 };
 
 /// The struct that handles all the database operations.
@@ -67,6 +88,30 @@ impl DatabaseManager {
         Ok(Self { db })
     }
 
+    pub fn get_classes(&self) -> Collection<Class> {
+        // This is a getter for the collection in the database that
+        // contains all the Class structs.
+        self.db.collection("class")
+    }
+
+    pub fn get_assignments(&self) -> Collection<Assignment> {
+        // This is a getter for the collection in the database that
+        // contains all the Assignment structs.
+        self.db.collection("assignment")
+    }
+
+    pub fn get_memory(&self) -> Collection<MemoryState> {
+        // This is a getter for the collection in the database that
+        // holds how well each student remembers each question.
+        self.db.collection("memory")
+    }
+
+    pub fn get_attempts(&self) -> Collection<QuizAttempt> {
+        // This is a getter for the collection in the database that
+        // holds unfinished quiz attempts.
+        self.db.collection("attempt")
+    }
+
     pub fn get_users(&self) -> Collection<User> {
         // This is a getter for the collection in the database that
         // contains all the User structs.
@@ -79,6 +124,12 @@ impl DatabaseManager {
         self.db.collection("question")
     }
 
+    pub fn get_migrations(&self) -> Collection<Document> {
+        // This is a getter for the collection in the database that
+        // records which one-off migrations have already been run.
+        self.db.collection("migration")
+    }
+
     pub fn get_results(&self) -> Collection<QuizReview> {
         // This is a getter for the collection in the database that
         // contains all the QuizReview structs.
@@ -198,6 +249,41 @@ impl DatabaseManager {
         Ok(())
     }
 
+    pub fn get_arrangements(&self, username: &str) -> Result<AccessArrangements, UserReqError> {
+        self.get_users()
+            .find_one(doc! { "username": username }, None)
+            .map_err(|_| UserReqError::new(ConnectionError, "db operation failed".to_owned()))?
+            .map(|user| user.arrangements().clone())
+            .ok_or(UserReqError::new(
+                InvalidDetails,
+                "could not find user".to_owned(),
+            ))
+    }
+
+    pub fn set_arrangements(
+        &self,
+        username: &str,
+        arrangements: &AccessArrangements,
+    ) -> Result<(), UserReqError> {
+        let update = doc! { "$set": { "arrangements": to_bson(arrangements).unwrap() } };
+        let result = self
+            .get_users()
+            .update_one(doc! { "username": username }, update, None)
+            .map_err(|_| {
+                UserReqError::new(
+                    UpdateArrangementsError,
+                    "access arrangements could not be saved".to_owned(),
+                )
+            })?;
+        match result.matched_count {
+            0 => Err(UserReqError::new(
+                InvalidDetails,
+                "could not find user".to_owned(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
     pub fn delete_user(&self, username: String) -> Result<(), UserReqError> {
         // Query filter
         let query = doc! { "username": &username };
@@ -210,7 +296,10 @@ impl DatabaseManager {
         if is_user {
             // The deletion query just takes a query filter and deletes
             // the first matching object.
-            self.delete_redundant_reviews(username)?;
+            self.delete_redundant_reviews(username.clone())?;
+            self.delete_attempts_by(&username)?;
+            self.delete_memory_of(&username)?;
+            self.remove_from_classes(&username)?;
             match self.get_users().delete_one(query, None) {
                 Ok(_) => Ok(()),
                 Err(_) => Err(UserReqError::new(
@@ -226,9 +315,38 @@ impl DatabaseManager {
         }
     }
 
+    /// Publishes a set. A set under a name the author has used before
+    /// replaces their older one as its next version, keeping its ID so
+    /// that reviews of the older version still point at it. The review
+    /// schedule of the older version is reset, as its questions may have
+    /// moved.
     pub fn add_question_set(&self, new_set: QuestionSet) -> Result<(), UserReqError> {
-        match self.get_questions().insert_one(new_set, None) {
-            Ok(_) => Ok(()), // We can discard the InsertOneResult
+        let query = doc! { "name": new_set.name() };
+        let older = self
+            .get_questions()
+            .find_one(query.clone(), None)
+            .map_err(|_| UserReqError::new(ConnectionError, "db operation failed".to_owned()))?;
+        let mut replaced = None;
+        let result = match older {
+            Some(older) if older.author() != new_set.author() => {
+                return Err(UserReqError::new(
+                    AddSetError,
+                    "another author already has a set with that name".into(),
+                ))
+            }
+            Some(older) => {
+                replaced = Some(self.find_set_id(new_set.name())?);
+                self.get_questions()
+                    .replace_one(query, new_set.replacing(&older), None)
+                    .map(|_| ())
+            }
+            None => self.get_questions().insert_one(new_set, None).map(|_| ()),
+        };
+        match result {
+            Ok(_) => match replaced {
+                Some(set_id) => self.delete_memory_of_set(&set_id),
+                None => Ok(()),
+            },
             Err(_) => Err(UserReqError::new(
                 // Converting the error into my own error
                 AddSetError,
@@ -237,18 +355,26 @@ impl DatabaseManager {
         }
     }
 
-    pub fn delete_question_set(&self, name: String) -> Result<(), UserReqError> {
+    /// Deletes a set, which only its author or an admin can do.
+    pub fn delete_question_set(&self, name: String, user: &User) -> Result<(), UserReqError> {
         // Query filter
         let query = doc! { "name": name };
-        let is_q = self
+        let set = self
             .get_questions() // Gets question table handle
             .find_one(query.clone(), None) // Need to clone query to reuse later
-            .map_err(|_| UserReqError::new(ConnectionError, "db operation failed".to_owned()))?
-            .is_some();
+            .map_err(|_| UserReqError::new(ConnectionError, "db operation failed".to_owned()))?;
 
+        if let Some(set) = &set {
+            if user.access_level() != &AccessLevel::ADMIN && set.author() != user.username() {
+                return Err(UserReqError::new(
+                    PermissionDenied,
+                    "only the author of a set can delete it".to_owned(),
+                ));
+            }
+        }
         // The deletion query just takes a query filter and deletes
         // the first matching object
-        if is_q {
+        if set.is_some() {
             match self.get_questions().delete_one(query, None) {
                 Ok(_) => Ok(()),
                 Err(_) => Err(UserReqError::new(
@@ -290,25 +416,719 @@ impl DatabaseManager {
         }
     }
 
-    pub fn get_quiz_reviews(&self) -> Result<Vec<QuizReview>, UserReqError> {
-        match self.get_results().find(None, None) {
-            Ok(v) => Ok(v.filter_map(|v| v.ok()).collect()),
-            Err(_) => Err(UserReqError::new(
-                FetchReviewsError,
-                "could not fetch quiz reviews".into(),
-            )),
+    /// Runs a filtered, sorted search over the question sets and returns
+    /// one page of [`SetSummary`]s. Each part of the [`SetQuery`] that has
+    /// been filled in adds another clause to the filter, and they all
+    /// have to match. The projection stage means that the questions in
+    /// each set are counted by the database rather than being sent over.
+    pub fn find_set_summaries(
+        &self,
+        query: SetQuery,
+        page: Page,
+    ) -> Result<Paged<SetSummary>, UserReqError> {
+        let page = page.clamped();
+        let filter = Self::set_filter(&query);
+        let fetch_error = |_| {
+            UserReqError::new(
+                FetchQuestionsError,
+                "could not search question sets".to_owned(),
+            )
+        };
+
+        let total = self
+            .get_questions()
+            .count_documents(filter.clone(), None)
+            .map_err(fetch_error)?;
+
+        let pipeline = vec![
+            doc! { "$match": filter },
+            doc! { "$sort": Self::set_sort(query.sort, query.descending) },
+            doc! { "$skip": page.skip() as i64 },
+            doc! { "$limit": page.size as i64 },
+            doc! { "$project": {
+                "_id": 0,
+                "id": { "$toString": "$_id" },
+                "name": 1,
+                "author": 1,
+                "question_count": { "$size": "$questions" },
+                "tags": 1,
+                "level": 1,
+                "difficulty": 1,
+                "created": 1,
+            } },
+        ];
+
+        let items = self
+            .get_questions()
+            .aggregate(pipeline, None)
+            .map_err(fetch_error)?
+            .filter_map(|v| v.ok())
+            .filter_map(|v| from_document::<SetSummary>(v).ok())
+            .collect();
+
+        Ok(Paged { items, total, page })
+    }
+
+    fn set_filter(query: &SetQuery) -> Document {
+        let mut clauses = vec![];
+
+        let search = query.search.trim();
+        if !search.is_empty() {
+            let pattern = doc! { "$regex": escape_regex(search), "$options": "i" };
+            clauses.push(doc! {
+                "$or": [ { "name": pattern.clone() }, { "author": pattern } ]
+            });
+        }
+
+        // A tag can be on the set itself or on any of its questions, and
+        // a broad tag should also pick up everything underneath it.
+        for tag in &query.tags {
+            clauses.push(Self::tag_clause(tag));
+        }
+
+        // Sets from before levels and difficulties were recorded don't
+        // have the fields at all, and read back as the defaults.
+        if let Some(level) = query.level {
+            let clause = doc! { "level": format!("{:?}", level) };
+            clauses.push(match level == ExamLevel::default() {
+                true => doc! { "$or": [ clause, { "level": { "$exists": false } } ] },
+                false => clause,
+            });
+        }
+
+        if let Some(max) = query.max_difficulty {
+            let clause = doc! { "difficulty": { "$lte": max as i32 } };
+            clauses.push(match max >= MIN_DIFFICULTY {
+                true => doc! { "$or": [ clause, { "difficulty": { "$exists": false } } ] },
+                false => clause,
+            });
+        }
+
+        if clauses.is_empty() {
+            doc! {}
+        } else {
+            doc! { "$and": clauses }
         }
     }
 
-    pub fn add_quiz_review(&self, new_review: QuizReview) -> Result<(), UserReqError> {
-        match self.get_results().insert_one(new_review, None) {
-            Ok(_) => Ok(()), // We can discard the InsertOneResult
-            Err(_) => Err(UserReqError::new(
+    /// Matches sets with `tag`, or a narrower tag under it, on the set
+    /// or any of its questions.
+    fn tag_clause(tag: &str) -> Document {
+        let pattern = format!("^{}($|{})", escape_regex(tag), escape_regex(TAG_SEPARATOR));
+        let pattern = doc! { "$regex": pattern };
+        doc! {
+            "$or": [ { "tags": pattern.clone() }, { "questions.tags": pattern } ]
+        }
+    }
+
+    fn set_sort(sort: SetSort, descending: bool) -> Document {
+        let field = match sort {
+            SetSort::Name => "name",
+            SetSort::Author => "author",
+            SetSort::Date => "created",
+            SetSort::Difficulty => "difficulty",
+        };
+        let direction = if descending { -1 } else { 1 };
+        // Name is used as a tie-breaker so the order is always stable.
+        doc! { field: direction, "name": 1 }
+    }
+
+    /// Every tag used on a set or question, along with the broader tags
+    /// above them, sorted alphabetically.
+    pub fn get_set_tags(&self) -> Result<Vec<String>, UserReqError> {
+        let mut tags = vec![];
+        for field in ["tags", "questions.tags"] {
+            let values = self
+                .get_questions()
+                .distinct(field, None, None)
+                .map_err(|_| {
+                    UserReqError::new(FetchQuestionsError, "could not fetch tags".to_owned())
+                })?;
+            for value in values {
+                if let Bson::String(tag) = value {
+                    tags.extend(tag_with_parents(&tag));
+                }
+            }
+        }
+        tags.sort();
+        tags.dedup();
+        Ok(tags)
+    }
+
+    /// Runs a filtered search over the quiz reviews in `scope`, newest
+    /// first, and returns one page of them. The caller is expected to
+    /// have already checked that `user` is allowed to search `scope`.
+    pub fn find_quiz_reviews(
+        &self,
+        user: &User,
+        scope: ReviewScope,
+        query: ReviewQuery,
+        page: Page,
+    ) -> Result<Paged<QuizReview>, UserReqError> {
+        let fetch_error =
+            |_| UserReqError::new(FetchReviewsError, "could not fetch quiz reviews".into());
+        let page = page.clamped();
+
+        let mut clauses = vec![];
+        match scope {
+            ReviewScope::Mine => clauses.push(doc! { "username": user.username() }),
+            ReviewScope::Students => {
+                let students = if user.access_level() == &AccessLevel::ADMIN {
+                    self.get_users()
+                        .distinct(
+                            "username",
+                            doc! { "access_level": format!("{:?}", AccessLevel::USER) },
+                            None,
+                        )
+                        .map_err(fetch_error)?
+                } else {
+                    self.students_of(user.username())?
+                        .into_iter()
+                        .map(Bson::String)
+                        .collect()
+                };
+                clauses.push(doc! { "username": { "$in": students } });
+            }
+            ReviewScope::All => {}
+        }
+        // Teachers can only narrow a search down to one of their own classes.
+        if let Some(class_name) = &query.class_name {
+            let class = self.get_class(class_name)?;
+            if user.access_level() != &AccessLevel::ADMIN && !class.is_teacher(user.username()) {
+                return Err(UserReqError::new(
+                    PermissionDenied,
+                    "you do not teach that class".to_owned(),
+                ));
+            }
+            clauses.push(doc! { "username": { "$in": class.students() } });
+        }
+        clauses.extend(Self::review_filter(&query));
+        let filter = if clauses.is_empty() {
+            doc! {}
+        } else {
+            doc! { "$and": clauses }
+        };
+
+        let total = self
+            .get_results()
+            .count_documents(filter.clone(), None)
+            .map_err(fetch_error)?;
+
+        let options = FindOptions::builder()
+            .sort(doc! { "finished": -1, "_id": -1 })
+            .skip(page.skip())
+            .limit(page.size as i64)
+            .build();
+        let items = self
+            .get_results()
+            .find(filter, options)
+            .map_err(fetch_error)?
+            .filter_map(|v| v.ok())
+            .collect();
+
+        Ok(Paged { items, total, page })
+    }
+
+    /// Narrows a review filter down to attempts sat as tests, since
+    /// practice doesn't count towards assessment. Reviews from before
+    /// practice mode have no mode and are all tests.
+    fn assessed(mut filter: Document) -> Document {
+        filter.insert("mode", doc! { "$ne": QuizMode::Practice.name() });
+        filter
+    }
+
+    /// Works out a [`ProgressReport`] from every test `username` has sat.
+    pub fn get_progress(&self, username: &str) -> Result<ProgressReport, UserReqError> {
+        let reviews = self
+            .get_results()
+            .find(Self::assessed(doc! { "username": username }), None)
+            .map_err(|_| {
+                UserReqError::new(FetchReviewsError, "could not fetch quiz reviews".into())
+            })?
+            .filter_map(|v| v.ok())
+            .collect::<Vec<_>>();
+        Ok(ProgressReport::from_reviews(&reviews))
+    }
+
+    /// Compares how sure the student said they were of their answers
+    /// with how often they were right. The topics of each question are
+    /// its own tags and its set's, or the set's name if it has none.
+    pub fn get_calibration(&self, username: &str) -> Result<CalibrationReport, UserReqError> {
+        let reviews = self
+            .get_results()
+            .find(doc! { "username": username }, None)
+            .map_err(|_| {
+                UserReqError::new(FetchReviewsError, "could not fetch quiz reviews".into())
+            })?
+            .filter_map(|v| v.ok());
+
+        let mut sets = HashMap::new();
+        let mut answers = vec![];
+        for review in reviews {
+            for response in &review.responses {
+                let Some(confidence) = response.confidence() else {
+                    continue;
+                };
+                let question = response.question();
+                if !sets.contains_key(&question.set_id) {
+                    let set = self.get_question_set_by_id(&question.set_id).ok();
+                    sets.insert(question.set_id.clone(), set);
+                }
+                let mut topics = match &sets[&question.set_id] {
+                    Some(set) => set
+                        .tags()
+                        .iter()
+                        .chain(
+                            set.questions()
+                                .get(question.index)
+                                .map_or(&vec![], |q| q.tags()),
+                        )
+                        .cloned()
+                        .collect::<Vec<_>>(),
+                    None => vec![],
+                };
+                topics.sort();
+                topics.dedup();
+                if topics.is_empty() {
+                    topics.push(match &sets[&question.set_id] {
+                        Some(set) => set.name().clone(),
+                        None => review.set_name.clone(),
+                    });
+                }
+                answers.push(RatedAnswer {
+                    confidence,
+                    correct: response.is_correct(),
+                    topics,
+                });
+            }
+        }
+        Ok(CalibrationReport::from_answers(&answers))
+    }
+
+    /// Works out how every attempt at the set called `set_name` went,
+    /// question by question. All of the counting is done by two
+    /// aggregation pipelines so the responses never leave the database.
+    /// Only attempts at the set's current version are counted, as an
+    /// older one may have had different questions in each place.
+    pub fn get_item_analysis(&self, set_name: &str) -> Result<ItemAnalysis, UserReqError> {
+        let set_id = self.find_set_id(set_name)?;
+        let version = self.get_question_set(set_name.to_owned())?.version();
+        let fetch_error =
+            |_| UserReqError::new(FetchReviewsError, "could not analyse quiz reviews".into());
+        let reviews = self.get_results();
+        let of_version = || {
+            Self::assessed(doc! {
+                "set_id": &set_id,
+                "responses.question.set_version": version as i64,
+            })
+        };
+
+        let attempts = reviews
+            .count_documents(of_version(), None)
+            .map_err(fetch_error)? as u32;
+
+        // Each attempt is ranked by score and put into the upper or lower
+        // band if it is in the top or bottom 27%, before being split up
+        // into its responses and grouped by question.
+        let is_band = |band: &str| doc! { "$eq": ["$band", band] };
+        let stats_pipeline = vec![
+            doc! { "$match": of_version() },
+            doc! { "$setWindowFields": {
+                "sortBy": { "score": -1 },
+                "output": {
+                    "position": { "$documentNumber": {} },
+                    "total": { "$count": {}, "window": { "documents": ["unbounded", "unbounded"] } },
+                },
+            } },
+            doc! { "$addFields": {
+                "group_size": { "$ceil": { "$multiply": ["$total", DISCRIMINATION_GROUP] } },
+            } },
+            doc! { "$addFields": { "band": { "$switch": {
+                "branches": [
+                    { "case": { "$lte": ["$position", "$group_size"] }, "then": "upper" },
+                    {
+                        "case": { "$gt": ["$position", { "$subtract": ["$total", "$group_size"] }] },
+                        "then": "lower",
+                    },
+                ],
+                "default": "middle",
+            } } } },
+            doc! { "$unwind": "$responses" },
+            doc! { "$group": {
+                "_id": "$responses.question.index",
+                "question": { "$first": "$responses.snapshot" },
+                "attempts": { "$sum": 1 },
+                "correct": { "$sum": { "$cond": ["$responses.is_correct", 1, 0] } },
+                "average_time": { "$avg": "$responses.time_spent" },
+                "upper_attempts": { "$sum": { "$cond": [is_band("upper"), 1, 0] } },
+                "upper_correct": { "$sum": { "$cond": [
+                    { "$and": [is_band("upper"), "$responses.is_correct"] }, 1, 0,
+                ] } },
+                "lower_attempts": { "$sum": { "$cond": [is_band("lower"), 1, 0] } },
+                "lower_correct": { "$sum": { "$cond": [
+                    { "$and": [is_band("lower"), "$responses.is_correct"] }, 1, 0,
+                ] } },
+            } },
+            doc! { "$sort": { "_id": 1 } },
+        ];
+
+        let wrong_pipeline = vec![
+            doc! { "$match": of_version() },
+            doc! { "$unwind": "$responses" },
+            doc! { "$match": { "responses.is_correct": false } },
+            doc! { "$group": {
+                "_id": { "index": "$responses.question.index", "answer": "$responses.submitted" },
+                "count": { "$sum": 1 },
+            } },
+            doc! { "$sort": { "count": -1, "_id.answer": 1 } },
+            doc! { "$group": {
+                "_id": "$_id.index",
+                "answers": { "$push": { "answer": "$_id.answer", "count": "$count" } },
+            } },
+            doc! { "$project": { "answers": { "$slice": ["$answers", WRONG_ANSWERS as i32] } } },
+        ];
+
+        let wrong_answers = reviews
+            .aggregate(wrong_pipeline, None)
+            .map_err(fetch_error)?
+            .filter_map(|v| v.ok())
+            .map(|group| {
+                let answers = group
+                    .get_array("answers")
+                    .map(|a| a.to_vec())
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|a| match a {
+                        Bson::Document(a) => Some(WrongAnswer {
+                            answer: a.get_str("answer").unwrap_or_default().to_owned(),
+                            count: number(&a, "count") as u32,
+                        }),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                (number(&group, "_id") as usize, answers)
+            })
+            .collect::<Vec<_>>();
+
+        let items = reviews
+            .aggregate(stats_pipeline, None)
+            .map_err(fetch_error)?
+            .filter_map(|v| v.ok())
+            .map(|item| {
+                let index = number(&item, "_id") as usize;
+                let attempts = number(&item, "attempts");
+                let rate = |correct: &str, attempts: &str| match number(&item, attempts) {
+                    n if n > 0.0 => Some(number(&item, correct) / n),
+                    _ => None,
+                };
+                let discrimination = match (
+                    rate("upper_correct", "upper_attempts"),
+                    rate("lower_correct", "lower_attempts"),
+                ) {
+                    (Some(upper), Some(lower)) => Some(upper - lower),
+                    _ => None,
+                };
+                ItemStats {
+                    index,
+                    question: item
+                        .get_document("question")
+                        .ok()
+                        .and_then(|q| from_document(q.clone()).ok())
+                        .unwrap_or_default(),
+                    attempts: attempts as u32,
+                    facility: 100.0 * rate("correct", "attempts").unwrap_or_default(),
+                    average_time: number(&item, "average_time") / 1000.0,
+                    discrimination,
+                    wrong_answers: wrong_answers
+                        .iter()
+                        .find(|(i, _)| *i == index)
+                        .map(|(_, answers)| answers.clone())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        Ok(ItemAnalysis {
+            set_name: set_name.to_owned(),
+            attempts,
+            items,
+        })
+    }
+
+    fn review_filter(query: &ReviewQuery) -> Vec<Document> {
+        let mut clauses = vec![];
+        if let Some(set_name) = &query.set_name {
+            clauses.push(doc! { "set_name": set_name });
+        }
+        if let Some(username) = &query.username {
+            clauses.push(doc! { "username": username });
+        }
+        // Timestamps are stored as RFC 3339 strings, which sort in the
+        // same order as the times they represent. Converting with to_bson
+        // writes the bounds in exactly the same format.
+        if let Some(from) = query.from {
+            clauses.push(doc! { "finished": { "$gte": to_bson(&from).unwrap() } });
+        }
+        if let Some(until) = query.until {
+            clauses.push(doc! { "finished": { "$lt": to_bson(&until).unwrap() } });
+        }
+        clauses
+    }
+
+    /// Saves a finished attempt, linking it to the set that was taken
+    /// by the set's database ID so it still matches if the set is later
+    /// renamed. Attempts at an assignment are checked against it first,
+    /// and the time limit and marks are filled in from the set or
+    /// assignment rather than trusting the frontend. Every question
+    /// answered is then rescheduled for spaced repetition.
+    pub fn add_quiz_review(&self, mut new_review: QuizReview) -> Result<(), UserReqError> {
+        // Whether homework was late goes by when it was handed in, so
+        // that is decided here rather than by the frontend's clock.
+        new_review.finished = Utc::now();
+        new_review.started = new_review.started.min(new_review.finished);
+        let practice = new_review.mode == QuizMode::Practice;
+        let single_set = new_review.source == QuizSource::Set;
+        if let Some(id) = new_review.assignment_id.clone() {
+            if practice || !single_set {
+                return Err(UserReqError::new(
+                    AddReviewError,
+                    "homework has to be sat as a test of its set".to_owned(),
+                ));
+            }
+            self.check_assignment_attempt(&id, &new_review)?;
+        }
+        if single_set {
+            let conditions = self.quiz_conditions(
+                &new_review.username,
+                &new_review.set_name,
+                new_review.assignment_id.as_deref(),
+            )?;
+            // Practice is never timed.
+            new_review.time_limit = conditions.time_limit.filter(|_| !practice);
+            new_review.arrangements = conditions.arrangements;
+            new_review.link_to_set(self.find_set_id(&new_review.set_name)?);
+            // The marks are worked out again here, so that only the
+            // answers themselves come from the frontend.
+            let set = self.get_question_set(new_review.set_name.clone())?;
+            new_review
+                .remark(&set)
+                .map_err(|why| UserReqError::new(AddReviewError, why))?;
+        } else {
+            // Each response already points at the set its question came
+            // from, as the backend built the quiz.
+            let conditions = self.built_conditions(&new_review.username, &new_review.source)?;
+            new_review.time_limit = conditions.time_limit.filter(|_| !practice);
+            new_review.arrangements = conditions.arrangements;
+            (new_review.score, new_review.max_score) = new_review.score();
+        }
+        if let QuizSource::Adaptive(_) = new_review.source {
+            new_review.mastery = Some(self.mastery_of(&new_review));
+        }
+        if self.get_results().insert_one(&new_review, None).is_err() {
+            return Err(UserReqError::new(
                 // Converting the error into my own error
                 AddReviewError,
                 "could not add quiz review to database".into(),
-            )),
+            ));
+        }
+        // The review is already saved, so a scheduling failure only
+        // leaves its questions off the review schedule.
+        if let Err(why) = self.update_memory(&new_review, new_review.finished) {
+            eprintln!("{why}");
+        }
+        // The attempt has been handed in, so there is nothing left to
+        // resume. Only sets sat on their own are saved as attempts, and a
+        // live quiz of the same set must not throw one of those away.
+        if !single_set {
+            return Ok(());
         }
+        self.discard_attempt(
+            &new_review.username,
+            &new_review.set_name,
+            new_review.assignment_id.as_deref(),
+        )
+    }
+
+    /// The time limit for sitting a set, which an assignment can set
+    /// for itself in place of the set's own.
+    fn time_limit_for(
+        &self,
+        set_name: &str,
+        assignment_id: Option<&str>,
+    ) -> Result<Option<TimeLimit>, UserReqError> {
+        if let Some(id) = assignment_id {
+            if let Some(limit) = self.get_assignment(id)?.time_limit() {
+                return Ok(Some(limit));
+            }
+        }
+        Ok(self.get_question_set(set_name.to_owned())?.time_limit())
+    }
+
+    /// How `username` must sit a set: its time limit, lengthened by any
+    /// extra time they have, along with their access arrangements.
+    pub fn quiz_conditions(
+        &self,
+        username: &str,
+        set_name: &str,
+        assignment_id: Option<&str>,
+    ) -> Result<QuizConditions, UserReqError> {
+        let arrangements = self.get_arrangements(username)?;
+        Ok(QuizConditions {
+            time_limit: self
+                .time_limit_for(set_name, assignment_id)?
+                .map(|limit| arrangements.extend(limit)),
+            arrangements,
+        })
+    }
+
+    /// Makes sure a review submitted for an assignment is for the right
+    /// set, by a student in the class, and within the attempt limit.
+    fn check_assignment_attempt(&self, id: &str, review: &QuizReview) -> Result<(), UserReqError> {
+        let assignment = self.get_assignment(id)?;
+        if assignment.set_name() != &review.set_name {
+            return Err(UserReqError::new(
+                AddReviewError,
+                "this set is not part of the assignment".to_owned(),
+            ));
+        }
+        if !self
+            .get_class(assignment.class_name())?
+            .is_enrolled(&review.username)
+        {
+            return Err(UserReqError::new(
+                PermissionDenied,
+                "you are not in the class this was set for".to_owned(),
+            ));
+        }
+        let used = self.assignment_reviews(id, Some(&review.username))?.len() as u32;
+        if !assignment.can_attempt(Utc::now(), used) {
+            return Err(UserReqError::new(
+                AddReviewError,
+                "no attempts are left for this assignment".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The database ID of the set called `name`, as a hex string.
+    pub fn find_set_id(&self, name: &str) -> Result<String, UserReqError> {
+        let options = FindOneOptions::builder()
+            .projection(doc! { "_id": 1 })
+            .build();
+        let set = self
+            .get_questions()
+            .clone_with_type::<Document>()
+            .find_one(doc! { "name": name }, options)
+            .map_err(|_| UserReqError::new(ConnectionError, "db operation failed".to_owned()))?
+            .ok_or(UserReqError::new(
+                InvalidDetails,
+                "could not find question set".to_owned(),
+            ))?;
+
+        set.get_object_id("_id")
+            .map(|id| id.to_hex())
+            .map_err(|_| UserReqError::new(SerdeError, "question set has no ID".to_owned()))
+    }
+
+    /// Older reviews stored each question as an HTML string, which the
+    /// Review page then had to render unchecked. This rewrites any of
+    /// those left in the database into a [`QuestionRef`] and a plain
+    /// text [`QuestionSnapshot`], and returns how many were changed.
+    pub fn migrate_legacy_reviews(&self) -> Result<u64, UserReqError> {
+        let migrate_error = |_| {
+            UserReqError::new(
+                FetchReviewsError,
+                "could not migrate old quiz reviews".to_owned(),
+            )
+        };
+        let reviews = self.get_results().clone_with_type::<Document>();
+        let legacy = reviews
+            .find(doc! { "responses.question": { "$type": "string" } }, None)
+            .map_err(migrate_error)?
+            .filter_map(|v| v.ok())
+            .collect::<Vec<_>>();
+
+        let mut migrated = 0;
+        for review in legacy {
+            let Ok(id) = review.get_object_id("_id") else {
+                continue;
+            };
+            let set_id = review.get_str("set_id").unwrap_or_default();
+            let responses = review
+                .get_array("responses")
+                .map(|a| a.to_vec())
+                .unwrap_or_default()
+                .into_iter()
+                .enumerate()
+                .map(|(index, response)| match response {
+                    Bson::Document(mut response) => {
+                        if let Ok(html) = response.get_str("question") {
+                            let snapshot = QuestionSnapshot::from_legacy_html(html);
+                            let reference = QuestionRef {
+                                set_id: set_id.to_owned(),
+                                set_version: 1,
+                                index,
+                            };
+                            response.insert("snapshot", to_bson(&snapshot).unwrap());
+                            response.insert("question", to_bson(&reference).unwrap());
+                        }
+                        Bson::Document(response)
+                    }
+                    other => other,
+                })
+                .collect::<Vec<_>>();
+
+            reviews
+                .update_one(
+                    doc! { "_id": id },
+                    doc! { "$set": { "responses": responses } },
+                    None,
+                )
+                .map_err(migrate_error)?;
+            migrated += 1;
+        }
+        Ok(migrated)
+    }
+
+    /// Reviews saved before the review schedule existed never updated
+    /// it. The first time this runs, the schedule is rebuilt by replaying
+    /// every review in the order they were finished, and it returns how
+    /// many were replayed. After that it does nothing.
+    pub fn migrate_memory(&self) -> Result<u64, UserReqError> {
+        let migrate_error = |_| {
+            UserReqError::new(
+                UpdateMemoryError,
+                "could not build the review schedule from old quiz reviews".to_owned(),
+            )
+        };
+        let marker = doc! { "_id": "memory" };
+        let migrations = self.get_migrations();
+        if migrations
+            .find_one(marker.clone(), None)
+            .map_err(migrate_error)?
+            .is_some()
+        {
+            return Ok(0);
+        }
+
+        // Anything already scheduled came from reviews that are replayed
+        // below, so it is cleared rather than counted twice.
+        self.get_memory()
+            .delete_many(doc! {}, None)
+            .map_err(migrate_error)?;
+        let options = FindOptions::builder().sort(doc! { "finished": 1 }).build();
+        let reviews = self
+            .get_results()
+            .find(doc! {}, options)
+            .map_err(migrate_error)?
+            .filter_map(|v| v.ok())
+            .collect::<Vec<_>>();
+        for review in &reviews {
+            self.update_memory(review, review.finished)?;
+        }
+
+        migrations.insert_one(marker, None).map_err(migrate_error)?;
+        Ok(reviews.len() as u64)
     }
 
     fn delete_redundant_reviews(&self, username: String) -> Result<(), UserReqError> {
@@ -322,4 +1142,728 @@ impl DatabaseManager {
             })?;
         Ok(())
     }
+
+    pub fn add_class(&self, new_class: Class) -> Result<(), UserReqError> {
+        let exists = self
+            .get_classes()
+            .find_one(doc! { "name": new_class.name() }, None)
+            .map_err(|_| UserReqError::new(ConnectionError, "db operation failed".to_owned()))?
+            .is_some();
+        if exists {
+            return Err(UserReqError::new(
+                AddClassError,
+                "a class with that name already exists".to_owned(),
+            ));
+        }
+
+        match self.get_classes().insert_one(new_class, None) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(UserReqError::new(
+                AddClassError,
+                "could not add class to database".into(),
+            )),
+        }
+    }
+
+    pub fn get_class(&self, name: &str) -> Result<Class, UserReqError> {
+        self.get_classes()
+            .find_one(doc! { "name": name }, None)
+            .map_err(|_| UserReqError::new(ConnectionError, "db operation failed".to_owned()))?
+            .ok_or(UserReqError::new(
+                InvalidDetails,
+                "could not find class".to_owned(),
+            ))
+    }
+
+    /// The classes that `user` can see: the ones they teach, the ones
+    /// they are enrolled in, or every class for admins.
+    pub fn find_classes(&self, user: &User) -> Result<Vec<Class>, UserReqError> {
+        let filter = match user.access_level() {
+            AccessLevel::ADMIN => doc! {},
+            AccessLevel::TEACHER => doc! { "teachers": user.username() },
+            AccessLevel::USER => doc! { "students": user.username() },
+        };
+        let options = FindOptions::builder().sort(doc! { "name": 1 }).build();
+        match self.get_classes().find(filter, options) {
+            Ok(v) => Ok(v.filter_map(|v| v.ok()).collect()),
+            Err(_) => Err(UserReqError::new(
+                FetchClassesError,
+                "could not fetch classes".into(),
+            )),
+        }
+    }
+
+    /// Every student enrolled in any class that `teacher` teaches.
+    pub fn students_of(&self, teacher: &str) -> Result<Vec<String>, UserReqError> {
+        let students = self
+            .get_classes()
+            .distinct("students", doc! { "teachers": teacher }, None)
+            .map_err(|_| {
+                UserReqError::new(FetchClassesError, "could not fetch classes".to_owned())
+            })?;
+        Ok(students
+            .into_iter()
+            .filter_map(|s| match s {
+                Bson::String(s) => Some(s),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Deletes a class along with any assignments set for it.
+    pub fn delete_class(&self, name: &str) -> Result<(), UserReqError> {
+        self.get_assignments()
+            .delete_many(doc! { "class_name": name }, None)
+            .map_err(|_| {
+                UserReqError::new(
+                    DeleteAssignmentError,
+                    "could not delete the class's assignments".to_owned(),
+                )
+            })?;
+        match self.get_classes().delete_one(doc! { "name": name }, None) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(UserReqError::new(
+                DeleteClassError,
+                "the class could not be deleted".to_owned(),
+            )),
+        }
+    }
+
+    /// Enrols a student in a class. Only accounts with the `USER` access
+    /// level can be enrolled, and enrolling someone twice does nothing.
+    pub fn enrol_student(&self, class_name: &str, username: &str) -> Result<(), UserReqError> {
+        let student = self
+            .get_users()
+            .find_one(doc! { "username": username }, None)
+            .map_err(|_| UserReqError::new(ConnectionError, "db operation failed".to_owned()))?;
+        match student {
+            Some(student) if student.access_level() == &AccessLevel::USER => {}
+            Some(_) => {
+                return Err(UserReqError::new(
+                    UpdateClassError,
+                    "only students can be enrolled in a class".to_owned(),
+                ))
+            }
+            None => {
+                return Err(UserReqError::new(
+                    InvalidDetails,
+                    "could not find user".to_owned(),
+                ))
+            }
+        }
+        self.update_class(class_name, doc! { "$addToSet": { "students": username } })
+    }
+
+    pub fn unenrol_student(&self, class_name: &str, username: &str) -> Result<(), UserReqError> {
+        self.update_class(class_name, doc! { "$pull": { "students": username } })
+    }
+
+    /// Shares a class with another teacher, who can then manage it too.
+    pub fn add_class_teacher(&self, class_name: &str, username: &str) -> Result<(), UserReqError> {
+        let teacher = self
+            .get_users()
+            .find_one(doc! { "username": username }, None)
+            .map_err(|_| UserReqError::new(ConnectionError, "db operation failed".to_owned()))?;
+        match teacher {
+            Some(teacher) if teacher.access_level() != &AccessLevel::USER => {}
+            _ => {
+                return Err(UserReqError::new(
+                    UpdateClassError,
+                    "only teachers can teach a class".to_owned(),
+                ))
+            }
+        }
+        self.update_class(class_name, doc! { "$addToSet": { "teachers": username } })
+    }
+
+    fn update_class(&self, class_name: &str, update: Document) -> Result<(), UserReqError> {
+        self.get_classes()
+            .update_one(doc! { "name": class_name }, update, None)
+            .map_err(|_| {
+                UserReqError::new(
+                    UpdateClassError,
+                    "the class could not be updated".to_owned(),
+                )
+            })?;
+        Ok(())
+    }
+
+    /// Takes a deleted account out of every class it was part of.
+    fn remove_from_classes(&self, username: &str) -> Result<(), UserReqError> {
+        self.get_classes()
+            .update_many(
+                doc! {},
+                doc! { "$pull": { "students": username, "teachers": username } },
+                None,
+            )
+            .map_err(|_| {
+                UserReqError::new(UpdateClassError, "could not update classes".to_owned())
+            })?;
+        Ok(())
+    }
+
+    /// Saves a new assignment and returns it with the ID it was given.
+    pub fn add_assignment(&self, assignment: Assignment) -> Result<Assignment, UserReqError> {
+        self.find_set_id(assignment.set_name())?;
+        if assignment.due() <= assignment.opens() {
+            return Err(UserReqError::new(
+                AddAssignmentError,
+                "the due date must be after the assignment opens".to_owned(),
+            ));
+        }
+
+        let assignment = assignment.with_id(ObjectId::new().to_hex());
+        match self.get_assignments().insert_one(&assignment, None) {
+            Ok(_) => Ok(assignment),
+            Err(_) => Err(UserReqError::new(
+                AddAssignmentError,
+                "could not add assignment to database".into(),
+            )),
+        }
+    }
+
+    pub fn get_assignment(&self, id: &str) -> Result<Assignment, UserReqError> {
+        self.get_assignments()
+            .find_one(doc! { "id": id }, None)
+            .map_err(|_| UserReqError::new(ConnectionError, "db operation failed".to_owned()))?
+            .ok_or(UserReqError::new(
+                InvalidDetails,
+                "could not find assignment".to_owned(),
+            ))
+    }
+
+    /// The assignments set for any class that `user` can see, with the
+    /// soonest due first.
+    pub fn find_assignments(&self, user: &User) -> Result<Vec<Assignment>, UserReqError> {
+        let classes = self
+            .find_classes(user)?
+            .into_iter()
+            .map(|c| c.name().clone())
+            .collect::<Vec<_>>();
+        let options = FindOptions::builder().sort(doc! { "due": 1 }).build();
+        match self
+            .get_assignments()
+            .find(doc! { "class_name": { "$in": classes } }, options)
+        {
+            Ok(v) => Ok(v.filter_map(|v| v.ok()).collect()),
+            Err(_) => Err(UserReqError::new(
+                FetchAssignmentsError,
+                "could not fetch assignments".into(),
+            )),
+        }
+    }
+
+    /// A student's assignments, each with where they are up to with it.
+    pub fn student_assignments(&self, user: &User) -> Result<Vec<StudentAssignment>, UserReqError> {
+        let now = Utc::now();
+        self.find_assignments(user)?
+            .into_iter()
+            .map(|assignment| {
+                let reviews = self.assignment_reviews(assignment.id(), Some(user.username()))?;
+                Ok(StudentAssignment {
+                    status: assignment.status(now, &reviews),
+                    attempts_used: reviews.len() as u32,
+                    assignment,
+                })
+            })
+            .collect()
+    }
+
+    /// How each student in the class has got on with an assignment.
+    pub fn get_completion_grid(&self, id: &str) -> Result<CompletionGrid, UserReqError> {
+        let assignment = self.get_assignment(id)?;
+        let class = self.get_class(assignment.class_name())?;
+        let reviews = self.assignment_reviews(id, None)?;
+        Ok(CompletionGrid::new(assignment, class.students(), &reviews))
+    }
+
+    /// The reviews submitted for an assignment, optionally only those
+    /// by one student.
+    fn assignment_reviews(
+        &self,
+        id: &str,
+        username: Option<&str>,
+    ) -> Result<Vec<QuizReview>, UserReqError> {
+        let mut filter = doc! { "assignment_id": id };
+        if let Some(username) = username {
+            filter.insert("username", username);
+        }
+        match self.get_results().find(filter, None) {
+            Ok(v) => Ok(v.filter_map(|v| v.ok()).collect()),
+            Err(_) => Err(UserReqError::new(
+                FetchReviewsError,
+                "could not fetch quiz reviews".into(),
+            )),
+        }
+    }
+
+    /// Deletes an assignment. Reviews already submitted for it are kept
+    /// as ordinary attempts.
+    pub fn delete_assignment(&self, id: &str) -> Result<(), UserReqError> {
+        match self.get_assignments().delete_one(doc! { "id": id }, None) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(UserReqError::new(
+                DeleteAssignmentError,
+                "the assignment could not be deleted".to_owned(),
+            )),
+        }
+    }
+
+    fn get_question_set_by_id(&self, id: &str) -> Result<QuestionSet, UserReqError> {
+        let not_found = || UserReqError::new(InvalidDetails, "could not find question set".into());
+        let id = ObjectId::parse_str(id).map_err(|_| not_found())?;
+        self.get_questions()
+            .find_one(doc! { "_id": id }, None)
+            .map_err(|_| UserReqError::new(ConnectionError, "db operation failed".to_owned()))?
+            .ok_or_else(not_found)
+    }
+
+    /// Reschedules every question answered in a review at `now`, going
+    /// by how well each answer shows it was remembered. Answers to an
+    /// older version of a set are left out, as its questions may have
+    /// moved since.
+    fn update_memory(&self, review: &QuizReview, now: DateTime<Utc>) -> Result<(), UserReqError> {
+        let update_error = |_| {
+            UserReqError::new(
+                UpdateMemoryError,
+                "could not update the review schedule".into(),
+            )
+        };
+        let memory = self.get_memory();
+        let mut versions = HashMap::new();
+        for response in &review.responses {
+            let question = response.question();
+            if question.set_id.is_empty() {
+                continue;
+            }
+            let version = *versions.entry(question.set_id.clone()).or_insert_with(|| {
+                self.get_question_set_by_id(&question.set_id)
+                    .map(|set| set.version())
+                    .ok()
+            });
+            if version != Some(question.set_version) {
+                continue;
+            }
+            let filter = doc! {
+                "username": &review.username,
+                "question.set_id": &question.set_id,
+                "question.set_version": question.set_version as i64,
+                "question.index": question.index as i64,
+            };
+            let mut state = memory
+                .find_one(filter.clone(), None)
+                .map_err(update_error)?
+                .unwrap_or_else(|| {
+                    MemoryState::new(review.username.clone(), question.clone(), now)
+                });
+            state.question = question.clone();
+            state.review(recall_quality(response), now);
+            let options = ReplaceOptions::builder().upsert(true).build();
+            memory
+                .replace_one(filter, &state, options)
+                .map_err(update_error)?;
+        }
+        Ok(())
+    }
+
+    /// The student's questions that are due for review by the end of
+    /// today, the most overdue first.
+    pub fn due_questions(&self, username: &str) -> Result<Vec<MemoryState>, UserReqError> {
+        let now = Utc::now();
+        let options = FindOptions::builder().sort(doc! { "due": 1 }).build();
+        match self
+            .get_memory()
+            .find(doc! { "username": username }, options)
+        {
+            Ok(v) => Ok(v
+                .filter_map(|v| v.ok())
+                .filter(|state| state.due_today(now))
+                .collect()),
+            Err(_) => Err(UserReqError::new(
+                FetchReviewsError,
+                "could not fetch the review schedule".into(),
+            )),
+        }
+    }
+
+    /// The student's mistake deck, narrowed down by `filter`.
+    pub fn mistake_deck(
+        &self,
+        username: &str,
+        filter: &DeckFilter,
+    ) -> Result<Vec<DeckEntry>, UserReqError> {
+        Ok(self
+            .deck_questions(username, filter)?
+            .into_iter()
+            .map(|(question, set_name, _)| DeckEntry { question, set_name })
+            .collect())
+    }
+
+    fn deck_questions(
+        &self,
+        username: &str,
+        filter: &DeckFilter,
+    ) -> Result<Vec<(QuestionRef, String, Question)>, UserReqError> {
+        let reviews = self
+            .get_results()
+            .find(doc! { "username": username }, None)
+            .map_err(|_| {
+                UserReqError::new(FetchReviewsError, "could not fetch quiz reviews".into())
+            })?
+            .filter_map(|v| v.ok())
+            .collect::<Vec<_>>();
+        Ok(
+            self.look_up_questions(mistake_deck(&reviews), |set, question| {
+                filter.matches(set, question)
+            }),
+        )
+    }
+
+    /// Finds each question in `origins` that `keep` accepts, along with
+    /// the name of its set. Questions whose set has since been deleted
+    /// or republished are left out, as they may no longer be there.
+    fn look_up_questions(
+        &self,
+        origins: Vec<QuestionRef>,
+        keep: impl Fn(&QuestionSet, &Question) -> bool,
+    ) -> Vec<(QuestionRef, String, Question)> {
+        let mut sets = HashMap::new();
+        let mut found = vec![];
+        for origin in origins {
+            if !sets.contains_key(&origin.set_id) {
+                match self.get_question_set_by_id(&origin.set_id) {
+                    Ok(set) => sets.insert(origin.set_id.clone(), set),
+                    Err(_) => continue,
+                };
+            }
+            let set: &QuestionSet = &sets[&origin.set_id];
+            if origin.set_version != set.version() {
+                continue;
+            }
+            if let Some(question) = set.questions().get(origin.index) {
+                if keep(set, question) {
+                    found.push((origin, set.name().clone(), question.clone()));
+                }
+            }
+        }
+        found
+    }
+
+    /// Puts together a quiz for `username` from questions in any set.
+    pub fn build_quiz(
+        &self,
+        username: &str,
+        source: &QuizSource,
+    ) -> Result<BuiltQuiz, UserReqError> {
+        let found = match source {
+            QuizSource::Set => {
+                return Err(UserReqError::new(
+                    BuildQuizError,
+                    "a single set is sat as it is".into(),
+                ))
+            }
+            QuizSource::Live(_) => {
+                return Err(UserReqError::new(
+                    BuildQuizError,
+                    "a live quiz is run by the teacher hosting it".into(),
+                ))
+            }
+            QuizSource::DailyReview => {
+                let due = self
+                    .due_questions(username)?
+                    .into_iter()
+                    .map(|state| state.question)
+                    .collect();
+                let mut due = self.look_up_questions(due, |_, _| true);
+                due.truncate(DAILY_REVIEW_LIMIT);
+                due
+            }
+            QuizSource::MistakeDeck(filter) => {
+                let mut deck = self.deck_questions(username, filter)?;
+                deck.truncate(DECK_QUIZ_LIMIT);
+                deck
+            }
+            QuizSource::Adaptive(topic) => self.topic_pool(topic)?,
+            QuizSource::Custom(custom) => {
+                custom
+                    .validate()
+                    .map_err(|why| UserReqError::new(BuildQuizError, why))?;
+                custom.draw(self.custom_pool(custom)?)
+            }
+        };
+        if found.is_empty() {
+            return Err(UserReqError::new(
+                BuildQuizError,
+                "there are no questions to ask right now".into(),
+            ));
+        }
+
+        let (origins, questions) = found
+            .into_iter()
+            .map(|(origin, _, question)| (origin, question))
+            .unzip();
+        Ok(BuiltQuiz {
+            set: QuestionSet::new(source.name().to_owned(), username.to_owned(), questions),
+            origins,
+            conditions: self.built_conditions(username, source)?,
+        })
+    }
+
+    /// Only custom quizzes can be timed, as chosen by the student, with
+    /// any extra time they are allowed added on.
+    fn built_conditions(
+        &self,
+        username: &str,
+        source: &QuizSource,
+    ) -> Result<QuizConditions, UserReqError> {
+        let arrangements = self.get_arrangements(username)?;
+        let time_limit = match source {
+            QuizSource::Custom(custom) => custom.time_limit.map(|l| arrangements.extend(l)),
+            _ => None,
+        };
+        Ok(QuizConditions {
+            time_limit,
+            arrangements,
+        })
+    }
+
+    /// Every question on `topic`, from any set, to draw an adaptive quiz
+    /// from.
+    fn topic_pool(
+        &self,
+        topic: &str,
+    ) -> Result<Vec<(QuestionRef, String, Question)>, UserReqError> {
+        self.pool_from(Self::tag_clause(topic), |set, question| {
+            has_topic(set.tags().iter().chain(question.tags()), topic)
+        })
+    }
+
+    /// Every question that a custom quiz could draw from.
+    fn custom_pool(
+        &self,
+        custom: &CustomQuiz,
+    ) -> Result<Vec<(QuestionRef, String, Question)>, UserReqError> {
+        let mut sources = vec![doc! { "name": { "$in": &custom.set_names } }];
+        sources.extend(custom.tags.iter().map(|tag| Self::tag_clause(tag)));
+        self.pool_from(doc! { "$or": sources }, |set, question| {
+            custom.wants(set, question)
+        })
+    }
+
+    /// Every question that `keep` accepts from the sets matching
+    /// `filter`, along with where it came from.
+    fn pool_from(
+        &self,
+        filter: Document,
+        keep: impl Fn(&QuestionSet, &Question) -> bool,
+    ) -> Result<Vec<(QuestionRef, String, Question)>, UserReqError> {
+        let sets = self
+            .get_questions()
+            .clone_with_type::<Document>()
+            .find(filter, None)
+            .map_err(|_| {
+                UserReqError::new(FetchQuestionsError, "could not fetch question sets".into())
+            })?
+            .filter_map(|v| v.ok());
+
+        let mut pool = vec![];
+        for document in sets {
+            let (Ok(id), Ok(set)) = (
+                document.get_object_id("_id"),
+                from_document::<QuestionSet>(document.clone()),
+            ) else {
+                continue;
+            };
+            for (index, question) in set.questions().iter().enumerate() {
+                if keep(&set, question) {
+                    let origin = QuestionRef {
+                        set_id: id.to_hex(),
+                        ..QuestionRef::new(set.version(), index)
+                    };
+                    pool.push((origin, set.name().clone(), question.clone()));
+                }
+            }
+        }
+        Ok(pool)
+    }
+
+    /// Works out the mastery estimate for an adaptive quiz from the
+    /// questions in it, rather than trusting the frontend's. Questions
+    /// that can no longer be found are left out.
+    fn mastery_of(&self, review: &QuizReview) -> MasteryEstimate {
+        let refs = review
+            .responses
+            .iter()
+            .map(|response| response.question().clone())
+            .collect();
+        let difficulties = self
+            .look_up_questions(refs, |_, _| true)
+            .into_iter()
+            .map(|(origin, _, question)| ((origin.set_id, origin.index), question.difficulty()))
+            .collect::<HashMap<_, _>>();
+        let answers = review.responses.iter().filter_map(|response| {
+            let question = response.question();
+            difficulties
+                .get(&(question.set_id.clone(), question.index))
+                .map(|&difficulty| (difficulty, response.is_correct()))
+        });
+        AdaptiveRun::replay(answers).estimate()
+    }
+
+    fn attempt_filter(username: &str, set_name: &str, assignment_id: Option<&str>) -> Document {
+        doc! { "username": username, "set_name": set_name, "assignment_id": assignment_id }
+    }
+
+    /// Saves an unfinished attempt, replacing the last save of it.
+    pub fn save_attempt(&self, mut attempt: QuizAttempt) -> Result<(), UserReqError> {
+        attempt.saved = Utc::now();
+        let filter = Self::attempt_filter(
+            &attempt.username,
+            &attempt.set_name,
+            attempt.assignment_id.as_deref(),
+        );
+        let options = ReplaceOptions::builder().upsert(true).build();
+        match self.get_attempts().replace_one(filter, &attempt, options) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(UserReqError::new(
+                SaveAttemptError,
+                "could not save the quiz attempt".into(),
+            )),
+        }
+    }
+
+    /// A student's unfinished attempts that can still be resumed, with
+    /// the most recently saved first. Attempts that have expired, or
+    /// whose set has been deleted, are thrown away as they are found.
+    pub fn find_attempts(&self, username: &str) -> Result<Vec<QuizAttempt>, UserReqError> {
+        self.live_attempts(doc! { "username": username })
+    }
+
+    /// The student's unfinished attempt at a set, if they have one.
+    pub fn get_attempt(
+        &self,
+        username: &str,
+        set_name: &str,
+        assignment_id: Option<&str>,
+    ) -> Result<Option<QuizAttempt>, UserReqError> {
+        let filter = doc! {
+            "username": username,
+            "set_name": set_name,
+            "assignment_id": assignment_id,
+        };
+        Ok(self.live_attempts(filter)?.into_iter().next())
+    }
+
+    /// The attempts matching `filter` that can still be resumed, the
+    /// most recently saved first, throwing away any that can't.
+    fn live_attempts(&self, filter: Document) -> Result<Vec<QuizAttempt>, UserReqError> {
+        let options = FindOptions::builder().sort(doc! { "saved": -1 }).build();
+        let attempts = match self.get_attempts().find(filter, options) {
+            Ok(v) => v.filter_map(|v| v.ok()).collect::<Vec<_>>(),
+            Err(_) => {
+                return Err(UserReqError::new(
+                    FetchAttemptsError,
+                    "could not fetch quiz attempts".into(),
+                ))
+            }
+        };
+
+        let now = Utc::now();
+        let mut live = vec![];
+        for attempt in attempts {
+            // Only a set that is really gone throws the attempt away, not
+            // a failure to look it up.
+            let set = self
+                .get_questions()
+                .find_one(doc! { "name": &attempt.set_name }, None)
+                .map_err(|_| {
+                    UserReqError::new(ConnectionError, "db operation failed".to_owned())
+                })?;
+            match set {
+                Some(set) if !attempt.expired(set.resume_hours(), now) => live.push(attempt),
+                _ => self.discard_attempt(
+                    &attempt.username,
+                    &attempt.set_name,
+                    attempt.assignment_id.as_deref(),
+                )?,
+            }
+        }
+        Ok(live)
+    }
+
+    pub fn discard_attempt(
+        &self,
+        username: &str,
+        set_name: &str,
+        assignment_id: Option<&str>,
+    ) -> Result<(), UserReqError> {
+        let filter = Self::attempt_filter(username, set_name, assignment_id);
+        match self.get_attempts().delete_one(filter, None) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(UserReqError::new(
+                DeleteAttemptError,
+                "the quiz attempt could not be discarded".to_owned(),
+            )),
+        }
+    }
+
+    fn delete_attempts_by(&self, username: &str) -> Result<(), UserReqError> {
+        self.get_attempts()
+            .delete_many(doc! { "username": username }, None)
+            .map_err(|_| {
+                UserReqError::new(
+                    DeleteAttemptError,
+                    "could not delete quiz attempts".to_owned(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn delete_memory_of_set(&self, set_id: &str) -> Result<(), UserReqError> {
+        self.get_memory()
+            .delete_many(doc! { "question.set_id": set_id }, None)
+            .map_err(|_| {
+                UserReqError::new(
+                    UpdateMemoryError,
+                    "could not reset the review schedule".to_owned(),
+                )
+            })?;
+        Ok(())
+    }
+
+    fn delete_memory_of(&self, username: &str) -> Result<(), UserReqError> {
+        self.get_memory()
+            .delete_many(doc! { "username": username }, None)
+            .map_err(|_| {
+                UserReqError::new(
+                    UpdateMemoryError,
+                    "could not delete the review schedule".to_owned(),
+                )
+            })?;
+        Ok(())
+    }
+}
+
+/// Reads a number out of an aggregation result, whichever numeric type
+/// MongoDB chose for it. Anything missing or non-numeric reads as zero.
+fn number(document: &Document, key: &str) -> f64 {
+    match document.get(key) {
+        Some(Bson::Int32(n)) => *n as f64,
+        Some(Bson::Int64(n)) => *n as f64,
+        Some(Bson::Double(n)) => *n,
+        _ => 0.0,
+    }
+}
+
+/// Escapes anything a user typed so it is matched literally inside
+/// a MongoDB regular expression.
+fn escape_regex(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
 }
@@ -0,0 +1,266 @@
+//! Runs live quizzes over the local network.
+//!
+//! A teacher's copy of the app hosts the session on a small HTTP server,
+//! and each student's copy sends it JSON [`LiveMessage`]s, polling to
+//! find out when the teacher moves on. Several copies of the app on one
+//! machine can take part by joining `localhost`.
+
+use once_cell::sync::Lazy;
+use shared::{
+    live::{LiveHostView, LiveMessage, LiveRequest, LiveSession, LiveView, LIVE_PORT},
+    questions::QuestionSet,
+    requests::{
+        UserReqError,
+        UserReqErrorKind::{ConnectionError, LiveSessionError},
+    },
+};
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    io::{self, BufRead, BufReader, ErrorKind, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs, UdpSocket},
+    sync::Mutex,
+    thread,
+    time::Duration,
+};
+
+/// How long either end waits on the other before giving up.
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// The largest request body the host will read.
+const MAX_BODY: usize = 64 * 1024;
+
+/// The session this copy of the app is hosting, if any.
+static HOSTED: Lazy<Mutex<Option<LiveSession>>> = Lazy::new(|| Mutex::new(None));
+/// Whether the server has been started. It keeps running once it has,
+/// answering that there is no session between them.
+static SERVING: Mutex<bool> = Mutex::new(false);
+/// The session this copy of the app joined, if any.
+static JOINED: Lazy<Mutex<Option<Joined>>> = Lazy::new(|| Mutex::new(None));
+
+/// Where a joined session is, and what to send it.
+#[derive(Clone)]
+struct Joined {
+    address: String,
+    code: String,
+    token: String,
+}
+
+fn live_error(message: &str) -> UserReqError {
+    UserReqError::new(LiveSessionError, message.to_owned())
+}
+
+/// Starts hosting `set`, in place of any session already being hosted.
+pub fn host(set: QuestionSet, set_id: String) -> Result<LiveHostView, UserReqError> {
+    let mut serving = SERVING.lock().unwrap();
+    if !*serving {
+        let listener = TcpListener::bind(("0.0.0.0", LIVE_PORT))
+            .map_err(|_| live_error(&format!("could not open port {LIVE_PORT} to host on")))?;
+        thread::spawn(move || serve(listener));
+        *serving = true;
+    }
+    *HOSTED.lock().unwrap() = Some(LiveSession::new(set, set_id, random_seed()));
+    host_view()
+}
+
+/// A seed no one on the network could guess, taken from the random keys
+/// the standard library gives each hash map.
+fn random_seed() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_i64(chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default());
+    hasher.finish()
+}
+
+/// Changes the hosted session with `change`, then shows it to the
+/// teacher.
+pub fn update_hosted(change: impl FnOnce(&mut LiveSession)) -> Result<LiveHostView, UserReqError> {
+    match HOSTED.lock().unwrap().as_mut() {
+        Some(session) => change(session),
+        None => return Err(live_error("you are not hosting a live quiz")),
+    }
+    host_view()
+}
+
+pub fn host_view() -> Result<LiveHostView, UserReqError> {
+    let hosted = HOSTED.lock().unwrap();
+    let session = hosted
+        .as_ref()
+        .ok_or(live_error("you are not hosting a live quiz"))?;
+    Ok(LiveHostView {
+        address: local_address(),
+        ..session.host_view()
+    })
+}
+
+/// This machine's address on the local network, found by asking which
+/// address would be used to reach the outside world. Nothing is sent.
+fn local_address() -> String {
+    UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| {
+            socket.connect("8.8.8.8:80")?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_else(|_| String::from("localhost"))
+}
+
+fn serve(listener: TcpListener) {
+    for stream in listener.incoming().flatten() {
+        thread::spawn(move || {
+            let _ = answer_request(stream);
+        });
+    }
+}
+
+fn answer_request(mut stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    let body = read_body(&mut BufReader::new(&stream))?;
+    let reply = match serde_json::from_slice::<LiveMessage>(&body) {
+        Ok(message) => respond(message),
+        Err(_) => Err(String::from("the request could not be read")),
+    };
+    let body = serde_json::to_string(&reply)?;
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    )
+}
+
+fn respond(message: LiveMessage) -> Result<LiveView, String> {
+    let mut hosted = HOSTED.lock().unwrap();
+    let session = hosted
+        .as_mut()
+        .filter(|session| session.code == message.code.trim().to_uppercase())
+        .ok_or(String::from("there is no live quiz with that code"))?;
+    let username = &message.username;
+    let token = message.token.as_deref();
+    match message.request {
+        LiveRequest::Join => {
+            session.join(username, token)?;
+        }
+        LiveRequest::Poll => session.check(username, token)?,
+        LiveRequest::Answer {
+            question,
+            submissions,
+        } => {
+            session.check(username, token)?;
+            session.answer(username, question, submissions)?
+        }
+    }
+    Ok(session.view(username))
+}
+
+/// Reads the headers of an HTTP message, whether a request or a reply,
+/// and returns the body that follows them.
+fn read_body(reader: &mut impl BufRead) -> io::Result<Vec<u8>> {
+    let mut length = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(ErrorKind::UnexpectedEof.into());
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| io::Error::from(ErrorKind::InvalidData))?;
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Err(ErrorKind::InvalidData.into());
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(body)
+}
+
+/// Sends `message` to the host at `address`, which is a machine name
+/// or IP, with a port on the end if it isn't the usual one.
+fn send(address: &str, message: &LiveMessage) -> Result<LiveView, UserReqError> {
+    let unreachable = || UserReqError::new(ConnectionError, format!("could not reach {address}"));
+    let address = match address.contains(':') {
+        true => address.to_owned(),
+        false => format!("{address}:{LIVE_PORT}"),
+    };
+    let target = address
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .ok_or_else(unreachable)?;
+    let body = serde_json::to_string(message).map_err(|_| unreachable())?;
+
+    let reply = (|| -> io::Result<Vec<u8>> {
+        let mut stream = TcpStream::connect_timeout(&target, TIMEOUT)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        write!(
+            stream,
+            "POST /live HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            address,
+            body.len(),
+            body
+        )?;
+        read_body(&mut BufReader::new(&stream))
+    })()
+    .map_err(|_| unreachable())?;
+
+    serde_json::from_slice::<Result<LiveView, String>>(&reply)
+        .map_err(|_| unreachable())?
+        .map_err(|why| live_error(&why))
+}
+
+/// Joins the session with `code` hosted at `address`, and remembers it
+/// for the rest of the quiz. Joining the same session again sends the
+/// token given the first time.
+pub fn join(address: String, code: String, username: &str) -> Result<LiveView, UserReqError> {
+    let address = address.trim().to_owned();
+    let code = code.trim().to_uppercase();
+    let token = JOINED
+        .lock()
+        .unwrap()
+        .clone()
+        .filter(|joined| joined.address == address && joined.code == code)
+        .map(|joined| joined.token);
+    let message = LiveMessage {
+        code: code.clone(),
+        username: username.to_owned(),
+        token,
+        request: LiveRequest::Join,
+    };
+    let view = send(&address, &message)?;
+    *JOINED.lock().unwrap() = Some(Joined {
+        address,
+        code,
+        token: view.token.clone(),
+    });
+    Ok(view)
+}
+
+/// Sends `request` to the session that was joined.
+pub fn ask(request: LiveRequest, username: &str) -> Result<LiveView, UserReqError> {
+    let joined = JOINED
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or(live_error("you have not joined a live quiz"))?;
+    let message = LiveMessage {
+        code: joined.code,
+        username: username.to_owned(),
+        token: Some(joined.token),
+        request,
+    };
+    send(&joined.address, &message)
+}
+
+/// Forgets the session that was joined, as there is nothing more to ask
+/// of it.
+pub fn leave() {
+    *JOINED.lock().unwrap() = None;
+}
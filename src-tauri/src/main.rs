@@ -6,10 +6,29 @@
 //! them up at **compile time** so that they can interact with a WASM based frontend.
 
 pub mod database;
+pub mod live;
+pub mod session;
 
 use database::DatabaseManager;
 use once_cell::sync::Lazy;
-use shared::{questions::QuestionSet, requests::UserReqError, responses::QuizReview, User};
+use shared::{
+    analytics::{CalibrationReport, ItemAnalysis, ProgressReport},
+    arrangements::{AccessArrangements, QuizConditions},
+    assignments::{Assignment, CompletionGrid, StudentAssignment},
+    attempts::QuizAttempt,
+    classes::Class,
+    live::{LiveHostView, LiveRequest, LiveView},
+    mistakes::{DeckEntry, DeckFilter},
+    queries::{Page, Paged, ReviewQuery, ReviewScope, SetQuery},
+    questions::{QuestionSet, SetSummary},
+    requests::{
+        UserReqError,
+        UserReqErrorKind::{AddClassError, ExportError, PermissionDenied},
+    },
+    responses::QuizReview,
+    sources::{BuiltQuiz, QuizSource},
+    AccessLevel, User,
+};
 
 // Statics are somewhat like constants - the important aspect of them that is relevant
 // here is that they are available everywhere in the program, on every thread, no matter
@@ -30,7 +49,14 @@ const DBM: Lazy<DatabaseManager> =
 /// login attempt was succesful, and of course, returning all of the final states of this process.
 #[tauri::command]
 fn validate_login(username: String, password: String) -> Result<User, UserReqError> {
-    DBM.validate_login(username, password)
+    let user = DBM.validate_login(username, password)?;
+    session::log_in(user.clone());
+    Ok(user)
+}
+
+#[tauri::command]
+fn log_out() {
+    session::log_out()
 }
 
 #[tauri::command]
@@ -78,14 +104,28 @@ fn get_question_sets() -> Result<Vec<QuestionSet>, UserReqError> {
     DBM.get_question_sets()
 }
 
+#[tauri::command]
+fn find_set_summaries(query: SetQuery, page: Page) -> Result<Paged<SetSummary>, UserReqError> {
+    DBM.find_set_summaries(query, page)
+}
+
+#[tauri::command]
+fn get_set_tags() -> Result<Vec<String>, UserReqError> {
+    DBM.get_set_tags()
+}
+
+/// Publishes a set as the logged in teacher's own, whatever author the
+/// frontend sent with it.
 #[tauri::command]
 fn add_question_set(new_set: QuestionSet) -> Result<(), UserReqError> {
-    DBM.add_question_set(new_set)
+    let user = session::require(&[AccessLevel::TEACHER, AccessLevel::ADMIN])?;
+    DBM.add_question_set(new_set.with_author(user.username().clone()))
 }
 
 #[tauri::command]
 fn delete_question_set(name: String) -> Result<(), UserReqError> {
-    DBM.delete_question_set(name)
+    let user = session::require(&[AccessLevel::TEACHER, AccessLevel::ADMIN])?;
+    DBM.delete_question_set(name, &user)
 }
 
 #[tauri::command]
@@ -93,14 +133,335 @@ fn get_question_set(name: String) -> Result<QuestionSet, UserReqError> {
     DBM.get_question_set(name)
 }
 
+/// The logged in user's progress across every quiz they have taken.
+#[tauri::command]
+fn get_progress() -> Result<ProgressReport, UserReqError> {
+    DBM.get_progress(session::current_user()?.username())
+}
+
+/// How well the logged in user's confidence in their answers matches
+/// how often they are right.
+#[tauri::command]
+fn get_calibration() -> Result<CalibrationReport, UserReqError> {
+    DBM.get_calibration(session::current_user()?.username())
+}
+
+/// A question by question breakdown of how students did on a set.
+#[tauri::command]
+fn get_item_analysis(set_name: String) -> Result<ItemAnalysis, UserReqError> {
+    session::require(&[AccessLevel::TEACHER, AccessLevel::ADMIN])?;
+    DBM.get_item_analysis(&set_name)
+}
+
+/// Writes the item analysis for a set to a CSV file in the user's
+/// downloads folder, and returns where it was saved.
+#[tauri::command]
+fn export_item_analysis(set_name: String) -> Result<String, UserReqError> {
+    session::require(&[AccessLevel::TEACHER, AccessLevel::ADMIN])?;
+    let analysis = DBM.get_item_analysis(&set_name)?;
+
+    let folder = tauri::api::path::download_dir()
+        .or_else(tauri::api::path::home_dir)
+        .ok_or(UserReqError::new(
+            ExportError,
+            "could not find a folder to save to".into(),
+        ))?;
+    let file_name = set_name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    let path = folder.join(format!("{file_name}-item-analysis.csv"));
+
+    std::fs::write(&path, analysis.to_csv())
+        .map_err(|_| UserReqError::new(ExportError, "could not write the CSV file".into()))?;
+    Ok(path.display().to_string())
+}
+
+/// Fetches a class that the logged in user is allowed to change, which
+/// means they either teach it or are an admin.
+fn managed_class(name: &str) -> Result<Class, UserReqError> {
+    let user = session::require(&[AccessLevel::TEACHER, AccessLevel::ADMIN])?;
+    let class = DBM.get_class(name)?;
+    if user.access_level() == &AccessLevel::ADMIN || class.is_teacher(user.username()) {
+        Ok(class)
+    } else {
+        Err(UserReqError::new(
+            PermissionDenied,
+            "you do not teach that class".into(),
+        ))
+    }
+}
+
+/// Creates a new class taught by whoever is logged in.
+#[tauri::command]
+fn add_class(name: String) -> Result<(), UserReqError> {
+    let user = session::require(&[AccessLevel::TEACHER, AccessLevel::ADMIN])?;
+    let name = name.trim().to_owned();
+    if name.is_empty() {
+        return Err(UserReqError::new(
+            AddClassError,
+            "a class needs a name".into(),
+        ));
+    }
+    DBM.add_class(Class::new(name, user.username().clone()))
+}
+
+/// The classes that the logged in user teaches or is enrolled in.
+#[tauri::command]
+fn get_classes() -> Result<Vec<Class>, UserReqError> {
+    DBM.find_classes(&session::current_user()?)
+}
+
+#[tauri::command]
+fn delete_class(name: String) -> Result<(), UserReqError> {
+    managed_class(&name)?;
+    DBM.delete_class(&name)
+}
+
+#[tauri::command]
+fn enrol_student(class_name: String, username: String) -> Result<(), UserReqError> {
+    managed_class(&class_name)?;
+    DBM.enrol_student(&class_name, &username)
+}
+
+#[tauri::command]
+fn unenrol_student(class_name: String, username: String) -> Result<(), UserReqError> {
+    managed_class(&class_name)?;
+    DBM.unenrol_student(&class_name, &username)
+}
+
+#[tauri::command]
+fn add_class_teacher(class_name: String, username: String) -> Result<(), UserReqError> {
+    managed_class(&class_name)?;
+    DBM.add_class_teacher(&class_name, &username)
+}
+
+/// Sets a question set as homework for one of the logged in teacher's
+/// classes, and returns it with the ID it was saved under.
+#[tauri::command]
+fn add_assignment(assignment: Assignment) -> Result<Assignment, UserReqError> {
+    let user = session::require(&[AccessLevel::TEACHER, AccessLevel::ADMIN])?;
+    managed_class(assignment.class_name())?;
+    DBM.add_assignment(
+        Assignment::new(
+            assignment.set_name().clone(),
+            assignment.class_name().clone(),
+            user.username().clone(),
+            *assignment.opens(),
+            *assignment.due(),
+            assignment.attempt_limit(),
+        )
+        .with_time_limit(assignment.time_limit()),
+    )
+}
+
+/// Every assignment set for the classes the logged in user can see.
+#[tauri::command]
+fn get_assignments() -> Result<Vec<Assignment>, UserReqError> {
+    DBM.find_assignments(&session::current_user()?)
+}
+
+/// The logged in student's assignments, with where they are up to.
+#[tauri::command]
+fn get_my_assignments() -> Result<Vec<StudentAssignment>, UserReqError> {
+    DBM.student_assignments(&session::require(&[AccessLevel::USER])?)
+}
+
+#[tauri::command]
+fn get_completion_grid(id: String) -> Result<CompletionGrid, UserReqError> {
+    managed_class(DBM.get_assignment(&id)?.class_name())?;
+    DBM.get_completion_grid(&id)
+}
+
+#[tauri::command]
+fn delete_assignment(id: String) -> Result<(), UserReqError> {
+    managed_class(DBM.get_assignment(&id)?.class_name())?;
+    DBM.delete_assignment(&id)
+}
+
+/// How the logged in user must sit a set, or an assignment of it.
+#[tauri::command]
+fn get_quiz_conditions(
+    set_name: String,
+    assignment_id: Option<String>,
+) -> Result<QuizConditions, UserReqError> {
+    let user = session::current_user()?;
+    DBM.quiz_conditions(user.username(), &set_name, assignment_id.as_deref())
+}
+
+/// Makes sure the logged in user can change `username`'s settings:
+/// admins can change anybody's, and teachers can change those of the
+/// students in their classes.
+fn managed_student(username: &str) -> Result<(), UserReqError> {
+    let user = session::require(&[AccessLevel::TEACHER, AccessLevel::ADMIN])?;
+    if user.access_level() == &AccessLevel::ADMIN
+        || DBM
+            .students_of(user.username())?
+            .iter()
+            .any(|s| s == username)
+    {
+        Ok(())
+    } else {
+        Err(UserReqError::new(
+            PermissionDenied,
+            "you do not teach that student".into(),
+        ))
+    }
+}
+
 #[tauri::command]
-fn add_quiz_review(new_review: QuizReview) -> Result<(), UserReqError> {
+fn get_arrangements(username: String) -> Result<AccessArrangements, UserReqError> {
+    managed_student(&username)?;
+    DBM.get_arrangements(&username)
+}
+
+#[tauri::command]
+fn set_arrangements(
+    username: String,
+    arrangements: AccessArrangements,
+) -> Result<(), UserReqError> {
+    managed_student(&username)?;
+    DBM.set_arrangements(&username, &arrangements)
+}
+
+/// The review is always filed under whoever is logged in, whatever
+/// username the frontend put on it.
+#[tauri::command]
+fn add_quiz_review(mut new_review: QuizReview) -> Result<(), UserReqError> {
+    new_review.username = session::current_user()?.username().clone();
     DBM.add_quiz_review(new_review)
 }
 
+/// Saves the logged in user's progress through a quiz so far.
+#[tauri::command]
+fn save_attempt(mut attempt: QuizAttempt) -> Result<(), UserReqError> {
+    attempt.username = session::current_user()?.username().clone();
+    DBM.save_attempt(attempt)
+}
+
+/// The logged in user's unfinished attempt at a set, if there is one
+/// that can still be resumed.
 #[tauri::command]
-fn get_quiz_reviews() -> Result<Vec<QuizReview>, UserReqError> {
-    DBM.get_quiz_reviews()
+fn get_attempt(
+    set_name: String,
+    assignment_id: Option<String>,
+) -> Result<Option<QuizAttempt>, UserReqError> {
+    let user = session::current_user()?;
+    DBM.get_attempt(user.username(), &set_name, assignment_id.as_deref())
+}
+
+#[tauri::command]
+fn get_my_attempts() -> Result<Vec<QuizAttempt>, UserReqError> {
+    DBM.find_attempts(session::current_user()?.username())
+}
+
+#[tauri::command]
+fn discard_attempt(set_name: String, assignment_id: Option<String>) -> Result<(), UserReqError> {
+    let user = session::current_user()?;
+    DBM.discard_attempt(user.username(), &set_name, assignment_id.as_deref())
+}
+
+/// Puts together a quiz for the logged in user from questions in
+/// several sets, such as today's daily review.
+#[tauri::command]
+fn build_quiz(source: QuizSource) -> Result<BuiltQuiz, UserReqError> {
+    DBM.build_quiz(session::current_user()?.username(), &source)
+}
+
+/// How many questions the logged in user has due for review today.
+#[tauri::command]
+fn get_due_count() -> Result<u32, UserReqError> {
+    let user = session::current_user()?;
+    Ok(DBM.due_questions(user.username())?.len() as u32)
+}
+
+/// The questions the logged in user got wrong and hasn't put right yet.
+#[tauri::command]
+fn get_mistake_deck(filter: DeckFilter) -> Result<Vec<DeckEntry>, UserReqError> {
+    DBM.mistake_deck(session::current_user()?.username(), &filter)
+}
+
+/// Starts hosting a live quiz of `set_name` for a class to join.
+#[tauri::command]
+fn host_live_session(set_name: String) -> Result<LiveHostView, UserReqError> {
+    session::require(&[AccessLevel::TEACHER, AccessLevel::ADMIN])?;
+    let set = DBM.get_question_set(set_name.clone())?;
+    live::host(set, DBM.find_set_id(&set_name)?)
+}
+
+#[tauri::command]
+fn get_live_host_view() -> Result<LiveHostView, UserReqError> {
+    session::require(&[AccessLevel::TEACHER, AccessLevel::ADMIN])?;
+    live::host_view()
+}
+
+/// Puts up the next question of the hosted live quiz, or finishes it
+/// after the last one.
+#[tauri::command]
+fn advance_live_session() -> Result<LiveHostView, UserReqError> {
+    session::require(&[AccessLevel::TEACHER, AccessLevel::ADMIN])?;
+    live::update_hosted(|session| session.advance())
+}
+
+#[tauri::command]
+fn end_live_session() -> Result<LiveHostView, UserReqError> {
+    session::require(&[AccessLevel::TEACHER, AccessLevel::ADMIN])?;
+    live::update_hosted(|session| session.finish())
+}
+
+/// Joins the live quiz with `code` that is hosted at `address`.
+#[tauri::command]
+fn join_live_session(address: String, code: String) -> Result<LiveView, UserReqError> {
+    live::join(address, code, session::current_user()?.username())
+}
+
+/// What is happening in the live quiz that was joined. When it has
+/// finished, the logged in user's attempt is saved like any other.
+#[tauri::command]
+fn get_live_view() -> Result<LiveView, UserReqError> {
+    let user = session::current_user()?;
+    save_live_review(live::ask(LiveRequest::Poll, user.username())?, &user)
+}
+
+/// Saves the user's attempt once the live quiz has finished. The session
+/// is only forgotten after that, so that a failed save can be tried again.
+fn save_live_review(view: LiveView, user: &User) -> Result<LiveView, UserReqError> {
+    if let Some(mut review) = view.review.clone() {
+        review.username = user.username().clone();
+        DBM.add_quiz_review(review)?;
+        live::leave();
+    }
+    Ok(view)
+}
+
+#[tauri::command]
+fn answer_live_question(
+    question: usize,
+    submissions: Vec<String>,
+) -> Result<LiveView, UserReqError> {
+    let user = session::current_user()?;
+    let request = LiveRequest::Answer {
+        question,
+        submissions,
+    };
+    save_live_review(live::ask(request, user.username())?, &user)
+}
+
+/// Searches the quiz reviews that the logged in user is allowed to see.
+/// Students can only ever see their own, teachers can also see their
+/// students', and admins can see everybody's.
+#[tauri::command]
+fn find_quiz_reviews(
+    scope: ReviewScope,
+    query: ReviewQuery,
+    page: Page,
+) -> Result<Paged<QuizReview>, UserReqError> {
+    let user = match scope {
+        ReviewScope::Mine => session::current_user()?,
+        ReviewScope::Students => session::require(&[AccessLevel::TEACHER, AccessLevel::ADMIN])?,
+        ReviewScope::All => session::require(&[AccessLevel::ADMIN])?,
+    };
+    DBM.find_quiz_reviews(&user, scope, query, page)
 }
 
 /// This is the starting point of the backend. It creates a `Builder` object
@@ -111,20 +472,68 @@ fn get_quiz_reviews() -> Result<Vec<QuizReview>, UserReqError> {
 /// `tauri.conf.json` file inside the `src-tauri` workspace to load the other configuration
 /// of the project.
 fn main() {
+    // Old reviews are brought up to date before anything can read them.
+    match DBM.migrate_legacy_reviews() {
+        Ok(0) => {}
+        Ok(n) => println!("Migrated {n} old quiz reviews"),
+        Err(why) => eprintln!("{why}"),
+    }
+    // The review schedule is then built from the reviews saved before it.
+    match DBM.migrate_memory() {
+        Ok(0) => {}
+        Ok(n) => println!("Scheduled questions from {n} old quiz reviews"),
+        Err(why) => eprintln!("{why}"),
+    }
+
     tauri::Builder::default()
         .invoke_handler(tauri::generate_handler![
             validate_login,
+            log_out,
             add_user,
             debug_fetch_all,
             all_usernames,
             unlock_user,
             delete_user,
             get_question_sets,
+            find_set_summaries,
+            get_set_tags,
             add_question_set,
             delete_question_set,
             get_question_set,
             add_quiz_review,
-            get_quiz_reviews,
+            get_quiz_conditions,
+            save_attempt,
+            get_attempt,
+            get_my_attempts,
+            discard_attempt,
+            build_quiz,
+            get_due_count,
+            get_mistake_deck,
+            get_arrangements,
+            set_arrangements,
+            find_quiz_reviews,
+            get_progress,
+            get_calibration,
+            host_live_session,
+            get_live_host_view,
+            advance_live_session,
+            end_live_session,
+            join_live_session,
+            get_live_view,
+            answer_live_question,
+            get_item_analysis,
+            export_item_analysis,
+            add_class,
+            get_classes,
+            delete_class,
+            enrol_student,
+            unenrol_student,
+            add_class_teacher,
+            add_assignment,
+            get_assignments,
+            get_my_assignments,
+            get_completion_grid,
+            delete_assignment,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,51 @@
+//! Keeps track of who is logged in to this copy of the app.
+//!
+//! The frontend could claim to be anybody in the arguments it sends,
+//! so any command that should only show a user their own data asks
+//! this module who logged in instead. The user is remembered when
+//! `validate_login` succeeds and forgotten when they log out.
+
+use once_cell::sync::Lazy;
+use shared::{
+    requests::{
+        UserReqError,
+        UserReqErrorKind::{NotLoggedIn, PermissionDenied},
+    },
+    AccessLevel, User,
+};
+use std::sync::Mutex;
+
+// Unlike the database manager this has to be a real static, so that
+// every command sees the same logged in user.
+static SESSION: Lazy<Mutex<Option<User>>> = Lazy::new(|| Mutex::new(None));
+
+/// Remembers `user` as the one using the app.
+pub fn log_in(user: User) {
+    *SESSION.lock().unwrap() = Some(user);
+}
+
+pub fn log_out() {
+    *SESSION.lock().unwrap() = None;
+}
+
+/// The logged in user, or an error if nobody has logged in yet.
+pub fn current_user() -> Result<User, UserReqError> {
+    SESSION
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or(UserReqError::new(NotLoggedIn, "nobody is logged in".into()))
+}
+
+/// The logged in user, as long as their access level is one of `levels`.
+pub fn require(levels: &[AccessLevel]) -> Result<User, UserReqError> {
+    let user = current_user()?;
+    if levels.contains(user.access_level()) {
+        Ok(user)
+    } else {
+        Err(UserReqError::new(
+            PermissionDenied,
+            "you do not have permission to do that".into(),
+        ))
+    }
+}
@@ -0,0 +1,172 @@
+#![allow(non_camel_case_types)]
+
+use crate::{
+    app::Route,
+    commands::{invoke_export_item_analysis, invoke_get_item_analysis},
+    components::{
+        charts::BarChart,
+        inputs::Button,
+        layout::{Column, Row},
+        theme_ctx::use_theme,
+        user_ctx::use_user,
+    },
+};
+use shared::{analytics::ItemAnalysis, requests::UserReqError, AccessLevel};
+use stylist::yew::styled_component;
+use tauri_sys::dialog::{MessageDialogBuilder, MessageDialogKind};
+use yew::{
+    platform::spawn_local,
+    prelude::*,
+    suspense::{use_future_with, Suspension, UseFutureHandle},
+};
+use yew_autoprops::autoprops;
+use yew_router::{components::Redirect, hooks::use_navigator};
+
+#[hook]
+pub fn use_item_analysis(
+    set_name: AttrValue,
+) -> Result<UseFutureHandle<Result<ItemAnalysis, UserReqError>>, Suspension> {
+    use_future_with(set_name, |name| {
+        let name = name.to_string();
+        async move { invoke_get_item_analysis(name).await }
+    })
+}
+
+/// The teacher's item analysis of a set, showing how hard each question
+/// was and whether it separates stronger students from weaker ones.
+#[autoprops]
+#[styled_component(Analysis)]
+pub fn analysis(set_name: AttrValue) -> Html {
+    let theme = use_theme();
+    let nav = use_navigator().unwrap();
+    let user = use_user();
+    let analysis = use_item_analysis(set_name.clone());
+
+    match (*user).clone().inner {
+        Some(user) if user.access_level() != &AccessLevel::USER => {}
+        Some(_) => return html! { <Redirect<Route> to={Route::Dashboard}/> },
+        None => return html! { <Redirect<Route> to={Route::Login}/> },
+    }
+
+    let analysis = match analysis {
+        Ok(res) => match res.clone() {
+            Ok(analysis) => analysis,
+            Err(why) => return html! { <p>{ why.message }</p> },
+        },
+        Err(_) => return html! { "Loading..." },
+    };
+
+    let onexport = {
+        let set_name = set_name.to_string();
+        move |_| {
+            let set_name = set_name.clone();
+            spawn_local(async move {
+                let (kind, message) = match invoke_export_item_analysis(set_name).await {
+                    Ok(path) => (MessageDialogKind::Info, format!("Saved to {path}")),
+                    Err(why) => (MessageDialogKind::Error, why.message),
+                };
+                let _ = MessageDialogBuilder::new()
+                    .set_title("Item Analysis")
+                    .set_kind(kind)
+                    .message(&message)
+                    .await;
+            });
+        }
+    };
+
+    let class = css!(
+        r#"
+            margin: ${fs};
+            background-color: ${bg};
+
+            p {
+                flex: 1;
+                text-align: center;
+            }
+
+            h1, h2 {
+                padding: calc( 0.5 * ${fs} );
+            }
+
+            button {
+                margin: calc( 0.25 * ${fs} );
+            }
+        "#,
+        fs = theme.font_size,
+        bg = theme.bg_color,
+    );
+
+    let bars = analysis
+        .items
+        .iter()
+        .map(|item| {
+            (
+                AttrValue::from(format!("Q{}", item.index + 1)),
+                item.facility,
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut alternate = true;
+    let rows = analysis
+        .items
+        .iter()
+        .map(|item| {
+            let class = if alternate {
+                css!("background-color: ${bg};", bg = theme.bg_shade)
+            } else {
+                css!()
+            };
+            alternate = !alternate;
+            let discrimination = match item.discrimination {
+                Some(d) => format!("{d:.2}"),
+                None => String::from("-"),
+            };
+            let wrong = item
+                .wrong_answers
+                .iter()
+                .map(|w| format!("{} ({})", w.answer, w.count))
+                .collect::<Vec<_>>()
+                .join(", ");
+            html! {
+                <Row {class} wfill={true} justify_content={"space-between"} align_items={"center"}>
+                    <p>{ item.index + 1 }</p>
+                    <p>{ &item.question.title }</p>
+                    <p>{ &item.question.markup }</p>
+                    <p>{ item.attempts }</p>
+                    <p>{ format!("{:.0}%", item.facility) }</p>
+                    <p>{ format!("{:.1}s", item.average_time) }</p>
+                    <p>{ discrimination }</p>
+                    <p>{ wrong }</p>
+                </Row>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <Column hfill={true} {class}>
+            <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
+                <Button onclick={move |_| nav.push(&Route::Browser)}>{ "← Back" }</Button>
+                <h1>{ "Item Analysis: " }{ &analysis.set_name }</h1>
+                <Button onclick={onexport}>{ "Export CSV" }</Button>
+            </Row>
+            <p>{ analysis.attempts }{ " attempts" }</p>
+            if analysis.items.is_empty() {
+                <p>{ "Nobody has taken this set yet." }</p>
+            } else {
+                <h2>{ "Facility (% correct)" }</h2>
+                <BarChart {bars} />
+                <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
+                    <p><b>{ "#" }</b></p>
+                    <p><b>{ "Question" }</b></p>
+                    <p><b>{ "Equation" }</b></p>
+                    <p><b>{ "Attempts" }</b></p>
+                    <p><b>{ "Facility" }</b></p>
+                    <p><b>{ "Average Time" }</b></p>
+                    <p><b>{ "Discrimination" }</b></p>
+                    <p><b>{ "Common Wrong Answers" }</b></p>
+                </Row>
+                { rows }
+            }
+        </Column>
+    }
+}
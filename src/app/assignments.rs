@@ -0,0 +1,182 @@
+use crate::{
+    app::Route,
+    commands::invoke_add_assignment,
+    components::{
+        assignments::{use_assignments, AssignmentCard},
+        classes::use_classes,
+        inputs::{Button, DateInput, SelectInput},
+        layout::{Column, Row},
+        questions::use_set_summaries,
+        theme_ctx::use_theme,
+        user_ctx::use_user,
+    },
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use shared::{
+    assignments::Assignment,
+    queries::{Page, SetQuery},
+    timing::TimeLimit,
+    AccessLevel,
+};
+use stylist::yew::styled_component;
+use tauri_sys::dialog::{MessageDialogBuilder, MessageDialogKind};
+use yew::{platform::spawn_local, prelude::*};
+use yew_router::{components::Redirect, hooks::use_navigator};
+
+/// Shown in the class and set pickers until something is chosen.
+const UNCHOSEN: &str = "Choose...";
+/// The time limit option that leaves the set's own limit in place.
+const SET_TIME_LIMIT: &str = "Set's own";
+
+/// Reads the value of a date picker as the start of that day, or the
+/// end of it for the due date so work is due by midnight.
+fn picked_date(value: &str, end_of_day: bool) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    let time = match end_of_day {
+        true => date.and_hms_opt(23, 59, 59),
+        false => date.and_hms_opt(0, 0, 0),
+    };
+    Some(time?.and_utc())
+}
+
+/// Where teachers set question sets as homework for their classes and
+/// see who has done it.
+#[styled_component(AssignmentManager)]
+pub fn assignment_manager() -> Html {
+    let nav = use_navigator().unwrap();
+    let theme = use_theme();
+    let dependency = use_state(|| false);
+    let sets_changed = use_state(|| false);
+    let class_name = use_state_eq(|| AttrValue::from(UNCHOSEN));
+    let set_name = use_state_eq(|| AttrValue::from(UNCHOSEN));
+    let opens = use_state_eq(|| AttrValue::from(String::new()));
+    let due = use_state_eq(|| AttrValue::from(String::new()));
+    let attempt_limit = use_state_eq(|| AttrValue::from("Unlimited"));
+    let time_limit = use_state_eq(|| AttrValue::from(SET_TIME_LIMIT));
+    let classes = use_classes(dependency.clone());
+    let sets = use_set_summaries(SetQuery::default(), Page::new(0, 1000), sets_changed);
+    let assignments = use_assignments(dependency.clone());
+
+    let user = use_user();
+    match (*user).clone().inner {
+        Some(user) if user.access_level() != &AccessLevel::USER => {}
+        Some(_) => return html! { <Redirect<Route> to={Route::Dashboard}/> },
+        None => return html! { <Redirect<Route> to={Route::Login}/> },
+    }
+
+    let classes = match classes {
+        Ok(classes) => classes.clone().unwrap_or_default(),
+        Err(_) => vec![],
+    };
+    let class_options = std::iter::once(AttrValue::from(UNCHOSEN))
+        .chain(classes.iter().map(|c| AttrValue::from(c.name().clone())))
+        .collect::<Vec<_>>();
+    let sets = match sets {
+        Ok(sets) => sets.clone().map(|paged| paged.items).unwrap_or_default(),
+        Err(_) => vec![],
+    };
+    let set_options = std::iter::once(AttrValue::from(UNCHOSEN))
+        .chain(sets.iter().map(|s| AttrValue::from(s.name.clone())))
+        .collect::<Vec<_>>();
+    let limit_options = ["Unlimited", "1", "2", "3", "5"]
+        .into_iter()
+        .map(AttrValue::from)
+        .collect::<Vec<_>>();
+    let time_limit_options = std::iter::once(AttrValue::from(SET_TIME_LIMIT))
+        .chain(
+            TimeLimit::presets()
+                .iter()
+                .map(|l| AttrValue::from(l.name())),
+        )
+        .collect::<Vec<_>>();
+
+    let opens_at = picked_date(&opens, false);
+    let due_at = picked_date(&due, true);
+    let ready = class_name.as_str() != UNCHOSEN
+        && set_name.as_str() != UNCHOSEN
+        && matches!((opens_at, due_at), (Some(o), Some(d)) if o < d);
+
+    let oncreate = {
+        let dependency = dependency.clone();
+        let (class_name, set_name) = (class_name.to_string(), set_name.to_string());
+        let attempt_limit = attempt_limit.parse::<u32>().unwrap_or(0);
+        let time_limit = time_limit.parse::<TimeLimit>().ok();
+        move |_| {
+            let (Some(opens), Some(due)) = (opens_at, due_at) else {
+                return;
+            };
+            let assignment = Assignment::new(
+                set_name.clone(),
+                class_name.clone(),
+                String::new(),
+                opens,
+                due,
+                attempt_limit,
+            )
+            .with_time_limit(time_limit);
+            let dependency = dependency.clone();
+            spawn_local(async move {
+                if let Err(why) = invoke_add_assignment(assignment).await {
+                    let _ = MessageDialogBuilder::new()
+                        .set_title("Assignments")
+                        .set_kind(MessageDialogKind::Error)
+                        .message(&why.message)
+                        .await;
+                }
+                dependency.set(!*dependency);
+            });
+        }
+    };
+
+    let cards = match assignments {
+        Ok(res) => match res.clone() {
+            Ok(assignments) if assignments.is_empty() => {
+                html! { <p>{ "You haven't set any assignments yet." }</p> }
+            }
+            Ok(assignments) => assignments
+                .into_iter()
+                .map(|assignment| html! { <AssignmentCard {assignment} dependency={dependency.clone()} /> })
+                .collect::<Html>(),
+            Err(why) => html! { <p>{ why.message }</p> },
+        },
+        Err(_) => html! { "Loading..." },
+    };
+
+    let class = css!(
+        r#"
+            margin: ${fs};
+            background-color: ${bg};
+
+            h1 {
+                font-size: calc( 1.5 * ${fs} );
+                text-align: center;
+            }
+
+            button {
+                margin: calc( 0.25 * ${fs} );
+            }
+        "#,
+        fs = theme.font_size,
+        bg = theme.bg_color,
+    );
+
+    html! {
+        <Column hfill={true} {class}>
+            <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
+                <Button onclick={move |_| nav.push(&Route::Dashboard)}>{ "← Back" }</Button>
+                <h1>{ "Assignments" }</h1>
+                <div></div>
+            </Row>
+            <Row wfill={true} justify_content={"space-evenly"} align_items={"center"}>
+                <SelectInput id={"class"} options={class_options} handle={class_name}>{ "Class" }</SelectInput>
+                <SelectInput id={"set"} options={set_options} handle={set_name}>{ "Set" }</SelectInput>
+                <DateInput id={"opens"} handle={opens} past_only={false}>{ "Opens" }</DateInput>
+                <DateInput id={"due"} handle={due} past_only={false}>{ "Due" }</DateInput>
+                <SelectInput id={"attempt_limit"} options={limit_options} handle={attempt_limit}>{ "Attempts" }</SelectInput>
+                <SelectInput id={"time_limit"} options={time_limit_options} handle={time_limit}>{ "Time Limit" }</SelectInput>
+                <Button onclick={oncreate} clickable={ready}>{ "+ Set Assignment" }</Button>
+            </Row>
+            { cards }
+        </Column>
+    }
+}
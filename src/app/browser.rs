@@ -1,14 +1,19 @@
 use crate::{
     app::Route,
     components::{
-        inputs::Button,
+        custom::CustomQuizBuilder,
+        inputs::{Button, SelectInput, ValidatedInput},
         layout::{Column, Row},
-        questions::use_question_sets_with,
+        questions::{use_set_summaries, use_set_tags},
         theme_ctx::use_theme,
         user_ctx::use_user,
     },
 };
-use shared::AccessLevel;
+use shared::{
+    queries::{Page, SetQuery, SetSort},
+    questions::{ExamLevel, MAX_DIFFICULTY, MIN_DIFFICULTY},
+    AccessLevel,
+};
 use stylist::yew::styled_component;
 use tauri_sys::dialog::{MessageDialogBuilder, MessageDialogKind};
 use yew::{platform::spawn_local, prelude::*};
@@ -34,6 +39,16 @@ pub fn browser() -> Html {
         }
     };
 
+    let building = use_state_eq(|| false);
+    let onbuild = {
+        let building = building.clone();
+        move |_| building.set(true)
+    };
+    let onclose = {
+        let building = building.clone();
+        move |_| building.set(false)
+    };
+
     let theme = use_theme();
     let class = css!(
         r#"
@@ -75,14 +90,86 @@ pub fn browser() -> Html {
         bg = theme.bg_color,
     );
 
+    const ALL_TAGS: &str = "All tags";
+    const ANY: &str = "Any";
+    const PAGE_SIZE: u64 = 20;
+
+    let search = use_state_eq(|| AttrValue::from(String::new()));
+    let search_v = use_state_eq(|| true);
+    let tag = use_state_eq(|| AttrValue::from(ALL_TAGS));
+    let level = use_state_eq(|| AttrValue::from(ANY));
+    let max_difficulty = use_state_eq(|| AttrValue::from(ANY));
+    let sort = use_state_eq(|| AttrValue::from(SetSort::default().name()));
+    let order = use_state_eq(|| AttrValue::from("Ascending"));
+
+    let tag_options = match use_set_tags() {
+        Ok(tags) => tags.clone().unwrap_or_default(),
+        Err(_) => vec![],
+    };
+    let tag_options = std::iter::once(AttrValue::from(ALL_TAGS))
+        .chain(tag_options.into_iter().map(AttrValue::from))
+        .collect::<Vec<_>>();
+    let level_options = std::iter::once(AttrValue::from(ANY))
+        .chain(
+            ExamLevel::all()
+                .into_iter()
+                .map(|l| AttrValue::from(l.name())),
+        )
+        .collect::<Vec<_>>();
+    let difficulty_options = std::iter::once(AttrValue::from(ANY))
+        .chain((MIN_DIFFICULTY..=MAX_DIFFICULTY).map(|d| AttrValue::from(d.to_string())))
+        .collect::<Vec<_>>();
+    let sort_options = SetSort::all()
+        .into_iter()
+        .map(|s| AttrValue::from(s.name()))
+        .collect::<Vec<_>>();
+    let order_options = vec![AttrValue::from("Ascending"), AttrValue::from("Descending")];
+
+    // Every filter is sent to the backend, which re-runs the search
+    // whenever any of them change.
+    let query = SetQuery {
+        search: search.to_string(),
+        tags: match tag.as_str() {
+            ALL_TAGS => vec![],
+            tag => vec![tag.to_owned()],
+        },
+        level: level.parse().ok(),
+        max_difficulty: max_difficulty.parse().ok(),
+        sort: sort.parse().unwrap_or_default(),
+        descending: order.as_str() == "Descending",
+    };
+
+    // Changing any filter goes back to the first page of results.
+    let page = use_state_eq(|| 0u64);
+    {
+        let page = page.clone();
+        use_effect_with(query.clone(), move |_| page.set(0));
+    }
+
     let mut alternate = true;
     let dependency = use_state(|| false);
-    let thingy = use_question_sets_with(dependency.clone());
+    let thingy = use_set_summaries(query, Page::new(*page, PAGE_SIZE), dependency.clone());
     let navc = nav.clone();
-    let set_bars = match thingy {
-        Ok(sets) => sets
-            .clone()
-            .unwrap()
+    let (set_bars, page_controls) = match thingy {
+        Ok(res) => {
+            let paged = res.clone().unwrap();
+            let onprev = {
+                let page = page.clone();
+                move |_| page.set(*page - 1)
+            };
+            let onnext = {
+                let page = page.clone();
+                move |_| page.set(*page + 1)
+            };
+            let page_controls = html! {
+                <Row wfill={true} justify_content={"center"} align_items={"center"}>
+                    <Button onclick={onprev} clickable={paged.has_previous()}>{ "<" }</Button>
+                    <p>{ format!("Page {} of {} ({} sets)", paged.page.index + 1, paged.page_count(), paged.total) }</p>
+                    <Button onclick={onnext} clickable={paged.has_next()}>{ ">" }</Button>
+                </Row>
+            };
+            let set_bars = paged
+            .items
             .into_iter()
             .map(move |set| {
                 let sc = set.clone();
@@ -95,28 +182,53 @@ pub fn browser() -> Html {
                 alternate = !alternate;
                 let onclick = {
                     let nav = navc.clone();
-                    let set_name = AttrValue::from(set.name().clone());
+                    let set_name = AttrValue::from(set.name.clone());
                     move |_| {
                         nav.push(&Route::Quiz { set_name: set_name.clone() })
                     }
                 };
+                let onpractice = {
+                    let nav = navc.clone();
+                    let set_name = AttrValue::from(set.name.clone());
+                    move |_| nav.push(&Route::Practice { set_name: set_name.clone() })
+                };
+
+                let details = format!(
+                    "{} questions · {} · Difficulty {} · {}",
+                    set.question_count,
+                    set.level,
+                    set.difficulty,
+                    set.tags.join(", ")
+                );
 
                 match user.access_level() {
                     AccessLevel::USER => html! {
                         <Row {class} wfill={true} justify_content={"space-between"} align_items={"center"}>
-                            <p><b>{ set.name() }</b></p>
-                            <p>{ set.author() }</p>
+                            <p><b>{ &set.name }</b></p>
+                            <p>{ &set.author }</p>
+                            <p>{ details }</p>
+                            <Button onclick={onpractice}>{ "Practice" }</Button>
                             <Button {onclick}>{ "> Go!" }</Button>
                         </Row>
                     },
                     _ => {
+                        let onanalysis = {
+                            let nav = navc.clone();
+                            let set_name = AttrValue::from(set.name.clone());
+                            move |_| nav.push(&Route::Analysis { set_name: set_name.clone() })
+                        };
+                        let onlive = {
+                            let nav = navc.clone();
+                            let set_name = AttrValue::from(set.name.clone());
+                            move |_| nav.push(&Route::LiveHost { set_name: set_name.clone() })
+                        };
                         let dependency = dependency.clone();
                         let onclick = move |_| {
                             let dependency = dependency.clone();
                             let set = set.clone();
                             log::info!("{:?}", set);
                             spawn_local(async move {
-                                match crate::commands::invoke_delete_question_set(set.name().clone()).await {
+                                match crate::commands::invoke_delete_question_set(set.name.clone()).await {
                                     Ok(_) => {
                                         let _ = MessageDialogBuilder::new()
                                             .set_title("Question Browser")
@@ -137,15 +249,20 @@ pub fn browser() -> Html {
                         };
                         html! {
                             <Row {class} wfill={true} justify_content={"space-between"} align_items={"center"}>
-                                <p><b>{ sc.name() }</b></p>
-                                <p>{ sc.author() }</p>
+                                <p><b>{ &sc.name }</b></p>
+                                <p>{ &sc.author }</p>
+                                <p>{ details }</p>
+                                <Button onclick={onlive}>{ "Host Live" }</Button>
+                                <Button onclick={onanalysis}>{ "Analysis" }</Button>
                                 <Button {onclick}>{ "Delete" }</Button>
                             </Row>
                         }
                     },
                 }
-            }).collect::<Html>(),
-        Err(_) => html! { "Loading..." },
+            }).collect::<Html>();
+            (set_bars, page_controls)
+        }
+        Err(_) => (html! { "Loading..." }, html! {}),
     };
 
     html! {
@@ -153,9 +270,24 @@ pub fn browser() -> Html {
                 <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
                     <div><Button onclick={move |_| nav.push(&Route::Dashboard)}>{ "← Back" }</Button></div>
                     <div><h1>{ "Question Browser" }</h1></div>
-                    <div>{create_button}</div>
+                    <div>
+                        <Button onclick={onbuild}>{ "Build a Quiz" }</Button>
+                        {create_button}
+                    </div>
+                </Row>
+                <Row wfill={true} justify_content={"space-evenly"} align_items={"center"}>
+                    <ValidatedInput id={"search"} minl={0} maxl={40} text_handle={search} validity_handle={search_v}>{ "Search" }</ValidatedInput>
+                    <SelectInput id={"tag"} options={tag_options} handle={tag}>{ "Tag" }</SelectInput>
+                    <SelectInput id={"level"} options={level_options} handle={level}>{ "Level" }</SelectInput>
+                    <SelectInput id={"max_difficulty"} options={difficulty_options} handle={max_difficulty}>{ "Max Difficulty" }</SelectInput>
+                    <SelectInput id={"sort"} options={sort_options} handle={sort}>{ "Sort" }</SelectInput>
+                    <SelectInput id={"order"} options={order_options} handle={order}>{ "Order" }</SelectInput>
                 </Row>
             { set_bars }
+            { page_controls }
+            if *building {
+                <CustomQuizBuilder {onclose} />
+            }
         </Column>
     }
 }
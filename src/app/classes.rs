@@ -0,0 +1,101 @@
+use crate::{
+    app::Route,
+    commands::invoke_add_class,
+    components::{
+        arrangements::ArrangementsForm,
+        classes::{use_classes, ClassCard},
+        inputs::{Button, ValidatedInput},
+        layout::{Column, Row},
+        theme_ctx::use_theme,
+        user_ctx::use_user,
+    },
+};
+use shared::AccessLevel;
+use stylist::yew::styled_component;
+use tauri_sys::dialog::{MessageDialogBuilder, MessageDialogKind};
+use yew::{platform::spawn_local, prelude::*};
+use yew_router::{components::Redirect, hooks::use_navigator};
+
+/// Where teachers create their classes and manage who is enrolled.
+#[styled_component(ClassManager)]
+pub fn class_manager() -> Html {
+    let nav = use_navigator().unwrap();
+    let theme = use_theme();
+    let dependency = use_state(|| false);
+    let name = use_state_eq(|| AttrValue::from(String::new()));
+    let name_valid = use_state_eq(|| false);
+    let classes = use_classes(dependency.clone());
+
+    let user = use_user();
+    match (*user).clone().inner {
+        Some(user) if user.access_level() != &AccessLevel::USER => {}
+        Some(_) => return html! { <Redirect<Route> to={Route::Dashboard}/> },
+        None => return html! { <Redirect<Route> to={Route::Login}/> },
+    }
+
+    let oncreate = {
+        let name = name.clone();
+        let dependency = dependency.clone();
+        move |_| {
+            let name = name.to_string();
+            let dependency = dependency.clone();
+            spawn_local(async move {
+                if let Err(why) = invoke_add_class(name).await {
+                    let _ = MessageDialogBuilder::new()
+                        .set_title("Classes")
+                        .set_kind(MessageDialogKind::Error)
+                        .message(&why.message)
+                        .await;
+                }
+                dependency.set(!*dependency);
+            });
+        }
+    };
+
+    let cards = match classes {
+        Ok(res) => match res.clone() {
+            Ok(classes) if classes.is_empty() => {
+                html! { <p>{ "You don't have any classes yet." }</p> }
+            }
+            Ok(classes) => classes
+                .into_iter()
+                .map(|class| html! { <ClassCard {class} dependency={dependency.clone()} /> })
+                .collect::<Html>(),
+            Err(why) => html! { <p>{ why.message }</p> },
+        },
+        Err(_) => html! { "Loading..." },
+    };
+
+    let class = css!(
+        r#"
+            margin: ${fs};
+            background-color: ${bg};
+
+            h1 {
+                font-size: calc( 1.5 * ${fs} );
+                text-align: center;
+            }
+
+            button {
+                margin: calc( 0.25 * ${fs} );
+            }
+        "#,
+        fs = theme.font_size,
+        bg = theme.bg_color,
+    );
+
+    html! {
+        <Column hfill={true} {class}>
+            <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
+                <Button onclick={move |_| nav.push(&Route::Dashboard)}>{ "← Back" }</Button>
+                <h1>{ "Classes" }</h1>
+                <Row align_items={"center"}>
+                    <ValidatedInput id={"class_name"} minl={1} maxl={40} text_handle={name} validity_handle={name_valid.clone()}>{ "Class Name" }</ValidatedInput>
+                    <Button onclick={oncreate} clickable={*name_valid}>{ "+ New Class" }</Button>
+                </Row>
+            </Row>
+            { cards }
+            <ArrangementsForm />
+        </Column>
+    }
+}
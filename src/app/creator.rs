@@ -1,19 +1,27 @@
 use crate::{
     app::Route,
     components::{
-        inputs::{Button, ValidatedInput},
+        inputs::{Button, SelectInput, ValidatedInput},
         layout::{Column, Row},
-        questions::QuestionForm,
+        questions::{GeneratorForm, QuestionForm},
         theme_ctx::use_theme,
         user_ctx::use_user,
     },
 };
-use shared::questions::{QuestionBuilder, QuestionSet};
+use shared::{
+    questions::{
+        parse_tags, ExamLevel, QuestionBuilder, QuestionSet, MAX_DIFFICULTY, MIN_DIFFICULTY,
+    },
+    timing::TimeLimit,
+};
 use stylist::yew::styled_component;
 use tauri_sys::dialog::{MessageDialogBuilder, MessageDialogKind};
 use yew::{platform::spawn_local, prelude::*};
 use yew_router::hooks::use_navigator;
 
+/// The time limit option for sets that can be taken at any pace.
+const NO_TIME_LIMIT: &str = "No limit";
+
 #[styled_component(Creator)]
 pub fn creator() -> Html {
     let user_ctx = use_user();
@@ -25,6 +33,37 @@ pub fn creator() -> Html {
 
     let name = use_state_eq(|| AttrValue::from(String::new()));
     let name_v = use_state_eq(|| false);
+    let penalties = (0..=3)
+        .map(|p| AttrValue::from(p.to_string()))
+        .collect::<Vec<_>>();
+    let hint_penalty = use_state_eq(|| penalties[0].clone());
+    let tags = use_state_eq(|| AttrValue::from(String::new()));
+    let tags_v = use_state_eq(|| true);
+    let levels = ExamLevel::all()
+        .into_iter()
+        .map(|l| AttrValue::from(l.name()))
+        .collect::<Vec<_>>();
+    let level = use_state_eq(|| AttrValue::from(ExamLevel::default().name()));
+    let difficulties = (MIN_DIFFICULTY..=MAX_DIFFICULTY)
+        .map(|d| AttrValue::from(d.to_string()))
+        .collect::<Vec<_>>();
+    let difficulty = use_state_eq(|| difficulties[0].clone());
+    let time_limits = std::iter::once(AttrValue::from(NO_TIME_LIMIT))
+        .chain(
+            TimeLimit::presets()
+                .iter()
+                .map(|l| AttrValue::from(l.name())),
+        )
+        .collect::<Vec<_>>();
+    let time_limit = use_state_eq(|| AttrValue::from(NO_TIME_LIMIT));
+    let yes_no = vec![AttrValue::from("No"), AttrValue::from("Yes")];
+    let log_calculator = use_state_eq(|| yes_no[0].clone());
+    let resume_options = QuestionSet::RESUME_OPTIONS
+        .iter()
+        .map(|h| AttrValue::from(h.to_string()))
+        .collect::<Vec<_>>();
+    let resume_hours = use_state_eq(|| AttrValue::from("24"));
+    let shuffle = use_state_eq(|| yes_no[0].clone());
 
     let theme = use_theme();
     let class = css!(
@@ -76,6 +115,14 @@ pub fn creator() -> Html {
         let questions = questions.clone();
         let name = name.clone();
         let name_v = name_v.clone();
+        let hint_penalty = hint_penalty.clone();
+        let tags = tags.clone();
+        let level = level.clone();
+        let difficulty = difficulty.clone();
+        let time_limit = time_limit.clone();
+        let log_calculator = log_calculator.clone();
+        let resume_hours = resume_hours.clone();
+        let shuffle = shuffle.clone();
 
         move |_| {
             let user = (*user_ctx).clone();
@@ -96,7 +143,15 @@ pub fn creator() -> Html {
                         .await;
                 });
             } else if let Some(qlist) = questions {
-                let qset = QuestionSet::new((*name).to_string(), user.username().clone(), qlist);
+                let qset = QuestionSet::new((*name).to_string(), user.username().clone(), qlist)
+                    .with_hint_penalty(hint_penalty.parse().unwrap())
+                    .with_tags(parse_tags(&tags))
+                    .with_level(level.parse().unwrap())
+                    .with_difficulty(difficulty.parse().unwrap())
+                    .with_time_limit(time_limit.parse().ok())
+                    .with_calculator_logging(*log_calculator == "Yes")
+                    .with_resume_hours(resume_hours.parse().unwrap())
+                    .with_shuffled_questions(*shuffle == "Yes");
                 let nav = nav.clone();
                 spawn_local(async move {
                     match crate::commands::invoke_add_question_set(qset).await {
@@ -137,11 +192,22 @@ pub fn creator() -> Html {
                 <div class={classes!("expander")}></div>
                 <h1>{ "Question Writer" }</h1>
                 <div class={classes!("expander")}></div>
+                <SelectInput id={"hint_penalty"} options={penalties} handle={hint_penalty}>{ "Marks lost per hint" }</SelectInput>
                 <Button {onclick}>{ "Publish" }</Button>
             </Row>
+            <Row wfill={true} justify_content={"space-evenly"} align_items={"center"}>
+                <ValidatedInput id={"tags"} minl={0} maxl={80} text_handle={tags} validity_handle={tags_v}>{ "Set Tags, e.g. Algebra > Quadratics" }</ValidatedInput>
+                <SelectInput id={"level"} options={levels} handle={level}>{ "Level" }</SelectInput>
+                <SelectInput id={"difficulty"} options={difficulties} handle={difficulty}>{ "Difficulty" }</SelectInput>
+                <SelectInput id={"time_limit"} options={time_limits} handle={time_limit}>{ "Time Limit" }</SelectInput>
+                <SelectInput id={"shuffle"} options={yes_no.clone()} handle={shuffle}>{ "Shuffle Questions" }</SelectInput>
+                <SelectInput id={"log_calculator"} options={yes_no} handle={log_calculator}>{ "Log Calculator Use" }</SelectInput>
+                <SelectInput id={"resume_hours"} options={resume_options} handle={resume_hours}>{ "Resume Within (hours)" }</SelectInput>
+            </Row>
             <Column hfill={true} wfill={true} justify_content={"center"} align_items={"center"}>
-                <QuestionForm page_no={page_no.clone()} {questions} {pg_changed}/>
+                <QuestionForm page_no={page_no.clone()} questions={questions.clone()} pg_changed={pg_changed.clone()}/>
             </Column>
+            <GeneratorForm page_no={page_no.clone()} {questions} {pg_changed}/>
             <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
                 <Button onclick={cl}>{ "<" }</Button>
                 <h1><b>{ "Editing Question " }{ *page_no + 1 }</b></h1>
@@ -1,9 +1,17 @@
 use crate::{
     app::Route,
     components::{
+        adaptive::AdaptiveQuizCard,
         administration::{AddAccountForm, DeleteAccountForm},
+        arrangements::ArrangementsForm,
+        assignments::MyAssignments,
+        attempts::UnfinishedAttempts,
+        calibration::Calibration,
+        daily::DailyReviewCard,
         inputs::Button,
         layout::Column,
+        mistakes::MistakeDeckCard,
+        progress::Progress,
         tabs::{Tab, TabController},
         theme_ctx::use_theme,
         user_ctx::use_user,
@@ -26,6 +34,11 @@ pub fn dashboard() -> Html {
         }
     };
 
+    let onbrowse = {
+        let nav = nav.clone();
+        move |_| nav.push(&Route::Browser)
+    };
+
     let class = css!(
         r#"
             padding: ${fs};
@@ -44,7 +57,34 @@ pub fn dashboard() -> Html {
                 <br />
                 <p>{ "If you would like to take a test" }</p>
                 <p>{ "you can go to the browser." }</p>
-                <Button onclick={move |_| nav.push(&Route::Browser)}>{ "Go To Browser" }</Button>
+                <Button onclick={onbrowse}>{ "Go To Browser" }</Button>
+                if user.access_level() != &AccessLevel::USER {
+                    <Button onclick={let nav = nav.clone(); move |_| nav.push(&Route::Classes)}>{ "Manage Classes" }</Button>
+                    <Button onclick={move |_| nav.push(&Route::Assignments)}>{ "Assignments" }</Button>
+                } else {
+                    <Button onclick={move |_| nav.push(&Route::LiveJoin)}>{ "Join a Live Quiz" }</Button>
+                    <br />
+                    <h1>{ "Homework" }</h1>
+                    <MyAssignments />
+                }
+                <br />
+                <h1>{ "Daily Review" }</h1>
+                <DailyReviewCard />
+                <br />
+                <h1>{ "Adaptive Quiz" }</h1>
+                <AdaptiveQuizCard />
+                <br />
+                <h1>{ "Mistake Deck" }</h1>
+                <MistakeDeckCard />
+                <br />
+                <h1>{ "Unfinished Quizzes" }</h1>
+                <UnfinishedAttempts />
+                <br />
+                <h1>{ "Your Progress" }</h1>
+                <Progress />
+                <br />
+                <h1>{ "Confidence" }</h1>
+                <Calibration />
 
                 if user.access_level() == &AccessLevel::ADMIN {
                     <br />
@@ -56,6 +96,10 @@ pub fn dashboard() -> Html {
                         <Tab _id={"Delete"}>
                             <DeleteAccountForm />
                         </Tab>
+
+                        <Tab _id={"Arrangements"}>
+                            <ArrangementsForm />
+                        </Tab>
                     </TabController>
                 }
             </Column>
@@ -0,0 +1,380 @@
+#![allow(non_camel_case_types)]
+
+use crate::{
+    app::Route,
+    commands::{
+        invoke_advance_live_session, invoke_answer_live_question, invoke_end_live_session,
+        invoke_get_live_host_view, invoke_get_live_view, invoke_host_live_session,
+        invoke_join_live_session,
+    },
+    components::{
+        charts::BarChart,
+        inputs::{Button, ValidatedInput},
+        layout::{Column, Row},
+        theme_ctx::use_theme,
+    },
+};
+use shared::{
+    live::{LiveHostView, LiveView, LIVE_CODE_LENGTH},
+    questions::QuestionPart,
+    requests::UserReqError,
+};
+use stylist::yew::styled_component;
+use tauri_sys::dialog::{MessageDialogBuilder, MessageDialogKind};
+use yew::{
+    platform::{spawn_local, time::sleep},
+    prelude::*,
+};
+use yew_autoprops::autoprops;
+use yew_router::hooks::use_navigator;
+
+/// How often the teacher's and students' screens check for changes.
+const POLL_EVERY: std::time::Duration = std::time::Duration::from_secs(1);
+
+async fn show_error(why: UserReqError) {
+    let _ = MessageDialogBuilder::new()
+        .set_title("Live Quiz")
+        .set_kind(MessageDialogKind::Error)
+        .message(&why.message)
+        .await;
+}
+
+/// The teacher's screen for a live quiz of `set_name`, which is hosted
+/// as soon as the page opens. It shows the join code, how the class has
+/// answered the current question, and the leaderboard.
+#[autoprops]
+#[styled_component(LiveHost)]
+pub fn live_host(set_name: AttrValue) -> Html {
+    let nav = use_navigator().unwrap();
+    let theme = use_theme();
+    let hosted = use_state_eq(|| None::<LiveHostView>);
+    let tick = use_state_eq(|| 0u32);
+
+    {
+        let (hosted, nav) = (hosted.clone(), nav.clone());
+        use_effect_with(set_name.clone(), move |set_name| {
+            let set_name = set_name.to_string();
+            spawn_local(async move {
+                match invoke_host_live_session(set_name).await {
+                    Ok(view) => hosted.set(Some(view)),
+                    Err(why) => {
+                        show_error(why).await;
+                        nav.push(&Route::Browser);
+                    }
+                }
+            });
+        });
+    }
+    {
+        let (hosted, tick) = (hosted.clone(), tick.clone());
+        let finished = hosted.as_ref().is_some_and(|view| view.finished);
+        use_effect_with(*tick, move |_| {
+            if !finished {
+                spawn_local(async move {
+                    sleep(POLL_EVERY).await;
+                    if let Ok(view) = invoke_get_live_host_view().await {
+                        hosted.set(Some(view));
+                    }
+                    tick.set(*tick + 1);
+                });
+            }
+        });
+    }
+
+    let view = match (*hosted).clone() {
+        Some(view) => view,
+        None => return html! { "Starting the live quiz..." },
+    };
+
+    let onnext = {
+        let hosted = hosted.clone();
+        move |_| {
+            let hosted = hosted.clone();
+            spawn_local(async move {
+                match invoke_advance_live_session().await {
+                    Ok(view) => hosted.set(Some(view)),
+                    Err(why) => show_error(why).await,
+                }
+            });
+        }
+    };
+    let onend = {
+        let hosted = hosted.clone();
+        move |_| {
+            let hosted = hosted.clone();
+            spawn_local(async move {
+                match invoke_end_live_session().await {
+                    Ok(view) => hosted.set(Some(view)),
+                    Err(why) => show_error(why).await,
+                }
+            });
+        }
+    };
+    let onback = move |_| nav.push(&Route::Browser);
+
+    let bars = view
+        .distribution
+        .iter()
+        .map(|count| {
+            let mark = if count.correct { "✓" } else { "✗" };
+            let answer = match count.answer.is_empty() {
+                true => "(blank)",
+                false => count.answer.as_str(),
+            };
+            (
+                AttrValue::from(format!("{mark} {answer}")),
+                count.count as f64,
+            )
+        })
+        .collect::<Vec<_>>();
+    let leaderboard = view
+        .leaderboard
+        .iter()
+        .map(|entry| {
+            html! {
+                <li>
+                    <b>{ &entry.username }</b>
+                    { format!(" - {} marks, {} right", entry.marks, entry.correct) }
+                </li>
+            }
+        })
+        .collect::<Html>();
+    let last = view.current.is_some_and(|i| i + 1 == view.total);
+
+    let class = css!(
+        r#"
+            padding: ${fs};
+            background-color: ${bg};
+
+            h2 {
+                margin-top: ${fs};
+                margin-bottom: calc( 0.5 * ${fs} );
+            }
+
+            li {
+                margin-left: ${fs};
+            }
+
+            .code {
+                font-size: calc( 3 * ${fs} );
+                letter-spacing: calc( 0.25 * ${fs} );
+            }
+        "#,
+        fs = theme.font_size,
+        bg = theme.bg_color,
+    );
+
+    html! {
+        <Column wfill={true} hfill={true} justify_content={"center"} align_items={"center"}>
+            <Column {class} align_items={"center"}>
+                <h1>{ "Live Quiz: " }{ &view.set_name }</h1>
+                if view.finished {
+                    <h2>{ "The quiz is over" }</h2>
+                } else {
+                    <p class={classes!("code")}>{ &view.code }</p>
+                    <p>{ "Join at " }<b>{ &view.address }</b>{ " with the code above." }</p>
+                }
+                <p>{ format!("{} joined", view.players.len()) }</p>
+                if let Some((i, question)) = view.current.zip(view.question.clone()) {
+                    <h2>{ format!("Question {} of {}", i + 1, view.total) }</h2>
+                    <h3>{ question.title() }</h3>
+                    <p>{ question.markup() }</p>
+                    <p>{ format!("{} of {} answered", view.answered, view.players.len()) }</p>
+                    <BarChart {bars} max={view.players.len().max(1) as f64} />
+                } else if !view.finished {
+                    <h2>{ "Waiting for students to join" }</h2>
+                }
+                if !view.leaderboard.is_empty() {
+                    <h2>{ "Leaderboard" }</h2>
+                    <ol>{ leaderboard }</ol>
+                }
+                <br />
+                <Row align_items={"center"} justify_content={"center"}>
+                    if view.finished {
+                        <Button onclick={onback}>{ "← Back" }</Button>
+                    } else {
+                        <Button onclick={onend}>{ "End Now" }</Button>
+                        <Button onclick={onnext}>
+                            { match (view.current, last) {
+                                (None, _) => "Start",
+                                (Some(_), false) => "Next Question",
+                                (Some(_), true) => "Finish",
+                            } }
+                        </Button>
+                    }
+                </Row>
+            </Column>
+        </Column>
+    }
+}
+
+/// A student's screen for joining and sitting a live quiz. Each question
+/// is answered part by part, and then the screen waits for the teacher
+/// to move on.
+#[styled_component(LiveJoin)]
+pub fn live_join() -> Html {
+    let nav = use_navigator().unwrap();
+    let theme = use_theme();
+    let address = use_state_eq(|| AttrValue::from("localhost"));
+    let address_valid = use_state_eq(|| true);
+    let code = use_state_eq(|| AttrValue::from(String::new()));
+    let code_valid = use_state_eq(|| false);
+    let joined = use_state(|| None::<LiveView>);
+    let tick = use_state_eq(|| 0u32);
+    let answer = use_state_eq(|| AttrValue::from(String::new()));
+    let answer_valid = use_state_eq(|| true);
+    // The question being answered, with what has been given for each of
+    // its parts so far.
+    let given = use_state_eq(|| (0usize, Vec::<String>::new()));
+
+    {
+        let (joined, tick) = (joined.clone(), tick.clone());
+        let polling = joined.as_ref().is_some_and(|view| !view.finished);
+        use_effect_with(*tick, move |_| {
+            if polling {
+                spawn_local(async move {
+                    sleep(POLL_EVERY).await;
+                    match invoke_get_live_view().await {
+                        Ok(view) => joined.set(Some(view)),
+                        // The host may have gone, so stop asking.
+                        Err(why) => {
+                            joined.set(None);
+                            show_error(why).await;
+                        }
+                    }
+                    tick.set(*tick + 1);
+                });
+            }
+        });
+    }
+
+    let onjoin = {
+        let (address, code) = (address.clone(), code.clone());
+        let (joined, tick) = (joined.clone(), tick.clone());
+        move |_| {
+            let (address, code) = (address.to_string(), code.to_string());
+            let (joined, tick) = (joined.clone(), tick.clone());
+            spawn_local(async move {
+                match invoke_join_live_session(address, code).await {
+                    Ok(view) => {
+                        joined.set(Some(view));
+                        tick.set(*tick + 1);
+                    }
+                    Err(why) => show_error(why).await,
+                }
+            });
+        }
+    };
+
+    let class = css!(
+        r#"
+            padding: ${fs};
+            background-color: ${bg};
+
+            h2 {
+                margin-bottom: calc( 0.5 * ${fs} );
+            }
+        "#,
+        fs = theme.font_size,
+        bg = theme.bg_color,
+    );
+
+    let view = match (*joined).clone() {
+        Some(view) => view,
+        None => {
+            return html! {
+                <Column wfill={true} hfill={true} justify_content={"center"} align_items={"center"}>
+                    <Column {class} align_items={"center"}>
+                        <h1>{ "Join a Live Quiz" }</h1>
+                        <p>{ "Enter the address and code on your teacher's screen." }</p>
+                        <ValidatedInput id={"live_address"} minl={1} maxl={64} text_handle={address} validity_handle={address_valid.clone()}>{ "Address" }</ValidatedInput>
+                        <ValidatedInput id={"live_code"} minl={LIVE_CODE_LENGTH} maxl={LIVE_CODE_LENGTH} text_handle={code} validity_handle={code_valid.clone()}>{ "Code" }</ValidatedInput>
+                        <br />
+                        <Row align_items={"center"} justify_content={"center"}>
+                            <Button onclick={move |_| nav.push(&Route::Dashboard)}>{ "← Back" }</Button>
+                            <Button onclick={onjoin} clickable={*address_valid && *code_valid}>{ "Join" }</Button>
+                        </Row>
+                    </Column>
+                </Column>
+            };
+        }
+    };
+
+    let (score, out_of) = view.score;
+    let body = match view.current.zip(view.question.clone()) {
+        _ if view.finished => html! {
+            <>
+                <h2>{ "The quiz is over" }</h2>
+                <p>{ format!("You scored {score} out of {out_of}.") }</p>
+                <p>{ "Your answers have been saved with your other quizzes." }</p>
+                <br />
+                <Button onclick={move |_| nav.push(&Route::Review)}>{ "See Your Results" }</Button>
+            </>
+        },
+        None => html! { <h2>{ "Waiting for the teacher to start..." }</h2> },
+        Some(_) if view.answered => html! {
+            <>
+                <h2>{ "Answer sent" }</h2>
+                <p>{ "Waiting for the next question..." }</p>
+            </>
+        },
+        Some((i, question)) => {
+            let parts = question.parts.clone();
+            let so_far = match given.0 == i {
+                true => given.1.clone(),
+                false => vec![],
+            };
+            let part = parts[so_far.len().min(parts.len() - 1)].clone();
+            let onclick = {
+                let (given, answer, joined) = (given.clone(), answer.clone(), joined.clone());
+                let so_far = so_far.clone();
+                move |_| {
+                    let mut so_far = so_far.clone();
+                    so_far.push(answer.to_string());
+                    answer.set(AttrValue::from(String::new()));
+                    if so_far.len() < parts.len() {
+                        given.set((i, so_far.clone()));
+                        return;
+                    }
+                    given.set((i, vec![]));
+                    let (submissions, joined) = (so_far, joined.clone());
+                    spawn_local(async move {
+                        match invoke_answer_live_question(i, submissions).await {
+                            Ok(view) => joined.set(Some(view)),
+                            Err(why) => show_error(why).await,
+                        }
+                    });
+                }
+            };
+            html! {
+                <>
+                    <h2>{ format!("Question {} of {}", i + 1, view.total) }</h2>
+                    <h1>{ &question.title }</h1>
+                    <h2>{ &question.markup }</h2>
+                    <h3>
+                        if question.parts.len() > 1 {
+                            { QuestionPart::label(so_far.len()) }{ " " }{ &part.prompt }{ " " }
+                        }
+                        { format!("[{} mark{}]", part.marks, if part.marks == 1 { "" } else { "s" }) }
+                    </h3>
+                    <Row align_items={"center"} justify_content={"center"}>
+                        <ValidatedInput id={"live_answer"} minl={0} maxl={30} validity_handle={answer_valid} text_handle={answer}>{ "Enter Answer" }</ValidatedInput>
+                        <Button {onclick}>{ "⏎" }</Button>
+                    </Row>
+                </>
+            }
+        }
+    };
+
+    html! {
+        <Column wfill={true} hfill={true} justify_content={"center"} align_items={"center"}>
+            <Column {class} align_items={"center"}>
+                <h1>{ "Live Quiz: " }{ &view.set_name }</h1>
+                if !view.finished {
+                    <p>{ format!("{score} of {out_of} marks so far") }</p>
+                }
+                { body }
+            </Column>
+        </Column>
+    }
+}
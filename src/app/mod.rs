@@ -1,6 +1,10 @@
+mod analysis;
+mod assignments;
 mod browser;
+mod classes;
 mod creator;
 mod dash;
+mod live;
 mod login;
 mod quiz;
 mod register;
@@ -8,14 +12,24 @@ mod review;
 
 use super::{
     app::{
-        browser::Browser, creator::Creator, dash::Dashboard, login::Login, quiz::Quiz,
-        register::Register, review::Review,
+        analysis::Analysis,
+        assignments::AssignmentManager,
+        browser::Browser,
+        classes::ClassManager,
+        creator::Creator,
+        dash::Dashboard,
+        live::{LiveHost, LiveJoin},
+        login::Login,
+        quiz::{CustomQuizPage, MistakeDeckQuiz, Quiz},
+        register::Register,
+        review::Review,
     },
     components::{
         theme_ctx::{Theme, ThemeProvider},
         user_ctx::UserContextProvider,
     },
 };
+use shared::{responses::QuizMode, sources::QuizSource};
 use stylist::yew::{styled_component, Global};
 use yew::prelude::*;
 use yew_router::prelude::*;
@@ -34,8 +48,33 @@ pub enum Route {
     Creator,
     #[at("/quiz/:set_name")]
     Quiz { set_name: AttrValue },
+    #[at("/quiz/:set_name/:assignment_id")]
+    AssignedQuiz {
+        set_name: AttrValue,
+        assignment_id: AttrValue,
+    },
+    #[at("/practice/:set_name")]
+    Practice { set_name: AttrValue },
+    #[at("/daily")]
+    DailyReview,
+    #[at("/mistakes")]
+    Mistakes,
+    #[at("/adaptive/:topic")]
+    Adaptive { topic: AttrValue },
+    #[at("/custom")]
+    CustomQuiz,
+    #[at("/live/host/:set_name")]
+    LiveHost { set_name: AttrValue },
+    #[at("/live/join")]
+    LiveJoin,
     #[at("/review")]
     Review,
+    #[at("/classes")]
+    Classes,
+    #[at("/assignments")]
+    Assignments,
+    #[at("/analysis/:set_name")]
+    Analysis { set_name: AttrValue },
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -49,7 +88,30 @@ pub fn switch(route: Route) -> Html {
         Route::Browser => html! { <Browser /> },
         Route::Creator => html! { <Creator /> },
         Route::Review => html! { <Review /> },
+        Route::Classes => html! { <ClassManager /> },
         Route::Quiz { set_name } => html! { <Quiz {set_name} /> },
+        Route::AssignedQuiz {
+            set_name,
+            assignment_id,
+        } => html! { <Quiz {set_name} assignment_id={Some(assignment_id)} /> },
+        Route::Practice { set_name } => html! { <Quiz {set_name} mode={QuizMode::Practice} /> },
+        Route::DailyReview => html! {
+            <Quiz
+                set_name={QuizSource::DailyReview.name()}
+                source={QuizSource::DailyReview}
+                mode={QuizMode::Practice}
+            />
+        },
+        Route::Mistakes => html! { <MistakeDeckQuiz /> },
+        Route::CustomQuiz => html! { <CustomQuizPage /> },
+        Route::Adaptive { topic } => {
+            let source = QuizSource::Adaptive(topic.to_string());
+            html! { <Quiz set_name={source.name()} {source} /> }
+        }
+        Route::LiveHost { set_name } => html! { <LiveHost {set_name} /> },
+        Route::LiveJoin => html! { <LiveJoin /> },
+        Route::Assignments => html! { <AssignmentManager /> },
+        Route::Analysis { set_name } => html! { <Analysis {set_name} /> },
         Route::NotFound => html! { "Page Not Found" },
     }
 }
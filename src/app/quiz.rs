@@ -2,39 +2,110 @@
 
 use crate::{
     app::Route,
+    commands::{
+        invoke_build_quiz, invoke_get_attempt, invoke_get_question_set, invoke_get_quiz_conditions,
+        invoke_save_attempt,
+    },
     components::{
+        calculator::CalculatorPanel,
+        feedback::PracticeFeedback,
         inputs::{Button, ValidatedInput},
         layout::{Column, Row},
         theme_ctx::use_theme,
         user_ctx::use_user,
     },
 };
+use chrono::{DateTime, Duration, Utc};
 use shared::{
-    questions::QuestionSet,
+    adaptive::adaptive_order,
+    arrangements::QuizConditions,
+    attempts::{
+        draft_at, draft_mut, flagged, question_order, unanswered, QuestionDraft, QuizAttempt,
+    },
+    mistakes::DeckFilter,
+    questions::QuestionPart,
     requests::UserReqError,
-    responses::{QuizReview, Response},
+    responses::{Confidence, QuizMode, QuizReview},
+    sources::{BuiltQuiz, CustomQuiz, QuizSource},
+    timing::TimeLimit,
 };
 use stylist::yew::styled_component;
 use tauri_sys::dialog::{MessageDialogBuilder, MessageDialogKind};
 use yew::{
-    platform::spawn_local,
+    platform::{spawn_local, time::sleep},
     prelude::*,
     suspense::{use_future, Suspension, UseFutureHandle},
 };
 use yew_autoprops::autoprops;
-use yew_router::{components::Redirect, hooks::use_navigator};
+use yew_router::{
+    components::Redirect,
+    hooks::{use_location, use_navigator},
+};
+
+/// Fetches the questions for a quiz and the conditions it is sat under.
+/// A single set is fetched as it is, while anything else is put together
+/// by the backend.
+#[hook]
+pub fn use_quiz(
+    set_name: AttrValue,
+    assignment_id: Option<AttrValue>,
+    source: QuizSource,
+) -> Result<UseFutureHandle<Result<BuiltQuiz, UserReqError>>, Suspension> {
+    let set_name = set_name.to_string();
+    let assignment_id = assignment_id.map(|id| id.to_string());
+    use_future(|| async move {
+        match source {
+            QuizSource::Set => {
+                let set = invoke_get_question_set(set_name.clone()).await?;
+                let conditions = invoke_get_quiz_conditions(set_name, assignment_id)
+                    .await
+                    .unwrap_or_default();
+                Ok(BuiltQuiz {
+                    set,
+                    origins: vec![],
+                    conditions,
+                })
+            }
+            source => invoke_build_quiz(source).await,
+        }
+    })
+}
 
 #[hook]
-pub fn use_question_set(
-    name: AttrValue,
-) -> Result<UseFutureHandle<Result<QuestionSet, UserReqError>>, Suspension> {
-    let name = name.to_string();
-    use_future(|| async { crate::commands::invoke_get_question_set(name).await })
+pub fn use_saved_attempt(
+    set_name: AttrValue,
+    assignment_id: Option<AttrValue>,
+) -> Result<UseFutureHandle<Result<Option<QuizAttempt>, UserReqError>>, Suspension> {
+    let set_name = set_name.to_string();
+    let assignment_id = assignment_id.map(|id| id.to_string());
+    use_future(|| async { invoke_get_attempt(set_name, assignment_id).await })
+}
+
+/// Reads some text aloud, for students who have a reader.
+fn read_aloud(text: &str) {
+    let Some(synth) = web_sys::window().and_then(|w| w.speech_synthesis().ok()) else {
+        return;
+    };
+    if let Ok(utterance) = web_sys::SpeechSynthesisUtterance::new_with_text(text) {
+        synth.cancel();
+        synth.speak(&utterance);
+    }
+}
+
+/// Shows the time left as minutes and seconds, like `3:07`.
+fn format_countdown(remaining: Duration) -> String {
+    let seconds = remaining.num_seconds().max(0);
+    format!("{}:{:02}", seconds / 60, seconds % 60)
 }
 
 #[autoprops]
 #[styled_component(Quiz)]
-pub fn quiz(set_name: AttrValue) -> Html {
+pub fn quiz(
+    set_name: AttrValue,
+    #[prop_or_default] assignment_id: Option<AttrValue>,
+    #[prop_or_default] mode: QuizMode,
+    #[prop_or_default] source: QuizSource,
+) -> Html {
     let theme = use_theme();
     let nav = use_navigator().unwrap();
 
@@ -47,13 +118,23 @@ pub fn quiz(set_name: AttrValue) -> Html {
     };
 
     let current_question = use_state_eq(|| 0usize);
-    let responses = use_state_eq(|| vec![]);
+    let current_part = use_state_eq(|| 0usize);
+    let drafts = use_state_eq(Vec::<QuestionDraft>::new);
+    let summary = use_state_eq(|| false);
     let answer = use_state_eq(|| AttrValue::from(String::new()));
     let valid = use_state_eq(|| true);
+    let started = use_state(Utc::now);
+    let question_shown = use_state(Utc::now);
+    let now = use_state(Utc::now);
+    let break_started = use_state_eq(|| None::<DateTime<Utc>>);
+    let break_time = use_state_eq(Duration::zero);
+    let calculator_log = use_state_eq(Vec::<String>::new);
+    let seed = use_state(|| Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64);
 
-    let set = match use_question_set(set_name) {
+    let saved = use_saved_attempt(set_name.clone(), assignment_id.clone());
+    let built = match use_quiz(set_name, assignment_id.clone(), source.clone()) {
         Ok(res) => match res.clone() {
-            Ok(qs) => qs,
+            Ok(built) => built,
             Err(why) => {
                 spawn_local(async move {
                     let _ = MessageDialogBuilder::new()
@@ -67,63 +148,525 @@ pub fn quiz(set_name: AttrValue) -> Html {
         },
         Err(_) => return html! { "Loading..." },
     };
+    let set = built.set.clone();
+    let QuizConditions {
+        time_limit,
+        arrangements,
+    } = built.conditions.clone();
+    // Practice is never timed, and isn't saved to be resumed later.
+    // Neither are quizzes put together by the backend, which won't be
+    // the same the next time they are built.
+    let practice = mode == QuizMode::Practice;
+    let time_limit = time_limit.filter(|_| !practice);
+    let resumable = !practice && source == QuizSource::Set;
+    let adaptive = matches!(source, QuizSource::Adaptive(_));
+
+    let saved = match saved {
+        Ok(res) => res.clone().ok().flatten().filter(|_| resumable),
+        Err(_) => return html! { "Loading..." },
+    };
+
+    let total = set.questions().len();
+
+    // Picks up where an unfinished attempt was left off. An untimed
+    // attempt's clock carries on from however long had already been
+    // spent, while a timed one counts the time away from it too, so that
+    // leaving can't stop the clock.
+    {
+        let (drafts, current_question, answer) =
+            (drafts.clone(), current_question.clone(), answer.clone());
+        let (started, question_shown, seed) =
+            (started.clone(), question_shown.clone(), seed.clone());
+        let break_time = break_time.clone();
+        use_effect_with(saved, move |saved| {
+            if let Some(attempt) = saved {
+                let now = Utc::now();
+                let question = attempt.current_question.min(total - 1);
+                let entered = draft_at(&attempt.drafts, question).answer(0).to_owned();
+                let mut all = attempt.drafts.clone();
+                match attempt.started {
+                    Some(began) => {
+                        started.set(began);
+                        break_time.set(Duration::seconds(attempt.break_seconds));
+                        question_shown.set(attempt.question_shown.unwrap_or(now));
+                        // The time on this question up to leaving was
+                        // counted when it was saved, and will be again
+                        // from when it was first shown.
+                        if let Some(shown) = attempt.question_shown {
+                            let draft = draft_mut(&mut all, question);
+                            let counted =
+                                (attempt.saved - shown).num_seconds() - attempt.break_seconds;
+                            draft.seconds_spent = (draft.seconds_spent - counted.max(0)).max(0);
+                        }
+                    }
+                    None => {
+                        started.set(now - Duration::seconds(attempt.elapsed));
+                        question_shown.set(now);
+                    }
+                }
+                answer.set(AttrValue::from(entered));
+                current_question.set(question);
+                drafts.set(all);
+                seed.set(attempt.seed);
+            }
+        });
+    }
+
+    // Questions are numbered in the order they are shown, which is only
+    // different to the set's own order if it shuffles them. Drafts are
+    // kept in the order shown. An adaptive quiz only knows its order up
+    // to the next question, which depends on how every question moved
+    // past so far was answered.
+    let order = match adaptive {
+        true => {
+            let difficulties = set
+                .questions()
+                .iter()
+                .map(|q| q.difficulty())
+                .collect::<Vec<_>>();
+            adaptive_order(&difficulties, *seed, |shown, index| {
+                (shown < *current_question).then(|| {
+                    draft_at(&drafts, shown)
+                        .to_response(built.origin(index), &set.questions()[index], 0, *now)
+                        .is_correct()
+                })
+            })
+        }
+        false => question_order(total, set.shuffle_questions(), *seed),
+    };
+    // Moving past the last question of an adaptive quiz goes straight
+    // to handing it in.
+    let run_over = adaptive && *current_question >= order.len();
+    {
+        let summary = summary.clone();
+        use_effect_with(run_over, move |run_over| {
+            if *run_over {
+                summary.set(true);
+            }
+        });
+    }
+    let total = match adaptive {
+        true => order.len(),
+        false => total,
+    };
+    let current = set.questions()[order[(*current_question).min(total - 1)]].clone();
+    let parts = current.parts();
+    let part = parts[*current_part].clone();
+    let draft = draft_at(&drafts, *current_question);
+    let per_question = matches!(time_limit, Some(TimeLimit::PerQuestion { .. }));
+
+    // Copies whatever is on screen into the current question's draft,
+    // and adds on the time spent since it was shown. Rest breaks only
+    // count against the question they were taken on when each question
+    // is timed separately.
+    let stash = {
+        let (drafts, answer) = (drafts.clone(), answer.clone());
+        let (question_shown, break_time) = (question_shown.clone(), break_time.clone());
+        let calculator_log = calculator_log.clone();
+        let (question, part) = (*current_question, *current_part);
+        let on_summary = *summary;
+        let log_calculator = set.log_calculator();
+        move || {
+            let mut all = (*drafts).clone();
+            let now = Utc::now();
+            if !on_summary {
+                let draft = draft_mut(&mut all, question);
+                if !draft.locked() {
+                    draft.set_answer(part, answer.to_string());
+                }
+                let paused = match per_question {
+                    true => *break_time,
+                    false => Duration::zero(),
+                };
+                draft.seconds_spent += (now - *question_shown - paused).num_seconds().max(0);
+                if log_calculator {
+                    draft.calculator_log.extend(calculator_log.iter().cloned());
+                }
+            }
+            calculator_log.set(vec![]);
+            question_shown.set(now);
+            if per_question {
+                break_time.set(Duration::zero());
+            }
+            all
+        }
+    };
+
+    // The clock stops during rest breaks by moving the deadline back by
+    // however long they took.
+    let paused = *break_time + break_started.map_or(Duration::zero(), |b| *now - b);
+
+    // Saves the attempt so far, so that it can be resumed if the app is
+    // closed or the student leaves part way through.
+    let save = {
+        let username = user.username().clone();
+        let set_name = set.name().clone();
+        let assignment_id = assignment_id.as_ref().map(|id| id.to_string());
+        let (started, seed) = (*started, *seed);
+        let (showing, shown) = (*current_question, *question_shown);
+        move |drafts: &[QuestionDraft], current_question: usize| {
+            if !resumable {
+                return;
+            }
+            let now = Utc::now();
+            // Moving to another question starts its clock afresh.
+            let moved = current_question != showing;
+            let attempt = QuizAttempt {
+                username: username.clone(),
+                set_name: set_name.clone(),
+                assignment_id: assignment_id.clone(),
+                current_question,
+                drafts: drafts.to_vec(),
+                elapsed: (now - started - paused).num_seconds(),
+                started: time_limit.map(|_| started),
+                question_shown: per_question.then_some(if moved { now } else { shown }),
+                break_seconds: match moved && per_question {
+                    true => 0,
+                    false => paused.num_seconds(),
+                },
+                seed,
+                saved: now,
+            };
+            spawn_local(async move {
+                if let Err(why) = invoke_save_attempt(attempt).await {
+                    log::warn!("{}", why);
+                }
+            });
+        }
+    };
 
     let onquit = {
+        let (stash, save) = (stash.clone(), save.clone());
         let nav = nav.clone();
+        let question = *current_question;
         move |_| {
+            save(&stash(), question);
             nav.push(&Route::Browser);
         }
     };
 
-    let total = set.questions().len();
-    let current = set.questions()[*current_question].clone();
+    // Shows a part of a question, or the summary screen for `None`.
+    let show = {
+        let (drafts, answer, summary) = (drafts.clone(), answer.clone(), summary.clone());
+        let (current_question, current_part) = (current_question.clone(), current_part.clone());
+        move |all: Vec<QuestionDraft>, target: Option<(usize, usize)>| {
+            save(
+                &all,
+                target.map_or(*current_question, |(question, _)| question),
+            );
+            match target {
+                Some((question, part)) => {
+                    let entered = draft_at(&all, question).answer(part).to_owned();
+                    answer.set(AttrValue::from(entered));
+                    current_question.set(question);
+                    current_part.set(part);
+                    summary.set(false);
+                }
+                None => summary.set(true),
+            }
+            drafts.set(all);
+        }
+    };
 
-    let onclick = {
+    let go_to = {
+        let (stash, show) = (stash.clone(), show.clone());
+        Callback::from(move |target: Option<(usize, usize)>| show(stash(), target))
+    };
+
+    let finish = {
         let user = user.clone();
-        let handle = answer.clone();
-        let submitted = answer.to_string();
-        let question = format!("<p>{}</p><p>{}</p>", current.title(), current.markup());
         let nav = nav.clone();
-        let answer = current.answer().clone();
-        let responses = responses.clone();
+        let set_name = set.name().clone();
+        let started = *started;
+        let assignment_id = assignment_id.as_ref().map(|id| id.to_string());
+        let questions = set.questions().clone();
+        let order = order.clone();
+        let built = built.clone();
+        let source = source.clone();
+        let hint_penalty = set.hint_penalty();
+        Callback::from(move |drafts: Vec<QuestionDraft>| {
+            let now = Utc::now();
+            let responses = order
+                .iter()
+                .enumerate()
+                .map(|(shown, &index)| {
+                    let reference = built.origin(index);
+                    let question = &questions[index];
+                    draft_at(&drafts, shown).to_response(reference, question, hint_penalty, now)
+                })
+                .collect::<Vec<_>>();
+            log::info!("{:?}", responses);
+
+            let quiz_review = QuizReview::new(
+                user.username().clone(),
+                set_name.clone(),
+                started,
+                responses,
+            )
+            .for_assignment(assignment_id.clone())
+            .with_time_limit(time_limit)
+            .with_question_order(order.clone())
+            .in_mode(mode)
+            .from_source(source.clone());
+            spawn_local(async move {
+                match crate::commands::invoke_add_quiz_review(quiz_review).await {
+                    Ok(_) => {
+                        let _ = MessageDialogBuilder::new()
+                            .set_title("Quiz")
+                            .set_kind(MessageDialogKind::Info)
+                            .message("The quiz was completed and a review was uploaded!")
+                            .await;
+                    }
+                    Err(_) => {
+                        let _ = MessageDialogBuilder::new()
+                            .set_title("Quiz")
+                            .set_kind(MessageDialogKind::Error)
+                            .message("The review of this quiz failed to upload!")
+                            .await;
+                    }
+                };
+            });
+            nav.push(&Route::Review);
+        })
+    };
 
-        let current_question = current_question.clone();
+    // Entering an answer moves on to the next part, then the next
+    // question, and finally the summary screen.
+    let next_question = match adaptive || *current_question + 1 < total {
+        true => Some((*current_question + 1, 0)),
+        false => None,
+    };
+    let next = match *current_part + 1 < parts.len() {
+        true => Some((*current_question, *current_part + 1)),
+        false => next_question,
+    };
+    // In practice, entering the answer to the last part marks the
+    // whole question straight away instead.
+    let check = {
+        let (stash, show) = (stash.clone(), show.clone());
+        let (question, part) = (*current_question, *current_part);
+        Callback::from(move |_: ()| {
+            let mut all = stash();
+            let draft = draft_mut(&mut all, question);
+            draft.checked = true;
+            draft.tries += 1;
+            show(all, Some((question, part)));
+        })
+    };
+    let onclick = {
+        let go_to = go_to.clone();
+        let checks = practice && *current_part + 1 == parts.len();
+        move |_| match checks {
+            true => check.emit(()),
+            false => go_to.emit(next),
+        }
+    };
+    let onretry = {
+        let (drafts, answer) = (drafts.clone(), answer.clone());
+        let (question, current_part) = (*current_question, current_part.clone());
+        move |_| {
+            let mut all = (*drafts).clone();
+            draft_mut(&mut all, question).checked = false;
+            answer.set(AttrValue::from(
+                draft_at(&all, question).answer(0).to_owned(),
+            ));
+            current_part.set(0);
+            drafts.set(all);
+        }
+    };
+    let feedback = draft.checked.then(|| {
+        let reference = built.origin(order[*current_question]);
+        draft.to_response(reference, &current, set.hint_penalty(), Utc::now())
+    });
+    let retries_left = feedback
+        .as_ref()
+        .filter(|response| draft.can_retry(response.is_correct()))
+        .map(|_| QuizMode::PRACTICE_RETRIES + 1 - draft.tries);
+    let onskip = {
+        let go_to = go_to.clone();
+        move |_| go_to.emit(next_question)
+    };
+    let onback = {
+        let go_to = go_to.clone();
+        let previous = current_question.saturating_sub(1);
+        move |_| go_to.emit(Some((previous, 0)))
+    };
+    let onsummary = {
+        let go_to = go_to.clone();
+        move |_| go_to.emit(None)
+    };
+    let onresume = {
+        let go_to = go_to.clone();
+        let target = Some((*current_question, *current_part));
+        move |_| go_to.emit(target)
+    };
+    let onhandin = {
+        let (stash, drafts, finish) = (stash.clone(), drafts.clone(), finish.clone());
+        move |_| {
+            let all = stash();
+            drafts.set(all.clone());
+            finish.emit(all);
+        }
+    };
+    let onflag = {
+        let drafts = drafts.clone();
+        let question = *current_question;
         move |_| {
-            let response = Response::new(question.clone(), submitted.clone(), answer.clone());
-            let mut tmp_rsp = (*responses).clone();
-            tmp_rsp.push(response);
-            log::info!("{:?}", tmp_rsp);
-            responses.set(tmp_rsp.clone());
+            let mut all = (*drafts).clone();
+            let draft = draft_mut(&mut all, question);
+            draft.flagged = !draft.flagged;
+            drafts.set(all);
+        }
+    };
 
-            handle.set(AttrValue::from(String::new()));
+    // Running out of overall time hands in the whole quiz, with every
+    // blank question marked as out of time. Running out of time on one
+    // question locks it and moves on to the next one still open.
+    let ontimeout = {
+        let (stash, show, drafts) = (stash.clone(), show.clone(), drafts.clone());
+        let question = *current_question;
+        let on_summary = *summary;
+        Callback::from(move |_: ()| {
+            let mut all = stash();
+            if !per_question {
+                let blank = unanswered(&all, total);
+                let locked = blank.into_iter().chain((!on_summary).then_some(question));
+                for index in locked {
+                    draft_mut(&mut all, index).timed_out = true;
+                }
+                drafts.set(all.clone());
+                finish.emit(all);
+                return;
+            }
+            draft_mut(&mut all, question).timed_out = true;
+            let open = (question + 1..total).find(|&i| !draft_at(&all, i).timed_out);
+            show(all, open.map(|i| (i, 0)));
+        })
+    };
 
-            if *current_question + 1 == total {
-                let quiz_review = QuizReview::new(user.username().clone(), tmp_rsp);
+    // Timed quizzes tick once a second, and act as soon as the deadline
+    // passes.
+    let remaining = match time_limit {
+        Some(TimeLimit::PerQuestion { .. }) if *summary || draft.timed_out => None,
+        Some(limit) => {
+            let shown = *question_shown - Duration::seconds(draft.seconds_spent);
+            Some(limit.deadline(*started, shown) + paused - *now)
+        }
+        None => None,
+    };
+    let expired = remaining.is_some_and(|r| r <= Duration::zero());
+    {
+        let now = now.clone();
+        use_effect_with(*now, move |_| {
+            if time_limit.is_some() {
                 spawn_local(async move {
-                    match crate::commands::invoke_add_quiz_review(quiz_review).await {
-                        Ok(_) => {
-                            let _ = MessageDialogBuilder::new()
-                                .set_title("Quiz")
-                                .set_kind(MessageDialogKind::Info)
-                                .message("The quiz was completed and a review was uploaded!")
-                                .await;
-                        }
-                        Err(_) => {
-                            let _ = MessageDialogBuilder::new()
-                                .set_title("Quiz")
-                                .set_kind(MessageDialogKind::Error)
-                                .message("The review of this quiz failed to upload!")
-                                .await;
-                        }
-                    };
+                    sleep(std::time::Duration::from_secs(1)).await;
+                    now.set(Utc::now());
                 });
-                nav.push(&Route::Review);
-            } else {
-                current_question.set(*current_question + 1);
             }
+        });
+    }
+    use_effect_with(
+        (*current_question, *summary, expired),
+        move |(_, _, expired)| {
+            if *expired {
+                ontimeout.emit(());
+            }
+        },
+    );
+
+    // Rating confidence is optional, and choosing the same rating again
+    // takes it back.
+    let confidence_buttons = Confidence::all()
+        .into_iter()
+        .map(|confidence| {
+            let onclick = {
+                let drafts = drafts.clone();
+                let question = *current_question;
+                move |_| {
+                    let mut all = (*drafts).clone();
+                    let draft = draft_mut(&mut all, question);
+                    draft.confidence = (draft.confidence != Some(confidence)).then_some(confidence);
+                    drafts.set(all);
+                }
+            };
+            let label = match draft.confidence == Some(confidence) {
+                true => format!("✓ {confidence}"),
+                false => confidence.to_string(),
+            };
+            html! { <Button {onclick}>{ label }</Button> }
+        })
+        .collect::<Html>();
+
+    let onhint = {
+        let drafts = drafts.clone();
+        let question = *current_question;
+        move |_| {
+            let mut all = (*drafts).clone();
+            draft_mut(&mut all, question).hints_used += 1;
+            drafts.set(all);
         }
     };
 
+    let onbreak = {
+        let (break_started, break_time) = (break_started.clone(), break_time.clone());
+        move |_| match *break_started {
+            Some(began) => {
+                break_time.set(*break_time + (Utc::now() - began));
+                break_started.set(None);
+            }
+            None => break_started.set(Some(Utc::now())),
+        }
+    };
+    let onread = {
+        let text = format!("{}. {}. {}", current.title(), current.markup(), part.prompt);
+        move |_| read_aloud(&text)
+    };
+    let calculator_allowed = arrangements.calculator_allowed(current.calculator_allowed());
+
+    let hints_left = current.hints().len() > draft.hints_used as usize;
+    let hints = current
+        .hints()
+        .iter()
+        .take(draft.hints_used as usize)
+        .map(|hint| html! { <li>{ hint }</li> })
+        .collect::<Html>();
+    let penalty_note = match set.hint_penalty() {
+        0 => String::from("Hint"),
+        n => format!("Hint (-{n})"),
+    };
+
+    // A button for each question, which shows whether it has been
+    // answered or flagged and jumps straight to it.
+    let jump_to = |indices: Vec<usize>| {
+        indices
+            .into_iter()
+            .map(|index| {
+                let entry = draft_at(&drafts, index);
+                let class = classes!(
+                    (index == *current_question && !*summary).then_some("current"),
+                    entry.is_answered().then_some("answered"),
+                    entry.flagged.then_some("flagged"),
+                );
+                let onclick = {
+                    let go_to = go_to.clone();
+                    move |_| go_to.emit(Some((index, 0)))
+                };
+                html! {
+                    <button {class} {onclick}>
+                        { index + 1 }
+                        if entry.flagged {
+                            { " ⚑" }
+                        }
+                    </button>
+                }
+            })
+            .collect::<Html>()
+    };
+    let navigator = jump_to((0..total).collect());
+    let blank = unanswered(&drafts, total);
+    let marked = flagged(&drafts);
+
     let class = css!(
         r#"
             background-color: ${bg};
@@ -132,31 +675,186 @@ pub fn quiz(set_name: AttrValue) -> Html {
             h2 { font-size: calc( 2.5 * ${fs}); }
             h3 { font-size: calc( 1.5 * ${fs}); }
             input { margin-right: calc( 0.5 * ${fs}); }
+            ol { margin-top: ${fs}; }
             > * > button {
                 margin: calc( 0.5 * ${fs});
                 background-color: ${ec};
                 border-color: ${ec};
             }
+
+            .navigator {
+                display: flex;
+                flex-wrap: wrap;
+                justify-content: center;
+            }
+
+            .navigator button {
+                margin: calc( 0.125 * ${fs} );
+                min-width: calc( 2.5 * ${fs} );
+                font-size: ${fs};
+                color: ${fg};
+                background-color: ${bgs};
+                border: 2px solid ${bgs};
+                cursor: pointer;
+            }
+
+            .navigator button.answered {
+                background-color: ${pc};
+                border-color: ${pc};
+            }
+
+            .navigator button.flagged {
+                border-color: ${ec};
+            }
+
+            .navigator button.current {
+                border-color: ${fg};
+            }
         "#,
         ec = theme.fail_color,
         fs = theme.font_size,
+        fg = theme.fg_color,
         bg = theme.bg_color,
+        bgs = theme.bg_shade,
+        pc = theme.primary_color,
     );
+    let countdown_class = match remaining {
+        Some(r) if r < Duration::minutes(1) => css!("color: ${fc};", fc = theme.fail_color),
+        _ => css!(),
+    };
     html! {
         <Column {class} hfill={true}>
             <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
-                <h3>{ "Question " }{ *current_question + 1 }{ " of " }{ total }</h3>
+                if *summary {
+                    <h3>{ "Summary" }</h3>
+                } else {
+                    <h3>
+                        if practice {
+                            { "Practice: " }
+                        }
+                        { "Question " }{ *current_question + 1 }
+                        if !adaptive {
+                            { " of " }{ total }
+                        }
+                    </h3>
+                    if !adaptive {
+                        <Button onclick={onflag}>{ if draft.flagged { "Unflag" } else { "Flag for Review" } }</Button>
+                    }
+                }
+                if !calculator_allowed && !*summary {
+                    <h3>{ "🔒 No calculator" }</h3>
+                }
+                if let Some(remaining) = remaining {
+                    <h3 class={countdown_class}>{ "Time left: " }{ format_countdown(remaining) }</h3>
+                    if arrangements.rest_breaks {
+                        <Button onclick={onbreak}>{ if break_started.is_some() { "Resume" } else { "Rest Break" } }</Button>
+                    }
+                }
                 <Button onclick={onquit}>{ "Exit" }</Button>
             </Row>
-            <Column hfill={true} wfill={true} align_items={"center"} justify_content={"center"}>
-                <h1>{ current.title() }</h1>
-                <h2>{ current.markup() }</h2>
-                <br />
-                <Row align_items={"center"} justify_content={"center"}>
-                    <ValidatedInput id={"answer"} minl={0} maxl={30} validity_handle={valid} text_handle={answer}>{ "Enter Answer" }</ValidatedInput>
-                    <Button {onclick}>{ "⏎" }</Button>
+            if break_started.is_some() {
+                <Column hfill={true} wfill={true} align_items={"center"} justify_content={"center"}>
+                    <h2>{ "On a rest break" }</h2>
+                    <h3>{ "The clock is stopped until you resume." }</h3>
+                </Column>
+            } else if *summary {
+                <Column hfill={true} wfill={true} align_items={"center"} justify_content={"center"}>
+                    <h2>{ "Ready to hand in?" }</h2>
+                    if run_over {
+                        <h3>{ "That's every question for this quiz." }</h3>
+                    } else if adaptive {
+                        <h3>{ "The quiz isn't over yet, so handing in now ends it early." }</h3>
+                    } else if blank.is_empty() {
+                        <h3>{ "Every question has an answer." }</h3>
+                    } else {
+                        <h3>{ format!("{} unanswered:", blank.len()) }</h3>
+                        <nav class={classes!("navigator")}>{ jump_to(blank) }</nav>
+                    }
+                    if !adaptive && !marked.is_empty() {
+                        <h3>{ "Flagged for review:" }</h3>
+                        <nav class={classes!("navigator")}>{ jump_to(marked) }</nav>
+                    }
+                    <br />
+                    <Row align_items={"center"} justify_content={"center"}>
+                        if !run_over {
+                            <Button onclick={onresume}>{ "Keep Going" }</Button>
+                        }
+                        <Button onclick={onhandin}>{ "Hand In" }</Button>
+                    </Row>
+                </Column>
+            } else {
+                // Adaptive questions can't be gone back to, as the ones
+                // after them were picked by how they were answered.
+                if !adaptive {
+                    <nav class={classes!("navigator")}>{ navigator }</nav>
+                }
+                <Column hfill={true} wfill={true} align_items={"center"} justify_content={"center"}>
+                    <h1>{ current.title() }</h1>
+                    <h2>{ current.markup() }</h2>
+                    <br />
+                    <h3>
+                        if parts.len() > 1 {
+                            { QuestionPart::label(*current_part) }{ " " }{ &part.prompt }{ " " }
+                        }
+                        { format!("[{} mark{}]", part.marks, if part.marks == 1 { "" } else { "s" }) }
+                    </h3>
+                    if let Some(response) = feedback {
+                        <PracticeFeedback {response} {retries_left} {onretry} onnext={onskip.clone()} />
+                    } else if draft.timed_out {
+                        <h3>{ "Time ran out on this question, so it can't be changed." }</h3>
+                    } else {
+                        <Row align_items={"center"} justify_content={"center"}>
+                            <ValidatedInput id={"answer"} minl={0} maxl={30} validity_handle={valid} text_handle={answer}>{ "Enter Answer" }</ValidatedInput>
+                            <Button {onclick}>{ "⏎" }</Button>
+                            if !current.hints().is_empty() {
+                                <Button onclick={onhint} clickable={hints_left}>{ penalty_note }</Button>
+                            }
+                            if arrangements.reader {
+                                <Button onclick={onread}>{ "Read Aloud" }</Button>
+                            }
+                        </Row>
+                        <Row align_items={"center"} justify_content={"center"}>
+                            <h3>{ "How sure are you?" }</h3>
+                            { confidence_buttons }
+                        </Row>
+                    }
+                    <ol>{ hints }</ol>
+                    if calculator_allowed {
+                        <CalculatorPanel log={calculator_log.clone()} />
+                    }
+                </Column>
+                <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
+                    <Button onclick={onback} clickable={!adaptive && *current_question > 0}>{ "Back" }</Button>
+                    <Button onclick={onsummary}>{ "Finish" }</Button>
+                    <Button onclick={onskip}>{ "Skip" }</Button>
                 </Row>
-            </Column>
+            }
         </Column>
     }
 }
+
+/// A practice quiz of the mistake deck, narrowed down by the page's query.
+#[function_component(MistakeDeckQuiz)]
+pub fn mistake_deck_quiz() -> Html {
+    let filter = use_location()
+        .and_then(|location| location.query::<DeckFilter>().ok())
+        .unwrap_or_default();
+    let source = QuizSource::MistakeDeck(filter);
+    html! {
+        <Quiz set_name={source.name()} {source} mode={QuizMode::Practice} />
+    }
+}
+
+/// A quiz put together in the quiz builder, which is passed along with
+/// the route. Reloading the page loses it, so that goes back to the
+/// browser instead.
+#[function_component(CustomQuizPage)]
+pub fn custom_quiz_page() -> Html {
+    match use_location().and_then(|location| location.state::<CustomQuiz>()) {
+        Some(custom) => {
+            let source = QuizSource::Custom((*custom).clone());
+            html! { <Quiz set_name={source.name()} {source} /> }
+        }
+        None => html! { <Redirect<Route> to={Route::Browser}/> },
+    }
+}
@@ -1,4 +1,12 @@
-use shared::{requests::UserReqError, responses::QuizReview};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use shared::{
+    queries::{Page, Paged, ReviewQuery, ReviewScope},
+    questions::{QuestionPart, MAX_DIFFICULTY},
+    requests::UserReqError,
+    responses::{QuizMode, QuizReview},
+    sources::QuizSource,
+    AccessLevel,
+};
 use stylist::yew::styled_component;
 use yew::{
     prelude::*,
@@ -8,8 +16,10 @@ use yew_router::components::Redirect;
 
 use crate::{
     app::Route,
-    commands::invoke_get_quiz_reviews,
+    commands::invoke_find_quiz_reviews,
     components::{
+        classes::use_classes,
+        inputs::{Button, DateInput, SelectInput, ValidatedInput},
         layout::{Column, Row},
         theme_ctx::use_theme,
         user_ctx::use_user,
@@ -18,15 +28,47 @@ use crate::{
 
 #[hook]
 pub fn use_quiz_reviews(
+    scope: ReviewScope,
+    query: ReviewQuery,
+    page: Page,
     update: UseStateHandle<bool>,
-) -> Result<UseFutureHandle<Result<Vec<QuizReview>, UserReqError>>, Suspension> {
-    use_future_with(update, |_| async { invoke_get_quiz_reviews().await })
+) -> Result<UseFutureHandle<Result<Paged<QuizReview>, UserReqError>>, Suspension> {
+    use_future_with((scope, query, page, *update), |deps| {
+        let (scope, query, page, _) = (*deps).clone();
+        async move { invoke_find_quiz_reviews(scope, query, page).await }
+    })
+}
+
+/// Reads the value of a date picker, which is empty until a date is picked.
+fn picked_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
+
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    date.and_hms_opt(0, 0, 0).unwrap().and_utc()
+}
+
+/// Shows a time as minutes and seconds, like `3:07`.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    format!("{}:{:02}", seconds / 60, seconds % 60)
 }
 
 #[styled_component(Review)]
 pub fn review() -> Html {
+    const PAGE_SIZE: u64 = 10;
+
     let theme = use_theme();
     let dependency = use_state_eq(|| false);
+    let scope = use_state_eq(|| AttrValue::from(ReviewScope::Mine.name()));
+    let set_name = use_state_eq(|| AttrValue::from(String::new()));
+    let set_name_v = use_state_eq(|| true);
+    let from = use_state_eq(|| AttrValue::from(String::new()));
+    let until = use_state_eq(|| AttrValue::from(String::new()));
+    let page = use_state_eq(|| 0u64);
+    const ALL_CLASSES: &str = "All classes";
+    let class_name = use_state_eq(|| AttrValue::from(ALL_CLASSES));
+    let classes = use_classes(dependency.clone());
 
     let user = use_user();
     let user = match (*user).clone().inner {
@@ -36,33 +78,228 @@ pub fn review() -> Html {
         }
     };
 
-    let quiz_reviews = match use_quiz_reviews(dependency) {
-        Ok(revs) => revs
-            .clone()
-            .unwrap()
-            .into_iter()
-            .filter(|review| &review.username == user.username())
-            .map(|review| {
+    // Students only ever see their own attempts, so they aren't offered
+    // a choice. The backend checks this again whatever is picked here.
+    let scope_options = match user.access_level() {
+        AccessLevel::USER => vec![ReviewScope::Mine],
+        AccessLevel::TEACHER => vec![ReviewScope::Mine, ReviewScope::Students],
+        AccessLevel::ADMIN => vec![ReviewScope::Mine, ReviewScope::Students, ReviewScope::All],
+    }
+    .into_iter()
+    .map(|s| AttrValue::from(s.name()))
+    .collect::<Vec<_>>();
+
+    let class_options = match classes {
+        Ok(classes) => classes.clone().unwrap_or_default(),
+        Err(_) => vec![],
+    };
+    let class_options = std::iter::once(AttrValue::from(ALL_CLASSES))
+        .chain(
+            class_options
+                .iter()
+                .map(|c| AttrValue::from(c.name().clone())),
+        )
+        .collect::<Vec<_>>();
+
+    let current_scope = scope.parse().unwrap_or_default();
+    let query = ReviewQuery {
+        set_name: Some(set_name.trim().to_owned()).filter(|name| !name.is_empty()),
+        username: None,
+        class_name: match class_name.as_str() {
+            _ if current_scope == ReviewScope::Mine => None,
+            ALL_CLASSES => None,
+            name => Some(name.to_owned()),
+        },
+        from: picked_date(&from).map(start_of_day),
+        // The end date is inclusive, so search up to the start of the next day.
+        until: picked_date(&until)
+            .and_then(|d| d.succ_opt())
+            .map(start_of_day),
+    };
+
+    {
+        let page = page.clone();
+        use_effect_with((current_scope, query.clone()), move |_| page.set(0));
+    }
+
+    let results = use_quiz_reviews(
+        current_scope,
+        query,
+        Page::new(*page, PAGE_SIZE),
+        dependency,
+    );
+    let (quiz_reviews, page_controls) = match results {
+        Ok(revs) => {
+            let paged = match revs.clone() {
+                Ok(paged) => paged,
+                Err(why) => return html! { <p>{ why.message }</p> },
+            };
+            let onprev = {
+                let page = page.clone();
+                move |_| page.set(*page - 1)
+            };
+            let onnext = {
+                let page = page.clone();
+                move |_| page.set(*page + 1)
+            };
+            let page_controls = html! {
+                <Row wfill={true} justify_content={"center"} align_items={"center"}>
+                    <Button onclick={onprev} clickable={paged.has_previous()}>{ "<" }</Button>
+                    <p>{ format!("Page {} of {} ({} attempts)", paged.page.index + 1, paged.page_count(), paged.total) }</p>
+                    <Button onclick={onnext} clickable={paged.has_next()}>{ ">" }</Button>
+                </Row>
+            };
+            // Attempts arrive newest first, and are gathered up so that
+            // each set taken on each day gets its own heading.
+            let mut groups: Vec<((NaiveDate, String), Vec<QuizReview>)> = vec![];
+            for review in paged.items {
+                let key = (review.finished.date_naive(), review.set_name.clone());
+                match groups.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, reviews)) => reviews.push(review),
+                    None => groups.push((key, vec![review])),
+                }
+            }
+
+            let render_review = |review: QuizReview| {
                 let answers = review.responses.iter().map(|resp| {
+                    let steps = resp
+                        .solution()
+                        .iter()
+                        .map(|step| html! { <li>{ step }</li> })
+                        .collect::<Html>();
+                    let calculations = resp
+                        .calculator_log()
+                        .iter()
+                        .map(|line| html! { <li>{ line }</li> })
+                        .collect::<Html>();
+                    let hints = match (resp.hints_used(), resp.hint_penalty()) {
+                        (0, _) => String::from("None"),
+                        (n, 0) => n.to_string(),
+                        (n, p) => format!("{n} (-{p})"),
+                    };
+                    let parts = resp
+                        .parts()
+                        .iter()
+                        .enumerate()
+                        .map(|(i, part)| {
+                            html! {
+                                <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
+                                    <p>{ QuestionPart::label(i) }{ " " }{ &part.prompt }</p>
+                                    <p>{ part.marks_awarded }{ "/" }{ part.marks_available }</p>
+                                    <p>{ &part.submitted }</p>
+                                    <p>{ &part.answer }</p>
+                                    <p></p>
+                                    <p></p>
+                                </Row>
+                            }
+                        })
+                        .collect::<Html>();
                     html! {
                         <>
                             <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
-                                { Html::from_html_unchecked(AttrValue::from(resp.question().clone())) }
-                                <p>{ resp.is_correct() }</p>
-                                <p>{ resp.submitted() }</p>
+                                <p>{ &resp.snapshot().title }</p>
+                                <p>{ &resp.snapshot().markup }</p>
+                                <p>{ resp.marks_awarded() }{ "/" }{ resp.marks_available() }</p>
+                                <p>
+                                    { resp.submitted() }
+                                    if resp.timed_out() {
+                                        { " (out of time)" }
+                                    }
+                                    if resp.tries() > 1 {
+                                        { format!(" ({} tries)", resp.tries()) }
+                                    }
+                                    if let Some(confidence) = resp.confidence() {
+                                        { format!(" ({confidence})") }
+                                    }
+                                </p>
                                 <p>{ resp.answer() }</p>
+                                <p>{ hints }</p>
+                                <p>{ format_duration(resp.time_spent()) }</p>
                             </Row>
+                            if resp.parts().len() > 1 {
+                                { parts }
+                            }
+                            if !resp.solution().is_empty() {
+                                <details>
+                                    <summary>{ "Worked solution" }</summary>
+                                    <ol>{ steps }</ol>
+                                </details>
+                            }
+                            if !resp.calculator_log().is_empty() {
+                                <details>
+                                    <summary>{ "Calculator use" }</summary>
+                                    <ol>{ calculations }</ol>
+                                </details>
+                            }
                             <br />
                         </>
                     }
                 }).collect::<Html>();
+                let (awarded, available) = review.score();
+                let order = review
+                    .question_order
+                    .iter()
+                    .map(|index| (index + 1).to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let duration = match review.duration() {
+                    Some(duration) => format_duration(duration),
+                    None => String::from("-"),
+                };
                 html! {
                     <Column>
+                        <Row justify_content={"space-between"} align_items={"center"}>
+                            <h3>{ review.finished.format("%H:%M").to_string() }</h3>
+                            if current_scope != ReviewScope::Mine {
+                                <h3>{ &review.username }</h3>
+                            }
+                            if review.mode == QuizMode::Practice {
+                                <h3>{ "Practice" }</h3>
+                            }
+                            <h3>{ "Time taken: " }{ duration }</h3>
+                            if let Some(limit) = review.time_limit {
+                                <h3>{ "Limit: " }{ limit.name() }</h3>
+                            }
+                            if let QuizSource::Adaptive(topic) = &review.source {
+                                <h3>{ "Topic: " }{ topic }</h3>
+                            }
+                            if let Some(mastery) = review.mastery {
+                                <h3>
+                                    { format!("Mastery: {:.1}/{}", mastery.level, MAX_DIFFICULTY) }
+                                    if !mastery.settled {
+                                        { " (unsettled)" }
+                                    }
+                                </h3>
+                            }
+                            if review.source == QuizSource::Set && review.shuffled() {
+                                <h3>{ "Order: " }{ order }</h3>
+                            }
+                            if !review.arrangements.is_empty() {
+                                <h3>{ "Arrangements: " }{ review.arrangements.notes().join(", ") }</h3>
+                            }
+                            <h3>{ "Score: " }{ awarded }{ "/" }{ available }</h3>
+                        </Row>
                         { answers }
                     </Column>
                 }
-            })
-            .collect::<Html>(),
+            };
+            let quiz_reviews = groups
+                .into_iter()
+                .map(|((date, set_name), reviews)| {
+                    let set_name = match set_name.as_str() {
+                        "" => String::from("Unknown set"),
+                        _ => set_name,
+                    };
+                    html! {
+                        <>
+                            <h2>{ set_name }{ " · " }{ date.format("%d/%m/%Y").to_string() }</h2>
+                            { reviews.into_iter().map(&render_review).collect::<Html>() }
+                        </>
+                    }
+                })
+                .collect::<Html>();
+            (quiz_reviews, page_controls)
+        }
         Err(_) => return html! { "Loading..." },
     };
 
@@ -79,6 +316,19 @@ pub fn review() -> Html {
                 flex: 1;
                 text-align: center;
             }
+
+            h2, h3 {
+                padding: calc( 0.5 * ${fs} );
+            }
+
+            details {
+                margin-left: ${fs};
+                cursor: pointer;
+            }
+
+            ol {
+                margin-left: calc( 2 * ${fs} );
+            }
         "#,
         fs = theme.font_size,
         bg = theme.bg_color,
@@ -86,14 +336,28 @@ pub fn review() -> Html {
     );
     html! {
         <div {class}>
+            <Row justify_content={"space-evenly"} align_items={"center"}>
+                if scope_options.len() > 1 {
+                    <SelectInput id={"scope"} options={scope_options} handle={scope}>{ "Showing" }</SelectInput>
+                }
+                if current_scope != ReviewScope::Mine {
+                    <SelectInput id={"class"} options={class_options} handle={class_name}>{ "Class" }</SelectInput>
+                }
+                <ValidatedInput id={"set_name"} minl={0} maxl={40} text_handle={set_name} validity_handle={set_name_v}>{ "Set Name" }</ValidatedInput>
+                <DateInput id={"from"} handle={from}>{ "From" }</DateInput>
+                <DateInput id={"until"} handle={until}>{ "Until" }</DateInput>
+            </Row>
             <Row justify_content={"space-between"} align_items={"center"}>
                 <p>{ "Question" }</p>
                 <p>{ "Equation" }</p>
-                <p>{ "Answer Correct?" }</p>
+                <p>{ "Marks" }</p>
                 <p>{ "Submitted Answer" }</p>
                 <p>{ "Correct Answer" }</p>
+                <p>{ "Hints Used" }</p>
+                <p>{ "Time" }</p>
             </Row>
             { quiz_reviews }
+            { page_controls }
         </div>
     }
 }
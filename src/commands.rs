@@ -1,8 +1,17 @@
 use serde::Serialize;
 use shared::{
-    questions::QuestionSet,
+    analytics::{CalibrationReport, ItemAnalysis, ProgressReport},
+    arrangements::{AccessArrangements, QuizConditions},
+    assignments::{Assignment, CompletionGrid, StudentAssignment},
+    attempts::QuizAttempt,
+    classes::Class,
+    live::{LiveHostView, LiveView},
+    mistakes::{DeckEntry, DeckFilter},
+    queries::{Page, Paged, ReviewQuery, ReviewScope, SetQuery},
+    questions::{QuestionSet, SetSummary},
     requests::{UserReqError, UserReqErrorKind::SerdeError},
     responses::QuizReview,
+    sources::{BuiltQuiz, QuizSource},
     User,
 };
 use std::fmt::Debug;
@@ -19,6 +28,22 @@ pub async fn invoke_get_question_sets() -> Result<Vec<QuestionSet>, UserReqError
     map_command_error(invoke("get_question_sets", &()).await)
 }
 
+pub async fn invoke_find_set_summaries(
+    query: SetQuery,
+    page: Page,
+) -> Result<Paged<SetSummary>, UserReqError> {
+    #[derive(Serialize)]
+    struct Payload {
+        query: SetQuery,
+        page: Page,
+    }
+    map_command_error(invoke::<Payload, _>("find_set_summaries", &Payload { query, page }).await)
+}
+
+pub async fn invoke_get_set_tags() -> Result<Vec<String>, UserReqError> {
+    map_command_error(invoke("get_set_tags", &()).await)
+}
+
 pub async fn invoke_validate_login(
     username: String,
     password: String,
@@ -31,6 +56,10 @@ pub async fn invoke_validate_login(
     map_command_error(invoke::<Payload, _>("validate_login", &Payload { username, password }).await)
 }
 
+pub async fn invoke_log_out() -> Result<(), UserReqError> {
+    map_command_error(invoke("log_out", &()).await)
+}
+
 pub async fn invoke_add_user(new_user: User) -> Result<(), UserReqError> {
     #[derive(Serialize)]
     #[allow(non_snake_case)]
@@ -85,8 +114,20 @@ pub async fn invoke_delete_user(username: String) -> Result<(), UserReqError> {
     map_command_error(invoke::<Payload, _>("delete_user", &Payload { username }).await)
 }
 
-pub async fn invoke_get_quiz_reviews() -> Result<Vec<QuizReview>, UserReqError> {
-    map_command_error(invoke("get_quiz_reviews", &()).await)
+pub async fn invoke_find_quiz_reviews(
+    scope: ReviewScope,
+    query: ReviewQuery,
+    page: Page,
+) -> Result<Paged<QuizReview>, UserReqError> {
+    #[derive(Serialize)]
+    struct Payload {
+        scope: ReviewScope,
+        query: ReviewQuery,
+        page: Page,
+    }
+    map_command_error(
+        invoke::<Payload, _>("find_quiz_reviews", &Payload { scope, query, page }).await,
+    )
 }
 
 pub async fn invoke_add_quiz_review(new_review: QuizReview) -> Result<(), UserReqError> {
@@ -105,3 +146,327 @@ pub async fn invoke_add_quiz_review(new_review: QuizReview) -> Result<(), UserRe
         .await,
     )
 }
+
+pub async fn invoke_get_progress() -> Result<ProgressReport, UserReqError> {
+    map_command_error(invoke("get_progress", &()).await)
+}
+
+pub async fn invoke_get_calibration() -> Result<CalibrationReport, UserReqError> {
+    map_command_error(invoke("get_calibration", &()).await)
+}
+
+pub async fn invoke_host_live_session(set_name: String) -> Result<LiveHostView, UserReqError> {
+    #[derive(Serialize)]
+    #[allow(non_snake_case)]
+    struct Payload {
+        setName: String,
+    }
+    map_command_error(
+        invoke::<Payload, _>("host_live_session", &Payload { setName: set_name }).await,
+    )
+}
+
+pub async fn invoke_get_live_host_view() -> Result<LiveHostView, UserReqError> {
+    map_command_error(invoke("get_live_host_view", &()).await)
+}
+
+pub async fn invoke_advance_live_session() -> Result<LiveHostView, UserReqError> {
+    map_command_error(invoke("advance_live_session", &()).await)
+}
+
+pub async fn invoke_end_live_session() -> Result<LiveHostView, UserReqError> {
+    map_command_error(invoke("end_live_session", &()).await)
+}
+
+pub async fn invoke_join_live_session(
+    address: String,
+    code: String,
+) -> Result<LiveView, UserReqError> {
+    #[derive(Serialize)]
+    struct Payload {
+        address: String,
+        code: String,
+    }
+    map_command_error(invoke::<Payload, _>("join_live_session", &Payload { address, code }).await)
+}
+
+pub async fn invoke_get_live_view() -> Result<LiveView, UserReqError> {
+    map_command_error(invoke("get_live_view", &()).await)
+}
+
+pub async fn invoke_answer_live_question(
+    question: usize,
+    submissions: Vec<String>,
+) -> Result<LiveView, UserReqError> {
+    #[derive(Serialize)]
+    struct Payload {
+        question: usize,
+        submissions: Vec<String>,
+    }
+    map_command_error(
+        invoke::<Payload, _>(
+            "answer_live_question",
+            &Payload {
+                question,
+                submissions,
+            },
+        )
+        .await,
+    )
+}
+
+pub async fn invoke_get_item_analysis(set_name: String) -> Result<ItemAnalysis, UserReqError> {
+    #[derive(Serialize)]
+    #[allow(non_snake_case)]
+    struct Payload {
+        setName: String,
+    }
+    map_command_error(
+        invoke::<Payload, _>("get_item_analysis", &Payload { setName: set_name }).await,
+    )
+}
+
+pub async fn invoke_export_item_analysis(set_name: String) -> Result<String, UserReqError> {
+    #[derive(Serialize)]
+    #[allow(non_snake_case)]
+    struct Payload {
+        setName: String,
+    }
+    map_command_error(
+        invoke::<Payload, _>("export_item_analysis", &Payload { setName: set_name }).await,
+    )
+}
+
+pub async fn invoke_get_quiz_conditions(
+    set_name: String,
+    assignment_id: Option<String>,
+) -> Result<QuizConditions, UserReqError> {
+    #[derive(Serialize)]
+    #[allow(non_snake_case)]
+    struct Payload {
+        setName: String,
+        assignmentId: Option<String>,
+    }
+    map_command_error(
+        invoke::<Payload, _>(
+            "get_quiz_conditions",
+            &Payload {
+                setName: set_name,
+                assignmentId: assignment_id,
+            },
+        )
+        .await,
+    )
+}
+
+pub async fn invoke_save_attempt(attempt: QuizAttempt) -> Result<(), UserReqError> {
+    #[derive(Serialize)]
+    struct Payload {
+        attempt: QuizAttempt,
+    }
+    map_command_error(invoke::<Payload, _>("save_attempt", &Payload { attempt }).await)
+}
+
+pub async fn invoke_get_attempt(
+    set_name: String,
+    assignment_id: Option<String>,
+) -> Result<Option<QuizAttempt>, UserReqError> {
+    #[derive(Serialize)]
+    #[allow(non_snake_case)]
+    struct Payload {
+        setName: String,
+        assignmentId: Option<String>,
+    }
+    map_command_error(
+        invoke::<Payload, _>(
+            "get_attempt",
+            &Payload {
+                setName: set_name,
+                assignmentId: assignment_id,
+            },
+        )
+        .await,
+    )
+}
+
+pub async fn invoke_get_my_attempts() -> Result<Vec<QuizAttempt>, UserReqError> {
+    map_command_error(invoke("get_my_attempts", &()).await)
+}
+
+pub async fn invoke_discard_attempt(
+    set_name: String,
+    assignment_id: Option<String>,
+) -> Result<(), UserReqError> {
+    #[derive(Serialize)]
+    #[allow(non_snake_case)]
+    struct Payload {
+        setName: String,
+        assignmentId: Option<String>,
+    }
+    map_command_error(
+        invoke::<Payload, _>(
+            "discard_attempt",
+            &Payload {
+                setName: set_name,
+                assignmentId: assignment_id,
+            },
+        )
+        .await,
+    )
+}
+
+pub async fn invoke_build_quiz(source: QuizSource) -> Result<BuiltQuiz, UserReqError> {
+    #[derive(Serialize)]
+    struct Payload {
+        source: QuizSource,
+    }
+    map_command_error(invoke("build_quiz", &Payload { source }).await)
+}
+
+pub async fn invoke_get_due_count() -> Result<u32, UserReqError> {
+    map_command_error(invoke("get_due_count", &()).await)
+}
+
+pub async fn invoke_get_mistake_deck(filter: DeckFilter) -> Result<Vec<DeckEntry>, UserReqError> {
+    #[derive(Serialize)]
+    struct Payload {
+        filter: DeckFilter,
+    }
+    map_command_error(invoke("get_mistake_deck", &Payload { filter }).await)
+}
+
+pub async fn invoke_add_class(name: String) -> Result<(), UserReqError> {
+    #[derive(Serialize)]
+    struct Payload {
+        name: String,
+    }
+    map_command_error(invoke::<Payload, _>("add_class", &Payload { name }).await)
+}
+
+pub async fn invoke_get_classes() -> Result<Vec<Class>, UserReqError> {
+    map_command_error(invoke("get_classes", &()).await)
+}
+
+pub async fn invoke_delete_class(name: String) -> Result<(), UserReqError> {
+    #[derive(Serialize)]
+    struct Payload {
+        name: String,
+    }
+    map_command_error(invoke::<Payload, _>("delete_class", &Payload { name }).await)
+}
+
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+struct ClassMemberPayload {
+    className: String,
+    username: String,
+}
+
+pub async fn invoke_enrol_student(
+    class_name: String,
+    username: String,
+) -> Result<(), UserReqError> {
+    map_command_error(
+        invoke::<ClassMemberPayload, _>(
+            "enrol_student",
+            &ClassMemberPayload {
+                className: class_name,
+                username,
+            },
+        )
+        .await,
+    )
+}
+
+pub async fn invoke_unenrol_student(
+    class_name: String,
+    username: String,
+) -> Result<(), UserReqError> {
+    map_command_error(
+        invoke::<ClassMemberPayload, _>(
+            "unenrol_student",
+            &ClassMemberPayload {
+                className: class_name,
+                username,
+            },
+        )
+        .await,
+    )
+}
+
+pub async fn invoke_add_class_teacher(
+    class_name: String,
+    username: String,
+) -> Result<(), UserReqError> {
+    map_command_error(
+        invoke::<ClassMemberPayload, _>(
+            "add_class_teacher",
+            &ClassMemberPayload {
+                className: class_name,
+                username,
+            },
+        )
+        .await,
+    )
+}
+
+pub async fn invoke_add_assignment(assignment: Assignment) -> Result<Assignment, UserReqError> {
+    #[derive(Serialize)]
+    struct Payload {
+        assignment: Assignment,
+    }
+    map_command_error(invoke::<Payload, _>("add_assignment", &Payload { assignment }).await)
+}
+
+pub async fn invoke_get_assignments() -> Result<Vec<Assignment>, UserReqError> {
+    map_command_error(invoke("get_assignments", &()).await)
+}
+
+pub async fn invoke_get_my_assignments() -> Result<Vec<StudentAssignment>, UserReqError> {
+    map_command_error(invoke("get_my_assignments", &()).await)
+}
+
+pub async fn invoke_get_completion_grid(id: String) -> Result<CompletionGrid, UserReqError> {
+    #[derive(Serialize)]
+    struct Payload {
+        id: String,
+    }
+    map_command_error(invoke::<Payload, _>("get_completion_grid", &Payload { id }).await)
+}
+
+pub async fn invoke_delete_assignment(id: String) -> Result<(), UserReqError> {
+    #[derive(Serialize)]
+    struct Payload {
+        id: String,
+    }
+    map_command_error(invoke::<Payload, _>("delete_assignment", &Payload { id }).await)
+}
+
+pub async fn invoke_get_arrangements(username: String) -> Result<AccessArrangements, UserReqError> {
+    #[derive(Serialize)]
+    struct Payload {
+        username: String,
+    }
+    map_command_error(invoke::<Payload, _>("get_arrangements", &Payload { username }).await)
+}
+
+pub async fn invoke_set_arrangements(
+    username: String,
+    arrangements: AccessArrangements,
+) -> Result<(), UserReqError> {
+    #[derive(Serialize)]
+    struct Payload {
+        username: String,
+        arrangements: AccessArrangements,
+    }
+    map_command_error(
+        invoke::<Payload, _>(
+            "set_arrangements",
+            &Payload {
+                username,
+                arrangements,
+            },
+        )
+        .await,
+    )
+}
@@ -0,0 +1,49 @@
+#![allow(non_camel_case_types)]
+
+use crate::{
+    app::Route,
+    components::{
+        inputs::{Button, SelectInput},
+        layout::{Column, Row},
+        questions::use_set_tags,
+    },
+};
+use shared::adaptive::ADAPTIVE_MAX_ITEMS;
+use yew::prelude::*;
+use yew_router::hooks::use_navigator;
+
+const CHOOSE: &str = "Choose a topic";
+
+/// Picks a topic to start an adaptive quiz on.
+#[function_component(AdaptiveQuizCard)]
+pub fn adaptive_quiz_card() -> Html {
+    let nav = use_navigator().unwrap();
+    let topic = use_state_eq(|| AttrValue::from(CHOOSE));
+    let tags = match use_set_tags() {
+        Ok(tags) => tags.clone().unwrap_or_default(),
+        Err(_) => vec![],
+    };
+    let options = std::iter::once(AttrValue::from(CHOOSE))
+        .chain(tags.into_iter().map(AttrValue::from))
+        .collect::<Vec<_>>();
+
+    let chosen = topic.as_str() != CHOOSE;
+    let onclick = {
+        let topic = (*topic).clone();
+        move |_| {
+            nav.push(&Route::Adaptive {
+                topic: topic.clone(),
+            })
+        }
+    };
+
+    html! {
+        <Column align_items={"center"}>
+            <p>{ format!("Up to {ADAPTIVE_MAX_ITEMS} questions that get harder or easier to match how you're doing.") }</p>
+            <Row align_items={"center"} justify_content={"center"}>
+                <SelectInput id={"adaptive_topic"} {options} handle={topic}>{ "Topic" }</SelectInput>
+                <Button clickable={chosen} {onclick}>{ "Start" }</Button>
+            </Row>
+        </Column>
+    }
+}
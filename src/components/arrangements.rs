@@ -0,0 +1,149 @@
+#![allow(non_camel_case_types)]
+
+use crate::{
+    commands::{invoke_get_arrangements, invoke_set_arrangements},
+    components::{
+        inputs::{Button, SelectInput, UserPicker},
+        layout::{Column, Row},
+        theme_ctx::use_theme,
+    },
+};
+use shared::{
+    arrangements::{AccessArrangements, CalculatorOverride},
+    requests::UserReqError,
+};
+use stylist::yew::styled_component;
+use tauri_sys::dialog::{MessageDialogBuilder, MessageDialogKind};
+use yew::{
+    platform::spawn_local,
+    prelude::*,
+    suspense::{use_future_with, Suspension, UseFutureHandle},
+};
+use yew_autoprops::autoprops;
+
+#[hook]
+pub fn use_arrangements(
+    username: AttrValue,
+) -> Result<UseFutureHandle<Result<AccessArrangements, UserReqError>>, Suspension> {
+    use_future_with(username, |username| {
+        let username = username.to_string();
+        async move { invoke_get_arrangements(username).await }
+    })
+}
+
+fn yes_no(value: bool) -> AttrValue {
+    AttrValue::from(if value { "Yes" } else { "No" })
+}
+
+/// Lets teachers and admins pick a student and change their exam
+/// access arrangements.
+#[styled_component(ArrangementsForm)]
+pub fn arrangements_form() -> Html {
+    let theme = use_theme();
+    let username = use_state_eq(|| AttrValue::from(String::new()));
+    let users_changed = use_state(|| false);
+
+    let class = css!(
+        r#"
+            background-color: ${bgs};
+            padding: calc( 0.5 * ${fs} );
+
+            h1 {
+                font-size: calc( 1.5 * ${fs} );
+                text-align: center;
+            }
+
+            > div > * {
+                margin: calc( 0.125 * ${fs} );
+            }
+        "#,
+        bgs = theme.bg_shade,
+        fs = theme.font_size,
+    );
+
+    html! {
+        <Column {class} align_items={"center"}>
+            <h1>{ "Access Arrangements" }</h1>
+            <br />
+            <UserPicker id={"arrangements_user"} user_handle={username.clone()} dependency={users_changed}>{ "Student" }</UserPicker>
+            <br />
+            if !username.is_empty() {
+                <ArrangementsEditor key={username.to_string()} username={(*username).clone()} />
+            }
+        </Column>
+    }
+}
+
+/// Fetches a student's arrangements so they can be edited.
+#[autoprops]
+#[function_component(ArrangementsEditor)]
+fn arrangements_editor(username: &AttrValue) -> Html {
+    match use_arrangements(username.clone()) {
+        Ok(res) => match res.clone() {
+            Ok(arrangements) => html! { <ArrangementsFields {username} {arrangements} /> },
+            Err(why) => html! { <p>{ why.message }</p> },
+        },
+        Err(_) => html! { "Loading..." },
+    }
+}
+
+#[autoprops]
+#[function_component(ArrangementsFields)]
+fn arrangements_fields(username: &AttrValue, arrangements: &AccessArrangements) -> Html {
+    let extra_time = use_state_eq(|| AttrValue::from(arrangements.extra_time.to_string()));
+    let reader = use_state_eq(|| yes_no(arrangements.reader));
+    let rest_breaks = use_state_eq(|| yes_no(arrangements.rest_breaks));
+    let calculator = use_state_eq(|| AttrValue::from(arrangements.calculator.name()));
+
+    let extra_time_options = AccessArrangements::EXTRA_TIME_OPTIONS
+        .iter()
+        .map(|p| AttrValue::from(p.to_string()))
+        .collect::<Vec<_>>();
+    let yes_no_options = vec![yes_no(true), yes_no(false)];
+    let calculator_options = CalculatorOverride::all()
+        .into_iter()
+        .map(|c| AttrValue::from(c.name()))
+        .collect::<Vec<_>>();
+
+    let onsave = {
+        let username = username.to_string();
+        let (extra_time, reader) = (extra_time.clone(), reader.clone());
+        let (rest_breaks, calculator) = (rest_breaks.clone(), calculator.clone());
+        move |_| {
+            let arrangements = AccessArrangements {
+                extra_time: extra_time.parse().unwrap_or(0),
+                reader: *reader == yes_no(true),
+                rest_breaks: *rest_breaks == yes_no(true),
+                calculator: calculator.parse().unwrap_or_default(),
+            };
+            let username = username.clone();
+            spawn_local(async move {
+                let (kind, message) = match invoke_set_arrangements(username, arrangements).await {
+                    Ok(_) => (
+                        MessageDialogKind::Info,
+                        "access arrangements were saved".to_owned(),
+                    ),
+                    Err(why) => (MessageDialogKind::Error, why.message),
+                };
+                let _ = MessageDialogBuilder::new()
+                    .set_title("Access Arrangements")
+                    .set_kind(kind)
+                    .message(&message)
+                    .await;
+            });
+        }
+    };
+
+    html! {
+        <>
+            <Row align_items={"center"}>
+                <SelectInput id={"extra_time"} options={extra_time_options} handle={extra_time}>{ "Extra Time (%)" }</SelectInput>
+                <SelectInput id={"reader"} options={yes_no_options.clone()} handle={reader}>{ "Reader" }</SelectInput>
+                <SelectInput id={"rest_breaks"} options={yes_no_options} handle={rest_breaks}>{ "Rest Breaks" }</SelectInput>
+                <SelectInput id={"calculator"} options={calculator_options} handle={calculator}>{ "Calculator" }</SelectInput>
+            </Row>
+            <br />
+            <Button onclick={onsave}>{ "Save" }</Button>
+        </>
+    }
+}
@@ -0,0 +1,235 @@
+#![allow(non_camel_case_types)]
+
+use crate::{
+    app::Route,
+    commands::{
+        invoke_delete_assignment, invoke_get_assignments, invoke_get_completion_grid,
+        invoke_get_my_assignments,
+    },
+    components::{
+        inputs::Button,
+        layout::{Column, Row},
+        theme_ctx::use_theme,
+    },
+};
+use chrono::{DateTime, Utc};
+use shared::{
+    assignments::{Assignment, AssignmentStatus, CompletionGrid, StudentAssignment},
+    requests::UserReqError,
+};
+use stylist::yew::styled_component;
+use tauri_sys::dialog::{MessageDialogBuilder, MessageDialogKind};
+use yew::{
+    platform::spawn_local,
+    prelude::*,
+    suspense::{use_future, use_future_with, Suspension, UseFutureHandle},
+};
+use yew_autoprops::autoprops;
+use yew_router::hooks::use_navigator;
+
+#[hook]
+pub fn use_assignments(
+    update: UseStateHandle<bool>,
+) -> Result<UseFutureHandle<Result<Vec<Assignment>, UserReqError>>, Suspension> {
+    use_future_with(update, |_| async { invoke_get_assignments().await })
+}
+
+#[hook]
+pub fn use_my_assignments(
+) -> Result<UseFutureHandle<Result<Vec<StudentAssignment>, UserReqError>>, Suspension> {
+    use_future(|| async { invoke_get_my_assignments().await })
+}
+
+#[hook]
+pub fn use_completion_grid(
+    id: AttrValue,
+) -> Result<UseFutureHandle<Result<CompletionGrid, UserReqError>>, Suspension> {
+    use_future_with(id, |id| {
+        let id = id.to_string();
+        async move { invoke_get_completion_grid(id).await }
+    })
+}
+
+fn format_date(date: &DateTime<Utc>) -> String {
+    date.format("%d/%m/%Y").to_string()
+}
+
+/// The logged in student's homework, with the most urgent first and a
+/// button to start any that can still be attempted.
+#[styled_component(MyAssignments)]
+pub fn my_assignments() -> Html {
+    let nav = use_navigator().unwrap();
+    let theme = use_theme();
+    let assignments = match use_my_assignments() {
+        Ok(res) => match res.clone() {
+            Ok(assignments) => assignments,
+            Err(why) => return html! { <p>{ why.message }</p> },
+        },
+        Err(_) => return html! { "Loading..." },
+    };
+
+    if assignments.is_empty() {
+        return html! { <p>{ "You haven't been set any homework." }</p> };
+    }
+
+    let class = css!(
+        r#"
+            > div > * {
+                margin: calc( 0.25 * ${fs} );
+            }
+        "#,
+        fs = theme.font_size,
+    );
+
+    let now = Utc::now();
+    let rows = assignments
+        .into_iter()
+        .map(|student| {
+            let StudentAssignment {
+                assignment,
+                status,
+                attempts_used,
+            } = student;
+            let can_start = assignment.can_attempt(now, attempts_used);
+            let attempts = match assignment.attempt_limit() {
+                0 => format!("{attempts_used} attempts"),
+                limit => format!("{attempts_used}/{limit} attempts"),
+            };
+            let onstart = {
+                let nav = nav.clone();
+                let route = Route::AssignedQuiz {
+                    set_name: assignment.set_name().clone().into(),
+                    assignment_id: assignment.id().clone().into(),
+                };
+                move |_| nav.push(&route)
+            };
+            let status_class = match status {
+                AssignmentStatus::Overdue | AssignmentStatus::CompletedLate => {
+                    css!("color: ${fc};", fc = theme.fail_color)
+                }
+                AssignmentStatus::Completed => css!("color: ${sc};", sc = theme.success_color),
+                _ => css!(),
+            };
+            html! {
+                <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
+                    <p>{ assignment.set_name() }</p>
+                    <p>{ assignment.class_name() }</p>
+                    <p>{ "Due " }{ format_date(assignment.due()) }</p>
+                    <p class={status_class}>{ status.name() }</p>
+                    <p>{ attempts }</p>
+                    <Button onclick={onstart} clickable={can_start}>{ "Start" }</Button>
+                </Row>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <Column {class} wfill={true}>{ rows }</Column>
+    }
+}
+
+/// One assignment as its teacher sees it, with who has done it and a
+/// button to delete it.
+#[autoprops]
+#[styled_component(AssignmentCard)]
+pub fn assignment_card(assignment: &Assignment, dependency: &UseStateHandle<bool>) -> Html {
+    let theme = use_theme();
+    let grid = use_completion_grid(assignment.id().clone().into());
+
+    let ondelete = {
+        let id = assignment.id().clone();
+        let dependency = dependency.clone();
+        move |_| {
+            let (id, dependency) = (id.clone(), dependency.clone());
+            spawn_local(async move {
+                if let Err(why) = invoke_delete_assignment(id).await {
+                    let _ = MessageDialogBuilder::new()
+                        .set_title("Assignments")
+                        .set_kind(MessageDialogKind::Error)
+                        .message(&why.message)
+                        .await;
+                }
+                dependency.set(!*dependency);
+            });
+        }
+    };
+
+    let rows = match grid {
+        Ok(res) => match res.clone() {
+            Ok(grid) if grid.rows.is_empty() => {
+                html! { <p>{ "Nobody is enrolled in this class." }</p> }
+            }
+            Ok(grid) => grid
+                .rows
+                .into_iter()
+                .map(|row| {
+                    let score = match row.best_score {
+                        Some((score, max)) => format!("{score}/{max}"),
+                        None => String::from("-"),
+                    };
+                    let finished = match (row.first_finished, row.late) {
+                        (Some(date), true) => format!("{} (late)", format_date(&date)),
+                        (Some(date), false) => format_date(&date),
+                        (None, _) => String::from("Not done"),
+                    };
+                    html! {
+                        <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
+                            <p>{ row.username }</p>
+                            <p>{ row.attempts }</p>
+                            <p>{ score }</p>
+                            <p>{ finished }</p>
+                        </Row>
+                    }
+                })
+                .collect::<Html>(),
+            Err(why) => html! { <p>{ why.message }</p> },
+        },
+        Err(_) => html! { "Loading..." },
+    };
+
+    let css = css!(
+        r#"
+            background-color: ${bg};
+            padding: calc( 0.5 * ${fs} );
+            margin: calc( 0.5 * ${fs} );
+
+            h2 {
+                font-size: calc( 1.25 * ${fs} );
+            }
+
+            p {
+                flex: 1;
+                text-align: center;
+            }
+        "#,
+        bg = theme.bg_shade,
+        fs = theme.font_size,
+    );
+
+    let limit = match assignment.attempt_limit() {
+        0 => String::from("Unlimited attempts"),
+        1 => String::from("1 attempt"),
+        n => format!("{n} attempts"),
+    };
+
+    html! {
+        <Column class={css} wfill={true}>
+            <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
+                <h2>{ assignment.set_name() }{ " for " }{ assignment.class_name() }</h2>
+                <p>{ format_date(assignment.opens()) }{ " to " }{ format_date(assignment.due()) }</p>
+                <p>{ limit }</p>
+                if let Some(time_limit) = assignment.time_limit() {
+                    <p>{ time_limit.name() }</p>
+                }
+                <Button onclick={ondelete}>{ "Delete" }</Button>
+            </Row>
+            <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
+                <p><b>{ "Student" }</b></p>
+                <p><b>{ "Attempts" }</b></p>
+                <p><b>{ "Best Score" }</b></p>
+                <p><b>{ "First Finished" }</b></p>
+            </Row>
+            { rows }
+        </Column>
+    }
+}
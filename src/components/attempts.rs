@@ -0,0 +1,114 @@
+#![allow(non_camel_case_types)]
+
+use crate::{
+    app::Route,
+    commands::{invoke_discard_attempt, invoke_get_my_attempts},
+    components::{
+        inputs::Button,
+        layout::{Column, Row},
+        theme_ctx::use_theme,
+    },
+};
+use shared::{attempts::QuizAttempt, requests::UserReqError};
+use stylist::yew::styled_component;
+use tauri_sys::dialog::{MessageDialogBuilder, MessageDialogKind};
+use yew::{
+    platform::spawn_local,
+    prelude::*,
+    suspense::{use_future_with, Suspension, UseFutureHandle},
+};
+use yew_router::hooks::use_navigator;
+
+#[hook]
+pub fn use_my_attempts(
+    dependency: UseStateHandle<bool>,
+) -> Result<UseFutureHandle<Result<Vec<QuizAttempt>, UserReqError>>, Suspension> {
+    use_future_with(dependency, |_| async { invoke_get_my_attempts().await })
+}
+
+/// The quizzes the logged in user has started but not handed in, with
+/// buttons to carry on with them or throw them away.
+#[styled_component(UnfinishedAttempts)]
+pub fn unfinished_attempts() -> Html {
+    let nav = use_navigator().unwrap();
+    let theme = use_theme();
+    let dependency = use_state(|| false);
+    let attempts = match use_my_attempts(dependency.clone()) {
+        Ok(res) => match res.clone() {
+            Ok(attempts) => attempts,
+            Err(why) => return html! { <p>{ why.message }</p> },
+        },
+        Err(_) => return html! { "Loading..." },
+    };
+
+    if attempts.is_empty() {
+        return html! { <p>{ "You have no unfinished quizzes." }</p> };
+    }
+
+    let class = css!(
+        r#"
+            > div > * {
+                margin: calc( 0.25 * ${fs} );
+            }
+        "#,
+        fs = theme.font_size,
+    );
+
+    let rows = attempts
+        .into_iter()
+        .map(|attempt| {
+            let QuizAttempt {
+                set_name,
+                assignment_id,
+                current_question,
+                drafts,
+                ..
+            } = attempt;
+            let answered = drafts.iter().filter(|d| d.is_answered()).count();
+            let onresume = {
+                let nav = nav.clone();
+                let route = match assignment_id.clone() {
+                    Some(id) => Route::AssignedQuiz {
+                        set_name: set_name.clone().into(),
+                        assignment_id: id.into(),
+                    },
+                    None => Route::Quiz {
+                        set_name: set_name.clone().into(),
+                    },
+                };
+                move |_| nav.push(&route)
+            };
+            let ondiscard = {
+                let dependency = dependency.clone();
+                let (set_name, assignment_id) = (set_name.clone(), assignment_id.clone());
+                move |_| {
+                    let dependency = dependency.clone();
+                    let (set_name, assignment_id) = (set_name.clone(), assignment_id.clone());
+                    spawn_local(async move {
+                        if let Err(why) = invoke_discard_attempt(set_name, assignment_id).await {
+                            let _ = MessageDialogBuilder::new()
+                                .set_title("Unfinished Quizzes")
+                                .set_kind(MessageDialogKind::Error)
+                                .message(&why.message)
+                                .await;
+                        }
+                        dependency.set(!*dependency);
+                    });
+                }
+            };
+            html! {
+                <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
+                    <p>{ &set_name }</p>
+                    <p>{ "On question " }{ current_question + 1 }</p>
+                    <p>{ answered }{ " answered" }</p>
+                    <Button onclick={onresume}>{ "Resume" }</Button>
+                    <Button onclick={ondiscard}>{ "Discard" }</Button>
+                </Row>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <Column {class} wfill={true}>{ rows }</Column>
+    }
+}
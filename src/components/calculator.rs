@@ -0,0 +1,179 @@
+#![allow(non_camel_case_types)]
+
+use crate::components::{
+    inputs::Button,
+    layout::{Column, Row},
+    theme_ctx::use_theme,
+};
+use shared::calculator::{format_result, Calculator};
+use stylist::yew::styled_component;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_autoprops::autoprops;
+
+/// A scientific calculator that students can use on questions which
+/// allow one. Every calculation is added to `log` as it is made, like
+/// `3^2 + 4^2 = 25`.
+#[autoprops]
+#[styled_component(CalculatorPanel)]
+pub fn calculator_panel(log: &UseStateHandle<Vec<String>>) -> Html {
+    let theme = use_theme();
+    let calculator = use_state_eq(Calculator::default);
+    let expression = use_state_eq(String::new);
+    let display = use_state_eq(|| String::from("0"));
+
+    let evaluate = {
+        let (calculator, expression) = (calculator.clone(), expression.clone());
+        let (display, log) = (display.clone(), log.clone());
+        Callback::from(move |_: ()| {
+            let mut calc = (*calculator).clone();
+            match calc.evaluate(&expression) {
+                Ok(value) => {
+                    let result = format_result(value);
+                    let mut entries = (*log).clone();
+                    entries.push(format!("{} = {result}", *expression));
+                    log.set(entries);
+                    display.set(result);
+                    calculator.set(calc);
+                    expression.set(String::new());
+                }
+                Err(why) => display.set(why),
+            }
+        })
+    };
+
+    // Each key on the panel types its text onto the end of the expression.
+    let key = |text: &'static str| {
+        let expression = expression.clone();
+        move |_: MouseEvent| expression.set(format!("{}{text}", *expression))
+    };
+    let keys = [
+        ("sin", "sin("),
+        ("cos", "cos("),
+        ("tan", "tan("),
+        ("sin⁻¹", "asin("),
+        ("cos⁻¹", "acos("),
+        ("tan⁻¹", "atan("),
+        ("ln", "ln("),
+        ("log", "log("),
+        ("√", "√("),
+        ("xʸ", "^"),
+        ("x!", "!"),
+        ("π", "π"),
+        ("e", "e"),
+        ("(", "("),
+        (")", ")"),
+        ("Ans", "Ans"),
+        ("MR", "M"),
+    ]
+    .into_iter()
+    .map(|(label, text)| html! { <Button onclick={key(text)}>{ label }</Button> })
+    .collect::<Html>();
+
+    let onmode = {
+        let calculator = calculator.clone();
+        move |_| {
+            let mut calc = (*calculator).clone();
+            calc.mode = calc.mode.toggled();
+            calculator.set(calc);
+        }
+    };
+    let memory = |change: fn(&mut Calculator, f64)| {
+        let calculator = calculator.clone();
+        move |_: MouseEvent| {
+            let mut calc = (*calculator).clone();
+            change(&mut calc, calc.ans);
+            calculator.set(calc);
+        }
+    };
+    let onclear_memory = {
+        let calculator = calculator.clone();
+        move |_| {
+            let mut calc = (*calculator).clone();
+            calc.memory_clear();
+            calculator.set(calc);
+        }
+    };
+    let onclear = {
+        let (expression, display) = (expression.clone(), display.clone());
+        move |_| {
+            expression.set(String::new());
+            display.set(String::from("0"));
+        }
+    };
+    let onequals = {
+        let evaluate = evaluate.clone();
+        move |_| evaluate.emit(())
+    };
+    let oninput = {
+        let expression = expression.clone();
+        move |e: InputEvent| {
+            expression.set(
+                e.target_dyn_into::<HtmlInputElement>()
+                    .expect("Input Element Failed To Cast")
+                    .value(),
+            )
+        }
+    };
+    let onkeydown = move |e: KeyboardEvent| {
+        if e.key() == "Enter" {
+            evaluate.emit(());
+        }
+    };
+
+    let class = css!(
+        r#"
+            background-color: ${bg};
+            padding: calc( 0.5 * ${fs} );
+            max-width: calc( 24 * ${fs} );
+
+            input {
+                width: 100%;
+                font-size: calc( 1.25 * ${fs} );
+                background-color: ${bgc};
+                color: ${fg};
+                border: 2px solid ${pc};
+            }
+
+            h3 {
+                width: 100%;
+                text-align: right;
+            }
+
+            .keys {
+                display: grid;
+                grid-template-columns: repeat(6, 1fr);
+            }
+
+            button {
+                margin: calc( 0.125 * ${fs} );
+            }
+        "#,
+        bg = theme.bg_shade,
+        bgc = theme.bg_color,
+        fg = theme.fg_color,
+        pc = theme.primary_color,
+        fs = theme.font_size,
+    );
+
+    html! {
+        <Column {class}>
+            <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
+                <Button onclick={onmode}>{ calculator.mode.name() }</Button>
+                if calculator.memory != 0.0 {
+                    <span>{ "M" }</span>
+                }
+            </Row>
+            <input type={"text"} value={(*expression).clone()} {oninput} {onkeydown} />
+            <h3>{ (*display).clone() }</h3>
+            <div class={classes!("keys")}>
+                { keys }
+                <Button onclick={memory(Calculator::memory_add)}>{ "M+" }</Button>
+                <Button onclick={memory(Calculator::memory_subtract)}>{ "M−" }</Button>
+                <Button onclick={onclear_memory}>{ "MC" }</Button>
+                <Button onclick={onclear}>{ "AC" }</Button>
+                <Button onclick={onequals}>{ "=" }</Button>
+            </div>
+        </Column>
+    }
+}
@@ -0,0 +1,96 @@
+#![allow(non_camel_case_types)]
+
+use crate::{
+    commands::invoke_get_calibration,
+    components::{charts::BarChart, layout::Column, theme_ctx::use_theme},
+};
+use shared::{analytics::CalibrationReport, requests::UserReqError};
+use stylist::yew::styled_component;
+use yew::{
+    prelude::*,
+    suspense::{use_future, Suspension, UseFutureHandle},
+};
+
+#[hook]
+pub fn use_calibration(
+) -> Result<UseFutureHandle<Result<CalibrationReport, UserReqError>>, Suspension> {
+    use_future(|| async { invoke_get_calibration().await })
+}
+
+/// How often the logged in user is right at each level of confidence,
+/// next to how often they would be if their confidence was spot on,
+/// and the topics where they are most overconfident.
+#[styled_component(Calibration)]
+pub fn calibration() -> Html {
+    let theme = use_theme();
+    let report = match use_calibration() {
+        Ok(res) => match res.clone() {
+            Ok(report) => report,
+            Err(why) => return html! { <p>{ why.message }</p> },
+        },
+        Err(_) => return html! { "Loading..." },
+    };
+
+    if report.levels.iter().all(|level| level.answers == 0) {
+        return html! { <p>{ "Rate how sure you are of your answers to see how well you judge them." }</p> };
+    }
+
+    let class = css!(
+        r#"
+            h2 {
+                margin-top: ${fs};
+                margin-bottom: calc( 0.5 * ${fs} );
+            }
+
+            li {
+                margin-left: ${fs};
+            }
+        "#,
+        fs = theme.font_size,
+    );
+
+    let bars = report
+        .levels
+        .iter()
+        .filter(|level| level.answers > 0)
+        .flat_map(|level| {
+            [
+                (
+                    AttrValue::from(format!("{} ({})", level.label, level.answers)),
+                    level.accuracy(),
+                ),
+                (
+                    AttrValue::from(format!("{} expected", level.label)),
+                    level.expected_accuracy(),
+                ),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let overconfident = report
+        .overconfident
+        .iter()
+        .map(|topic| {
+            html! {
+                <li>
+                    <b>{ &topic.label }</b>
+                    { format!(
+                        " - right {:.0}% of the time, but expected {:.0}%",
+                        topic.accuracy(),
+                        topic.expected_accuracy(),
+                    ) }
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <Column {class}>
+            <h2>{ "Accuracy by confidence (%)" }</h2>
+            <BarChart {bars} />
+            if !report.overconfident.is_empty() {
+                <h2>{ "Overconfident topics" }</h2>
+                <ol>{ overconfident }</ol>
+            }
+        </Column>
+    }
+}
@@ -0,0 +1,104 @@
+#![allow(non_camel_case_types)]
+
+//! Small charts drawn as SVG, so that nothing has to be loaded from a
+//! chart library or CDN. Every chart plots values between zero and
+//! `max`, which defaults to 100 for percentages.
+
+use crate::components::theme_ctx::use_theme;
+use stylist::yew::styled_component;
+use yew::prelude::*;
+use yew_autoprops::autoprops;
+
+const WIDTH: f64 = 400.0;
+const HEIGHT: f64 = 200.0;
+/// Room left around the plot for the axis labels.
+const PAD: f64 = 30.0;
+
+/// A line through `values` from left to right, with a dot on each one
+/// that shows its label when hovered over.
+#[autoprops]
+#[styled_component(LineChart)]
+pub fn line_chart(values: &Vec<f64>, labels: &Vec<AttrValue>, #[prop_or(100.0)] max: f64) -> Html {
+    let theme = use_theme();
+    let plot_w = WIDTH - 2.0 * PAD;
+    let plot_h = HEIGHT - 2.0 * PAD;
+    let x = |i: usize| match values.len() {
+        0 | 1 => PAD + plot_w / 2.0,
+        n => PAD + plot_w * i as f64 / (n - 1) as f64,
+    };
+    let y = |v: f64| PAD + plot_h * (1.0 - (v / max).clamp(0.0, 1.0));
+
+    let grid = [0.0, 0.5, 1.0]
+        .into_iter()
+        .map(|f| {
+            let gy = y(f * max);
+            html! {
+                <>
+                    <line x1={PAD.to_string()} y1={gy.to_string()} x2={(WIDTH - PAD).to_string()} y2={gy.to_string()} stroke={theme.bg_shade.clone()} />
+                    <text x={(PAD - 4.0).to_string()} y={(gy + 4.0).to_string()} text-anchor="end" font-size="10" fill={theme.input_color.clone()}>{ format!("{}", f * max) }</text>
+                </>
+            }
+        })
+        .collect::<Html>();
+
+    let points = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| format!("{:.1},{:.1}", x(i), y(*v)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let dots = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let label = labels.get(i).cloned().unwrap_or_default();
+            html! {
+                <circle cx={x(i).to_string()} cy={y(*v).to_string()} r="3" fill={theme.primary_shade.clone()}>
+                    <title>{ format!("{label}: {v:.0}") }</title>
+                </circle>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <svg viewBox={format!("0 0 {WIDTH} {HEIGHT}")} width={WIDTH.to_string()} height={HEIGHT.to_string()}>
+            { grid }
+            <polyline {points} fill="none" stroke={theme.primary_color.clone()} stroke-width="2" />
+            { dots }
+        </svg>
+    }
+}
+
+/// One horizontal bar for each label, with its value written beside it.
+#[autoprops]
+#[styled_component(BarChart)]
+pub fn bar_chart(bars: &Vec<(AttrValue, f64)>, #[prop_or(100.0)] max: f64) -> Html {
+    let theme = use_theme();
+    const BAR: f64 = 20.0;
+    const LABEL_W: f64 = 120.0;
+    let height = (bars.len() as f64 * (BAR + 6.0)).max(BAR);
+    let bar_w = WIDTH - LABEL_W - PAD;
+
+    let rows = bars
+        .iter()
+        .enumerate()
+        .map(|(i, (label, value))| {
+            let top = i as f64 * (BAR + 6.0);
+            let w = bar_w * (value / max).clamp(0.0, 1.0);
+            html! {
+                <>
+                    <text x={(LABEL_W - 4.0).to_string()} y={(top + 14.0).to_string()} text-anchor="end" font-size="11" fill={theme.fg_color.clone()}>{ label }</text>
+                    <rect x={LABEL_W.to_string()} y={top.to_string()} width={bar_w.to_string()} height={BAR.to_string()} fill={theme.bg_shade.clone()} />
+                    <rect x={LABEL_W.to_string()} y={top.to_string()} width={w.to_string()} height={BAR.to_string()} fill={theme.primary_color.clone()} />
+                    <text x={(LABEL_W + bar_w + 4.0).to_string()} y={(top + 14.0).to_string()} font-size="11" fill={theme.fg_color.clone()}>{ format!("{value:.0}") }</text>
+                </>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <svg viewBox={format!("0 0 {WIDTH} {height}")} width={WIDTH.to_string()} height={height.to_string()}>
+            { rows }
+        </svg>
+    }
+}
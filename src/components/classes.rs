@@ -0,0 +1,145 @@
+#![allow(non_camel_case_types)]
+
+use crate::{
+    commands::{
+        invoke_add_class_teacher, invoke_delete_class, invoke_enrol_student, invoke_get_classes,
+        invoke_unenrol_student,
+    },
+    components::{
+        inputs::{Button, UserPicker},
+        layout::{Column, Row},
+        theme_ctx::use_theme,
+    },
+};
+use shared::{classes::Class, requests::UserReqError};
+use stylist::yew::styled_component;
+use tauri_sys::dialog::{MessageDialogBuilder, MessageDialogKind};
+use yew::{
+    platform::spawn_local,
+    prelude::*,
+    suspense::{use_future_with, Suspension, UseFutureHandle},
+};
+use yew_autoprops::autoprops;
+
+#[hook]
+pub fn use_classes(
+    update: UseStateHandle<bool>,
+) -> Result<UseFutureHandle<Result<Vec<Class>, UserReqError>>, Suspension> {
+    use_future_with(update, |_| async { invoke_get_classes().await })
+}
+
+/// Shows a dialog if a class change failed, then asks for the classes
+/// to be fetched again either way.
+async fn report(result: Result<(), UserReqError>, dependency: UseStateHandle<bool>) {
+    if let Err(why) = result {
+        let _ = MessageDialogBuilder::new()
+            .set_title("Classes")
+            .set_kind(MessageDialogKind::Error)
+            .message(&why.message)
+            .await;
+    }
+    dependency.set(!*dependency);
+}
+
+/// One class, with controls for enrolling and removing students and
+/// for sharing it with another teacher.
+#[autoprops]
+#[styled_component(ClassCard)]
+pub fn class_card(class: &Class, dependency: &UseStateHandle<bool>) -> Html {
+    let theme = use_theme();
+    let student = use_state_eq(|| AttrValue::from(String::new()));
+    let teacher = use_state_eq(|| AttrValue::from(String::new()));
+    let users_changed = use_state(|| false);
+
+    let name = class.name().clone();
+    let onenrol = {
+        let (name, student) = (name.clone(), student.clone());
+        let dependency = dependency.clone();
+        move |_| {
+            let (name, username) = (name.clone(), student.to_string());
+            let dependency = dependency.clone();
+            spawn_local(async move {
+                report(invoke_enrol_student(name, username).await, dependency).await
+            });
+        }
+    };
+    let onteacher = {
+        let (name, teacher) = (name.clone(), teacher.clone());
+        let dependency = dependency.clone();
+        move |_| {
+            let (name, username) = (name.clone(), teacher.to_string());
+            let dependency = dependency.clone();
+            spawn_local(async move {
+                report(invoke_add_class_teacher(name, username).await, dependency).await
+            });
+        }
+    };
+    let ondelete = {
+        let name = name.clone();
+        let dependency = dependency.clone();
+        move |_| {
+            let (name, dependency) = (name.clone(), dependency.clone());
+            spawn_local(async move { report(invoke_delete_class(name).await, dependency).await });
+        }
+    };
+
+    let students = class
+        .students()
+        .iter()
+        .map(|username| {
+            let onremove = {
+                let (name, username) = (name.clone(), username.clone());
+                let dependency = dependency.clone();
+                move |_| {
+                    let (name, username) = (name.clone(), username.clone());
+                    let dependency = dependency.clone();
+                    spawn_local(async move {
+                        report(invoke_unenrol_student(name, username).await, dependency).await
+                    });
+                }
+            };
+            html! {
+                <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
+                    <p>{ username }</p>
+                    <Button onclick={onremove}>{ "Remove" }</Button>
+                </Row>
+            }
+        })
+        .collect::<Html>();
+
+    let css = css!(
+        r#"
+            background-color: ${bg};
+            padding: calc( 0.5 * ${fs} );
+            margin: calc( 0.5 * ${fs} );
+
+            h2 {
+                font-size: calc( 1.25 * ${fs} );
+            }
+
+            > div > * {
+                margin: calc( 0.125 * ${fs} );
+            }
+        "#,
+        bg = theme.bg_shade,
+        fs = theme.font_size,
+    );
+
+    html! {
+        <Column class={css} wfill={true}>
+            <Row wfill={true} justify_content={"space-between"} align_items={"center"}>
+                <h2>{ class.name() }</h2>
+                <p>{ "Taught by " }{ class.teachers().join(", ") }</p>
+                <Button onclick={ondelete}>{ "Delete Class" }</Button>
+            </Row>
+            <p>{ class.students().len() }{ " students" }</p>
+            { students }
+            <Row align_items={"center"}>
+                <UserPicker id={format!("{name}-student")} user_handle={student} dependency={users_changed.clone()}>{ "Student" }</UserPicker>
+                <Button onclick={onenrol}>{ "Enrol" }</Button>
+                <UserPicker id={format!("{name}-teacher")} user_handle={teacher} dependency={users_changed}>{ "Teacher" }</UserPicker>
+                <Button onclick={onteacher}>{ "Add Teacher" }</Button>
+            </Row>
+        </Column>
+    }
+}
@@ -0,0 +1,193 @@
+#![allow(non_camel_case_types)]
+
+use crate::{
+    app::Route,
+    components::{
+        inputs::{Button, SelectInput},
+        layout::{Column, Row},
+        questions::{use_set_summaries, use_set_tags},
+        theme_ctx::use_theme,
+    },
+};
+use chrono::Utc;
+use shared::{
+    queries::{Page, SetQuery},
+    questions::{MAX_DIFFICULTY, MIN_DIFFICULTY},
+    sources::{CustomQuiz, CUSTOM_QUIZ_MAX},
+    timing::TimeLimit,
+};
+use stylist::yew::styled_component;
+use tauri_sys::dialog::{MessageDialogBuilder, MessageDialogKind};
+use yew::{platform::spawn_local, prelude::*};
+use yew_autoprops::autoprops;
+use yew_router::hooks::use_navigator;
+
+const NO_TIME_LIMIT: &str = "No limit";
+/// The most sets offered to choose from in the builder.
+const SET_CHOICES: u64 = 200;
+
+/// A dropdown for adding to a list of choices, with a button to take
+/// each one back out again.
+#[autoprops]
+#[function_component(PickMany)]
+fn pick_many(
+    id: AttrValue,
+    label: AttrValue,
+    options: &Vec<AttrValue>,
+    chosen: &UseStateHandle<Vec<String>>,
+) -> Html {
+    let selected = use_state_eq(|| AttrValue::from(String::new()));
+    let options = std::iter::once(AttrValue::from(String::new()))
+        .chain(options.iter().cloned())
+        .collect::<Vec<_>>();
+    let onadd = {
+        let (selected, chosen) = (selected.clone(), chosen.clone());
+        move |_| {
+            let mut all = (*chosen).clone();
+            if !selected.is_empty() && !all.contains(&selected.to_string()) {
+                all.push(selected.to_string());
+            }
+            chosen.set(all);
+        }
+    };
+    let picked = chosen
+        .iter()
+        .map(|choice| {
+            let onclick = {
+                let (chosen, choice) = (chosen.clone(), choice.clone());
+                move |_| {
+                    let all = chosen.iter().filter(|c| **c != choice).cloned().collect();
+                    chosen.set(all);
+                }
+            };
+            html! { <Button {onclick}>{ choice }{ " ✕" }</Button> }
+        })
+        .collect::<Html>();
+
+    html! {
+        <Column>
+            <Row align_items={"center"}>
+                <SelectInput {id} {options} handle={selected}>{ label }</SelectInput>
+                <Button onclick={onadd}>{ "Add" }</Button>
+            </Row>
+            <Row align_items={"center"}>{ picked }</Row>
+        </Column>
+    }
+}
+
+/// A dialog for putting together a quiz from questions in several sets
+/// or topics, which the backend then draws at random.
+#[autoprops]
+#[styled_component(CustomQuizBuilder)]
+pub fn custom_quiz_builder(onclose: Callback<MouseEvent>) -> Html {
+    let nav = use_navigator().unwrap();
+    let theme = use_theme();
+
+    let set_names = use_state_eq(Vec::<String>::new);
+    let tags = use_state_eq(Vec::<String>::new);
+    let counts = [5, 10, 15, 20, 30, 40, CUSTOM_QUIZ_MAX]
+        .map(|c| AttrValue::from(c.to_string()))
+        .to_vec();
+    let count = use_state_eq(|| AttrValue::from("20"));
+    let difficulties = (MIN_DIFFICULTY..=MAX_DIFFICULTY)
+        .map(|d| AttrValue::from(d.to_string()))
+        .collect::<Vec<_>>();
+    let min_difficulty = use_state_eq(|| difficulties[0].clone());
+    let max_difficulty = use_state_eq(|| difficulties[difficulties.len() - 1].clone());
+    let time_limits = std::iter::once(AttrValue::from(NO_TIME_LIMIT))
+        .chain(
+            TimeLimit::presets()
+                .iter()
+                .map(|l| AttrValue::from(l.name())),
+        )
+        .collect::<Vec<_>>();
+    let time_limit = use_state_eq(|| AttrValue::from(NO_TIME_LIMIT));
+
+    let dependency = use_state(|| false);
+    let set_options =
+        match use_set_summaries(SetQuery::default(), Page::new(0, SET_CHOICES), dependency) {
+            Ok(res) => res
+                .clone()
+                .map(|paged| paged.items)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|set| AttrValue::from(set.name))
+                .collect(),
+            Err(_) => vec![],
+        };
+    let tag_options = match use_set_tags() {
+        Ok(tags) => tags
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .map(AttrValue::from)
+            .collect(),
+        Err(_) => vec![],
+    };
+
+    let onstart = {
+        let (set_names, tags, count) = (set_names.clone(), tags.clone(), count.clone());
+        let (min_difficulty, max_difficulty) = (min_difficulty.clone(), max_difficulty.clone());
+        let time_limit = time_limit.clone();
+        move |_| {
+            let custom = CustomQuiz {
+                set_names: (*set_names).clone(),
+                tags: (*tags).clone(),
+                count: count.parse().unwrap_or_default(),
+                min_difficulty: min_difficulty.parse().unwrap_or(MIN_DIFFICULTY),
+                max_difficulty: max_difficulty.parse().unwrap_or(MAX_DIFFICULTY),
+                time_limit: time_limit.parse().ok(),
+                seed: Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64,
+            };
+            match custom.validate() {
+                Ok(_) => nav.push_with_state(&Route::CustomQuiz, custom),
+                Err(why) => spawn_local(async move {
+                    let _ = MessageDialogBuilder::new()
+                        .set_title("Build a Quiz")
+                        .set_kind(MessageDialogKind::Error)
+                        .message(&why)
+                        .await;
+                }),
+            }
+        }
+    };
+
+    let class = css!(
+        r#"
+            position: fixed;
+            inset: 0;
+            display: flex;
+            align-items: center;
+            justify-content: center;
+            background-color: rgba(0, 0, 0, 0.5);
+
+            > div {
+                padding: ${fs};
+                background-color: ${bg};
+            }
+        "#,
+        fs = theme.font_size,
+        bg = theme.bg_color,
+    );
+
+    html! {
+        <div {class}>
+            <Column align_items={"center"}>
+                <h1>{ "Build a Quiz" }</h1>
+                <p>{ "Questions are drawn at random from every set and topic chosen." }</p>
+                <PickMany id={"custom_sets"} label={"Sets"} options={set_options} chosen={set_names} />
+                <PickMany id={"custom_tags"} label={"Topics"} options={tag_options} chosen={tags} />
+                <Row align_items={"center"} justify_content={"center"}>
+                    <SelectInput id={"custom_count"} options={counts} handle={count}>{ "Questions" }</SelectInput>
+                    <SelectInput id={"custom_min"} options={difficulties.clone()} handle={min_difficulty}>{ "Easiest" }</SelectInput>
+                    <SelectInput id={"custom_max"} options={difficulties} handle={max_difficulty}>{ "Hardest" }</SelectInput>
+                    <SelectInput id={"custom_time_limit"} options={time_limits} handle={time_limit}>{ "Time Limit" }</SelectInput>
+                </Row>
+                <Row align_items={"center"} justify_content={"center"}>
+                    <Button onclick={onclose}>{ "Cancel" }</Button>
+                    <Button onclick={onstart}>{ "Start" }</Button>
+                </Row>
+            </Column>
+        </div>
+    }
+}
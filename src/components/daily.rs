@@ -0,0 +1,47 @@
+#![allow(non_camel_case_types)]
+
+use crate::{
+    app::Route,
+    commands::invoke_get_due_count,
+    components::{inputs::Button, layout::Column},
+};
+use shared::requests::UserReqError;
+use yew::{
+    prelude::*,
+    suspense::{use_future, Suspension, UseFutureHandle},
+};
+use yew_router::hooks::use_navigator;
+
+#[hook]
+pub fn use_due_count() -> Result<UseFutureHandle<Result<u32, UserReqError>>, Suspension> {
+    use_future(|| async { invoke_get_due_count().await })
+}
+
+/// How many questions are due for spaced repetition review today, with
+/// a button to start reviewing them.
+#[function_component(DailyReviewCard)]
+pub fn daily_review_card() -> Html {
+    let nav = use_navigator().unwrap();
+    let due = match use_due_count() {
+        Ok(res) => match res.clone() {
+            Ok(due) => due,
+            Err(why) => return html! { <p>{ why.message }</p> },
+        },
+        Err(_) => return html! { "Loading..." },
+    };
+
+    let message = match due {
+        0 => String::from("You are all caught up for today."),
+        1 => String::from("1 question is due for review today."),
+        n => format!("{n} questions are due for review today."),
+    };
+
+    html! {
+        <Column align_items={"center"}>
+            <p>{ message }</p>
+            <Button clickable={due > 0} onclick={move |_| nav.push(&Route::DailyReview)}>
+                { "Start Review" }
+            </Button>
+        </Column>
+    }
+}
@@ -0,0 +1,54 @@
+#![allow(non_camel_case_types)]
+
+use crate::components::{
+    inputs::Button,
+    layout::{Column, Row},
+    theme_ctx::use_theme,
+};
+use shared::responses::Response;
+use stylist::yew::styled_component;
+use yew::prelude::*;
+use yew_autoprops::autoprops;
+
+/// Shows a practice answer straight after it is marked, along with the
+/// correct answer and worked solution. `retries_left` is `None` once the
+/// answer can't be tried again.
+#[autoprops]
+#[styled_component(PracticeFeedback)]
+pub fn practice_feedback(
+    response: &Response,
+    retries_left: Option<u32>,
+    onretry: Callback<MouseEvent>,
+    onnext: Callback<MouseEvent>,
+) -> Html {
+    let theme = use_theme();
+    let verdict_class = match response.is_correct() {
+        true => css!("color: ${sc};", sc = theme.success_color),
+        false => css!("color: ${fc};", fc = theme.fail_color),
+    };
+    let steps = response
+        .solution()
+        .iter()
+        .map(|step| html! { <li>{ step }</li> })
+        .collect::<Html>();
+
+    html! {
+        <Column align_items={"center"}>
+            <h3 class={verdict_class}>
+                { if response.is_correct() { "Correct! " } else { "Not quite. " } }
+                { response.marks_awarded() }{ "/" }{ response.marks_available() }{ " marks" }
+            </h3>
+            <h3>{ "You answered: " }{ response.submitted() }</h3>
+            <h3>{ "Answer: " }{ response.answer() }</h3>
+            if !response.solution().is_empty() {
+                <ol>{ steps }</ol>
+            }
+            <Row align_items={"center"} justify_content={"center"}>
+                if let Some(left) = retries_left {
+                    <Button onclick={onretry}>{ format!("Try Again ({left} left)") }</Button>
+                }
+                <Button onclick={onnext}>{ "Next" }</Button>
+            </Row>
+        </Column>
+    }
+}
@@ -4,7 +4,7 @@ use super::theme_ctx::use_theme;
 use chrono::Utc;
 use shared::{requests::UserReqError, AccessLevel, User};
 use stylist::yew::styled_component;
-use web_sys::{wasm_bindgen::JsCast, HtmlInputElement, HtmlSelectElement};
+use web_sys::{wasm_bindgen::JsCast, HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
 use yew::{
     prelude::*,
     suspense::{use_future_with, Suspension, UseFutureHandle},
@@ -200,6 +200,9 @@ pub fn button(
 #[styled_component(DateInput)]
 pub fn date_input(
     #[prop_or(false)] shaded: bool,
+    /// Stops dates after today being picked, as for a date of birth.
+    #[prop_or(true)]
+    past_only: bool,
     children: &Children,
     id: AttrValue,
     handle: &UseStateHandle<AttrValue>,
@@ -236,7 +239,7 @@ pub fn date_input(
         pc = theme.primary_color,
         fs = theme.font_size,
     );
-    let now = Utc::now().format("%Y-%m-%d").to_string();
+    let max = past_only.then(|| Utc::now().format("%Y-%m-%d").to_string());
     let handle = handle.clone();
     let onchange = move |e: Event| {
         handle.set(
@@ -250,7 +253,7 @@ pub fn date_input(
     html! {
         <div {class}>
             <label for={id.clone()}>{ children }</label>
-            <input type={"date"} {onchange} name={id.clone()} {id} min={"1990-01-01"} max={now}/>
+            <input type={"date"} {onchange} name={id.clone()} {id} min={"1990-01-01"} {max}/>
         </div>
     }
 }
@@ -431,3 +434,133 @@ pub fn radio_toggle(name: AttrValue, children: &Children, handle: &UseStateHandl
         </div>
     }
 }
+
+#[autoprops]
+#[styled_component(SelectInput)]
+pub fn select_input(
+    children: &Children,
+    id: AttrValue,
+    options: &Vec<AttrValue>,
+    handle: &UseStateHandle<AttrValue>,
+) -> Html {
+    let onchange = {
+        let handle = handle.clone();
+        move |e: Event| {
+            handle.set(
+                e.target_dyn_into::<HtmlSelectElement>()
+                    .expect("failed to cast")
+                    .value()
+                    .into(),
+            );
+        }
+    };
+
+    let options = options
+        .iter()
+        .map(|option| {
+            let selected = option == &**handle;
+            html! { <option value={option.clone()} {selected}>{ option }</option> }
+        })
+        .collect::<Html>();
+
+    let theme = use_theme();
+    let class = css!(
+        r#"
+            padding: calc( 0.5 * ${fs} );
+            border: 4px solid ${pc};
+
+            label {
+                color: ${fc};
+            }
+
+            select {
+                margin-left: calc( 0.25 * ${fs} );
+            }
+        "#,
+        fc = theme.input_color,
+        fs = theme.font_size,
+        pc = theme.primary_color,
+    );
+
+    html! {
+        <div {class}>
+            <label for={id.clone()}>{ children }</label>
+            <select name={id.clone()} {id} {onchange}>{ options }</select>
+        </div>
+    }
+}
+
+/// A multi-line text box where every line is treated as a separate
+/// entry, used for things like hints and worked solution steps.
+#[autoprops]
+#[styled_component(TextArea)]
+pub fn text_area(
+    children: &Children,
+    id: AttrValue,
+    #[prop_or(4)] rows: u32,
+    text_handle: &UseStateHandle<AttrValue>,
+) -> Html {
+    if let Some(window) = web_sys::window() {
+        if let Some(document) = window.document() {
+            if let Some(element) = document.get_element_by_id(&id) {
+                if let Ok(element) = element.dyn_into::<HtmlTextAreaElement>() {
+                    element.set_value(text_handle);
+                }
+            }
+        }
+    }
+
+    let oninput = {
+        let text_handle = text_handle.clone();
+        move |e: InputEvent| {
+            text_handle.set(
+                e.target_dyn_into::<HtmlTextAreaElement>()
+                    .expect("failed to cast")
+                    .value()
+                    .into(),
+            );
+        }
+    };
+
+    let theme = use_theme();
+    let class = css!(
+        r#"
+            display: flex;
+            flex-direction: column;
+
+            label {
+                color: ${ic};
+                font-size: calc( 0.75 * ${fs} );
+            }
+
+            textarea {
+                color: ${fg};
+                padding: calc( 0.5 * ${fs} );
+                background-color: ${bg};
+                border: solid 4px ${pc};
+                resize: vertical;
+            }
+        "#,
+        fs = theme.font_size,
+        ic = theme.input_color,
+        fg = theme.fg_color,
+        bg = theme.bg_color,
+        pc = theme.primary_color,
+    );
+
+    html! {
+        <div {class}>
+            <label for={&id}>{ children }</label>
+            <textarea {oninput} name={&id} {id} rows={rows.to_string()}></textarea>
+        </div>
+    }
+}
+
+/// Splits the contents of a [`TextArea`] into its non-empty lines.
+pub fn lines_of(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
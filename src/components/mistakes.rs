@@ -0,0 +1,96 @@
+#![allow(non_camel_case_types)]
+
+use crate::{
+    app::Route,
+    commands::invoke_get_mistake_deck,
+    components::{
+        inputs::{Button, SelectInput},
+        layout::{Column, Row},
+        questions::use_set_tags,
+    },
+};
+use shared::{
+    mistakes::{DeckEntry, DeckFilter},
+    requests::UserReqError,
+};
+use yew::{
+    prelude::*,
+    suspense::{use_future_with, Suspension, UseFutureHandle},
+};
+use yew_router::hooks::use_navigator;
+
+const ALL_SETS: &str = "All sets";
+const ALL_TAGS: &str = "All tags";
+
+#[hook]
+pub fn use_mistake_deck(
+    filter: DeckFilter,
+) -> Result<UseFutureHandle<Result<Vec<DeckEntry>, UserReqError>>, Suspension> {
+    use_future_with(filter, |filter| {
+        let filter = (*filter).clone();
+        async { invoke_get_mistake_deck(filter).await }
+    })
+}
+
+/// How many questions are in the logged in user's mistake deck, which
+/// can be narrowed down to one set or topic before practising them.
+#[function_component(MistakeDeckCard)]
+pub fn mistake_deck_card() -> Html {
+    let nav = use_navigator().unwrap();
+    let set = use_state_eq(|| AttrValue::from(ALL_SETS));
+    let tag = use_state_eq(|| AttrValue::from(ALL_TAGS));
+
+    let filter = DeckFilter {
+        set_name: Some(set.to_string()).filter(|s| s != ALL_SETS),
+        topic: Some(tag.to_string()).filter(|t| t != ALL_TAGS),
+    };
+    // The sets to choose from are the ones with anything in the deck.
+    let mut set_names = match use_mistake_deck(DeckFilter::default()) {
+        Ok(res) => res
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| entry.set_name)
+            .collect::<Vec<_>>(),
+        Err(_) => vec![],
+    };
+    set_names.sort();
+    set_names.dedup();
+    let set_options = std::iter::once(AttrValue::from(ALL_SETS))
+        .chain(set_names.into_iter().map(AttrValue::from))
+        .collect::<Vec<_>>();
+    let tag_options = match use_set_tags() {
+        Ok(tags) => tags.clone().unwrap_or_default(),
+        Err(_) => vec![],
+    };
+    let tag_options = std::iter::once(AttrValue::from(ALL_TAGS))
+        .chain(tag_options.into_iter().map(AttrValue::from))
+        .collect::<Vec<_>>();
+
+    let count = match use_mistake_deck(filter.clone()) {
+        Ok(res) => match res.clone() {
+            Ok(deck) => deck.len(),
+            Err(why) => return html! { <p>{ why.message }</p> },
+        },
+        Err(_) => return html! { "Loading..." },
+    };
+    let message = match count {
+        0 => String::from("There are no mistakes left to put right."),
+        1 => String::from("1 question to put right."),
+        n => format!("{n} questions to put right."),
+    };
+    let onclick = move |_| {
+        let _ = nav.push_with_query(&Route::Mistakes, &filter);
+    };
+
+    html! {
+        <Column align_items={"center"}>
+            <Row align_items={"center"} justify_content={"center"}>
+                <SelectInput id={"deck_set"} options={set_options} handle={set}>{ "Set" }</SelectInput>
+                <SelectInput id={"deck_tag"} options={tag_options} handle={tag}>{ "Topic" }</SelectInput>
+            </Row>
+            <p>{ message }</p>
+            <Button clickable={count > 0} {onclick}>{ "Practise Mistakes" }</Button>
+        </Column>
+    }
+}
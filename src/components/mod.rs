@@ -1,6 +1,19 @@
+pub mod adaptive;
 pub mod administration;
+pub mod arrangements;
+pub mod assignments;
+pub mod attempts;
+pub mod calculator;
+pub mod calibration;
+pub mod charts;
+pub mod classes;
+pub mod custom;
+pub mod daily;
+pub mod feedback;
 pub mod inputs;
 pub mod layout;
+pub mod mistakes;
+pub mod progress;
 pub mod questions;
 pub mod tabs;
 pub mod theme_ctx;
@@ -0,0 +1,103 @@
+#![allow(non_camel_case_types)]
+
+use crate::{
+    commands::invoke_get_progress,
+    components::{
+        charts::{BarChart, LineChart},
+        layout::{Column, Row},
+        theme_ctx::use_theme,
+    },
+};
+use shared::{analytics::ProgressReport, requests::UserReqError};
+use stylist::yew::styled_component;
+use yew::{
+    prelude::*,
+    suspense::{use_future, Suspension, UseFutureHandle},
+};
+
+#[hook]
+pub fn use_progress() -> Result<UseFutureHandle<Result<ProgressReport, UserReqError>>, Suspension> {
+    use_future(|| async { invoke_get_progress().await })
+}
+
+/// The logged in user's scores over time, how well they do on each set,
+/// and the questions they get wrong most often.
+#[styled_component(Progress)]
+pub fn progress() -> Html {
+    let theme = use_theme();
+    let report = match use_progress() {
+        Ok(res) => match res.clone() {
+            Ok(report) => report,
+            Err(why) => return html! { <p>{ why.message }</p> },
+        },
+        Err(_) => return html! { "Loading..." },
+    };
+
+    if report.history.is_empty() {
+        return html! { <p>{ "Finish a quiz to start tracking your progress." }</p> };
+    }
+
+    let class = css!(
+        r#"
+            h2 {
+                margin-top: ${fs};
+                margin-bottom: calc( 0.5 * ${fs} );
+            }
+
+            li {
+                margin-left: ${fs};
+            }
+        "#,
+        fs = theme.font_size,
+    );
+
+    let values = report
+        .history
+        .iter()
+        .map(|p| p.percent())
+        .collect::<Vec<_>>();
+    let labels = report
+        .history
+        .iter()
+        .map(|p| {
+            AttrValue::from(format!(
+                "{} ({})",
+                p.set_name,
+                p.finished.format("%d/%m/%Y")
+            ))
+        })
+        .collect::<Vec<_>>();
+    let bars = report
+        .set_accuracy
+        .iter()
+        .map(|a| (AttrValue::from(a.label.clone()), a.percent()))
+        .collect::<Vec<_>>();
+    let missed = report
+        .most_missed
+        .iter()
+        .map(|q| {
+            html! {
+                <li>
+                    <b>{ &q.question.title }</b>{ " " }{ &q.question.markup }
+                    { format!(" ({}) - missed {} of {}", q.set_name, q.misses, q.attempts) }
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <Column {class}>
+            <Row align_items={"center"}>
+                <p>{ "Questions attempted: " }<b>{ report.questions_attempted }</b></p>
+            </Row>
+            <h2>{ "Score over time (%)" }</h2>
+            <LineChart {values} {labels} />
+            <h2>{ "Accuracy per set (%)" }</h2>
+            <BarChart {bars} />
+            if !report.most_missed.is_empty() {
+                <h2>{ "Most missed questions" }</h2>
+                <ol>{ missed }</ol>
+            }
+        </Column>
+    }
+}
@@ -1,14 +1,21 @@
 #![allow(non_camel_case_types)]
 
 use crate::{
-    commands::invoke_get_question_sets,
+    commands::{invoke_find_set_summaries, invoke_get_set_tags},
     components::{
-        inputs::{Button, RadioToggle, ValidatedInput},
+        inputs::{lines_of, Button, RadioToggle, SelectInput, TextArea, ValidatedInput},
         layout::{Column, Row},
+        theme_ctx::use_theme,
     },
 };
+use chrono::Utc;
 use shared::{
-    questions::{Question, QuestionBuilder, QuestionSet},
+    generators::Generator,
+    marking::GradingRule,
+    queries::{Page, Paged, SetQuery},
+    questions::{
+        parse_tags, QuestionBuilder, QuestionPart, SetSummary, MAX_DIFFICULTY, MIN_DIFFICULTY,
+    },
     requests::UserReqError,
 };
 use stylist::yew::styled_component;
@@ -32,6 +39,25 @@ pub fn question_form(
     let answer = use_state_eq(|| AttrValue::from(String::new()));
     let answer_valid = use_state_eq(|| false);
     let calculator = use_state_eq(|| true);
+    let hints = use_state_eq(|| AttrValue::from(String::new()));
+    let solution = use_state_eq(|| AttrValue::from(String::new()));
+    let parts = use_state_eq(|| AttrValue::from(String::new()));
+
+    let mark_options = (1..=6)
+        .map(|m| AttrValue::from(m.to_string()))
+        .collect::<Vec<_>>();
+    let rule_options = GradingRule::all()
+        .into_iter()
+        .map(|r| AttrValue::from(r.to_string()))
+        .collect::<Vec<_>>();
+    let marks = use_state_eq(|| mark_options[0].clone());
+    let grading = use_state_eq(|| rule_options[0].clone());
+    let difficulty_options = (MIN_DIFFICULTY..=MAX_DIFFICULTY)
+        .map(|d| AttrValue::from(d.to_string()))
+        .collect::<Vec<_>>();
+    let difficulty = use_state_eq(|| difficulty_options[0].clone());
+    let tags = use_state_eq(|| AttrValue::from(String::new()));
+    let tags_valid = use_state_eq(|| true);
 
     let mut questions_now = (**questions).clone();
     let mut question = questions_now.get(**page_no);
@@ -62,6 +88,22 @@ pub fn question_form(
             answer_valid.set(false);
             AttrValue::from(String::new())
         });
+
+        hints.set(AttrValue::from(question.hints.join("\n")));
+        solution.set(AttrValue::from(question.solution.join("\n")));
+        parts.set(AttrValue::from(
+            question
+                .parts
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ));
+        marks.set(AttrValue::from(question.marks.to_string()));
+        grading.set(AttrValue::from(question.grading.to_string()));
+        calculator.set(question.calculator_allowed.unwrap_or(true));
+        difficulty.set(AttrValue::from(question.difficulty.to_string()));
+        tags.set(AttrValue::from(question.tags.join(", ")));
     }
 
     if *qtitle_valid {
@@ -83,6 +125,27 @@ pub fn question_form(
     }
 
     question.calculator_allowed = Some(*calculator);
+    question.hints = lines_of(&hints);
+    question.solution = lines_of(&solution);
+    question.marks = marks.parse().unwrap();
+    question.grading = grading.parse().unwrap();
+    question.difficulty = difficulty.parse().unwrap();
+    question.tags = parse_tags(&tags);
+
+    // A part which can't be read makes the whole question invalid, so
+    // that the set can't be published with a part silently missing.
+    let parsed_parts = lines_of(&parts)
+        .iter()
+        .map(|line| line.parse::<QuestionPart>())
+        .collect::<Result<Vec<_>, _>>();
+    let parts_valid = parsed_parts.is_ok();
+    match parsed_parts {
+        Ok(parsed) => question.parts = parsed,
+        Err(_) => {
+            question.parts = vec![];
+            question.answer = None;
+        }
+    }
 
     questions_now.set(**page_no, question);
     questions.set(questions_now);
@@ -96,13 +159,108 @@ pub fn question_form(
             <p>{ "Is this a set for calculator use?" }</p>
             <br />
             <RadioToggle name={"calc"} handle={calculator}>{ "" }</RadioToggle>
+            <br />
+            <Row align_items={"center"}>
+                <SelectInput id={"marks"} options={mark_options} handle={marks}>{ "Marks" }</SelectInput>
+                <SelectInput id={"grading"} options={rule_options} handle={grading}>{ "Grading" }</SelectInput>
+                <SelectInput id={"qdifficulty"} options={difficulty_options} handle={difficulty}>{ "Difficulty" }</SelectInput>
+            </Row>
+            <ValidatedInput id={"qtags"} minl={0} maxl={80} text_handle={tags} validity_handle={tags_valid}>{ "Tags, e.g. Algebra > Quadratics" }</ValidatedInput>
+            <br />
+            <TextArea id={"parts"} text_handle={parts}>{ "Parts, for multi-part questions (one per line: prompt | answer | marks | rule)" }</TextArea>
+            if !parts_valid {
+                <p>{ "Every part needs at least a prompt and an answer!" }</p>
+            }
+            <br />
+            <Row>
+                <TextArea id={"hints"} text_handle={hints}>{ "Hints (one per line, revealed in order)" }</TextArea>
+                <TextArea id={"solution"} text_handle={solution}>{ "Worked solution (one step per line)" }</TextArea>
+            </Row>
         </>
     }
 }
 
+/// A small panel for the Creator which appends procedurally generated
+/// questions onto the end of the set being written.
+#[autoprops]
+#[styled_component(GeneratorForm)]
+pub fn generator_form(
+    page_no: &UseStateHandle<usize>,
+    pg_changed: &UseStateHandle<bool>,
+    questions: &UseStateHandle<QuestionBuilder>,
+) -> Html {
+    let generators = Generator::all()
+        .into_iter()
+        .map(|g| AttrValue::from(g.name()))
+        .collect::<Vec<_>>();
+    let difficulties = (MIN_DIFFICULTY..=MAX_DIFFICULTY)
+        .map(|d| AttrValue::from(d.to_string()))
+        .collect::<Vec<_>>();
+    let counts = [1, 5, 10, 20]
+        .map(|c| AttrValue::from(c.to_string()))
+        .to_vec();
+
+    let generator = use_state_eq(|| generators[0].clone());
+    let difficulty = use_state_eq(|| difficulties[0].clone());
+    let count = use_state_eq(|| counts[1].clone());
+
+    let onclick = {
+        let generator = generator.clone();
+        let difficulty = difficulty.clone();
+        let count = count.clone();
+        let questions = questions.clone();
+        let page_no = page_no.clone();
+        let pg_changed = pg_changed.clone();
+        move |_| {
+            let generator = generator.parse::<Generator>().unwrap();
+            let difficulty = difficulty.parse::<u8>().unwrap();
+            let count = count.parse::<usize>().unwrap();
+            let seed = Utc::now().timestamp_millis() as u64;
+
+            let mut builder = (*questions).clone();
+            builder.append(generator.generate_many(difficulty, count, seed));
+            // Blank pages may have been dropped from under the one being
+            // edited.
+            page_no.set((*page_no).min(builder.len().saturating_sub(1)));
+            questions.set(builder);
+            pg_changed.set(true);
+        }
+    };
+
+    let theme = use_theme();
+    let class = css!(
+        r#"
+            > * {
+                margin-left: calc( 0.125 * ${fs});
+                margin-right: calc( 0.125 * ${fs});
+            }
+        "#,
+        fs = theme.font_size,
+    );
+
+    html! {
+        <Row {class} align_items={"center"} justify_content={"center"}>
+            <SelectInput id={"generator"} options={generators} handle={generator}>{ "Topic" }</SelectInput>
+            <SelectInput id={"gen_difficulty"} options={difficulties} handle={difficulty}>{ "Difficulty" }</SelectInput>
+            <SelectInput id={"count"} options={counts} handle={count}>{ "Count" }</SelectInput>
+            <Button {onclick}>{ "Generate" }</Button>
+        </Row>
+    }
+}
+
 #[hook]
-pub fn use_question_sets_with(
+pub fn use_set_summaries(
+    query: SetQuery,
+    page: Page,
     update: UseStateHandle<bool>,
-) -> Result<UseFutureHandle<Result<Vec<QuestionSet>, UserReqError>>, Suspension> {
-    use_future_with(update, |_| async { invoke_get_question_sets().await })
+) -> Result<UseFutureHandle<Result<Paged<SetSummary>, UserReqError>>, Suspension> {
+    use_future_with((query, page, *update), |deps| {
+        let (query, page, _) = (*deps).clone();
+        async move { invoke_find_set_summaries(query, page).await }
+    })
+}
+
+#[hook]
+pub fn use_set_tags() -> Result<UseFutureHandle<Result<Vec<String>, UserReqError>>, Suspension> {
+    use_future_with((), |_| async { invoke_get_set_tags().await })
 }
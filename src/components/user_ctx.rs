@@ -1,9 +1,9 @@
 #![allow(non_camel_case_types)]
 
-use crate::{app::Route, components::theme_ctx::use_theme};
+use crate::{app::Route, commands::invoke_log_out, components::theme_ctx::use_theme};
 use shared::User;
 use stylist::yew::styled_component;
-use yew::prelude::*;
+use yew::{platform::spawn_local, prelude::*};
 use yew_autoprops::autoprops;
 use yew_router::prelude::*;
 
@@ -73,6 +73,9 @@ pub fn context_bar() -> Html {
 
         move |e: MouseEvent| {
             e.prevent_default();
+            spawn_local(async {
+                let _ = invoke_log_out().await;
+            });
             ctx.dispatch(None);
             nav.push(&Route::Login);
         }